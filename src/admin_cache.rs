@@ -0,0 +1,123 @@
+//! Per-chat admin lists from `getChatAdministrators`, so permission checks don't cost a
+//! `getChatMember` call each.
+//!
+//! Lists expire after [`TTL`], and are dropped early when a `chat_member` update shows
+//! someone gaining or losing admin status, or when `/admincache` asks for a refresh.
+//!
+//! [`member`] reads a single `getChatMember` result for when one member's status or
+//! permissions matter.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::Deserialize;
+use serde_json::json;
+use tgbotrs::{Bot, BotError, ChatMember, ChatMemberUpdated, ChatPermissions, User};
+use crate::state::SharedState;
+
+/// How long a fetched admin list is trusted
+pub const TTL: Duration = Duration::from_secs(10 * 60);
+
+/// One entry of `getChatAdministrators`. tgbotrs reads these as an untagged `ChatMember`,
+/// which takes every administrator for an owner and drops their title, so the raw
+/// response is read into this instead.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Admin {
+    /// `creator` or `administrator`
+    pub status: String,
+    pub user: User,
+    #[serde(default)]
+    pub is_anonymous: bool,
+    pub custom_title: Option<String>,
+    /// Rights, all `false` for the owner, who has every one of them anyway
+    #[serde(default)]
+    pub can_change_info: bool,
+    #[serde(default)]
+    pub can_delete_messages: bool,
+    #[serde(default)]
+    pub can_restrict_members: bool,
+    #[serde(default)]
+    pub can_invite_users: bool,
+    #[serde(default)]
+    pub can_pin_messages: bool,
+    #[serde(default)]
+    pub can_manage_topics: bool,
+    #[serde(default)]
+    pub can_manage_video_chats: bool,
+    #[serde(default)]
+    pub can_post_stories: bool,
+    #[serde(default)]
+    pub can_promote_members: bool,
+}
+
+impl Admin {
+    pub fn is_owner(&self) -> bool {
+        self.status == "creator"
+    }
+}
+
+/// A `getChatMember` result. tgbotrs reads restricted members as plain members and drops
+/// their permissions, so the raw response is read into this instead.
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    /// `creator`, `administrator`, `member`, `restricted`, `left` or `kicked`
+    pub status: String,
+    /// Only filled in for `restricted` members
+    #[serde(flatten)]
+    pub perms: ChatPermissions,
+    /// 0 or missing for restrictions without an end
+    pub until_date: Option<i64>,
+}
+
+/// Looks one member up, bypassing the cache — it only holds admins.
+pub async fn member(bot: &Bot, chat_id: i64, user_id: i64) -> Result<Member, BotError> {
+    bot.call_api("getChatMember", &json!({ "chat_id": chat_id, "user_id": user_id })).await
+}
+
+#[derive(Debug, Default)]
+pub struct AdminCache {
+    /// chat_id → when the list was fetched, and the list
+    chats: HashMap<i64, (Instant, Vec<Admin>)>,
+}
+
+impl AdminCache {
+    /// The chat's admins, unless they were never fetched or are older than [`TTL`] at `now`
+    pub fn get(&self, chat_id: i64, now: Instant) -> Option<&[Admin]> {
+        self.chats.get(&chat_id)
+            .filter(|(fetched, _)| now.duration_since(*fetched) < TTL)
+            .map(|(_, admins)| admins.as_slice())
+    }
+
+    pub fn insert(&mut self, chat_id: i64, admins: Vec<Admin>, now: Instant) {
+        self.chats.insert(chat_id, (now, admins));
+    }
+
+    pub fn invalidate(&mut self, chat_id: i64) {
+        self.chats.remove(&chat_id);
+    }
+}
+
+fn is_admin_status(member: &ChatMember) -> bool {
+    matches!(member, ChatMember::ChatMemberOwner(_) | ChatMember::ChatMemberAdministrator(_))
+}
+
+/// The chat's owner and admins, from the cache while it's fresh.
+pub async fn admins(bot: &Bot, chat_id: i64, state: &SharedState) -> Result<Vec<Admin>, BotError> {
+    if let Some(admins) = state.lock().await.admins.get(chat_id, Instant::now()) {
+        return Ok(admins.to_vec());
+    }
+    refresh(bot, chat_id, state).await
+}
+
+/// Fetches the chat's admins and caches them, whatever the cache held.
+pub async fn refresh(bot: &Bot, chat_id: i64, state: &SharedState) -> Result<Vec<Admin>, BotError> {
+    let admins: Vec<Admin> = bot.call_api("getChatAdministrators", &json!({ "chat_id": chat_id })).await?;
+    state.lock().await.admins.insert(chat_id, admins.clone(), Instant::now());
+    Ok(admins)
+}
+
+/// Drops the chat's list when someone was promoted, demoted, or left as an admin.
+pub async fn on_member_update(update: &ChatMemberUpdated, state: &SharedState) {
+    if is_admin_status(&update.old_chat_member) || is_admin_status(&update.new_chat_member) {
+        state.lock().await.admins.invalidate(update.chat.id);
+    }
+}
@@ -0,0 +1,174 @@
+//! bot-sim — try commands locally without a bot token or a test group.
+//!
+//! Reads one action per line from stdin, turns it into a fake `Update`, runs it through
+//! `handler::handle_update` against the offline mock API and prints every call the bot made.
+//!
+//!   @alice: /start                              private chat with alice
+//!   @alice in group -100: /calc 2^10            message in a group
+//!   @bob in group -100 reply 7: /warn spam      reply to message #7
+//!   @alice in group -100 press 12: help:fun     press a button on message #12
+//!   :admin @bob -100                            make bob an admin of -100
+//!   :quit
+//!
+//! Message ids are printed next to everything the bot sends, so you can reply to them
+//! or press their buttons.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+use multipurpose_bot::{handler, middleware};
+use multipurpose_bot::mock_api::{callback, ApiCall, FakeMessage, FakeUser, MockApi};
+use multipurpose_bot::state::new_state;
+use multipurpose_bot::storage::MemoryStorage;
+
+/// One parsed input line
+enum Action {
+    Send  { user: String, chat: Option<i64>, reply_to: Option<i64>, text: String },
+    Press { user: String, chat: Option<i64>, message_id: i64, data: String },
+    Admin { user: String, chat: i64 },
+    Quit,
+}
+
+fn parse_line(line: &str) -> Result<Action, String> {
+    let line = line.trim();
+    if line == ":quit" || line == ":q" {
+        return Ok(Action::Quit);
+    }
+    if let Some(rest) = line.strip_prefix(":admin") {
+        let mut parts = rest.split_whitespace();
+        let user = parts.next().and_then(|u| u.strip_prefix('@')).ok_or("usage: :admin @user <chat_id>")?;
+        let chat = parts.next().and_then(|c| c.parse().ok()).ok_or("usage: :admin @user <chat_id>")?;
+        return Ok(Action::Admin { user: user.to_string(), chat });
+    }
+
+    let (head, body) = line.split_once(':').ok_or("expected `@user [in group <id>] [reply <id>|press <id>]: <text>`")?;
+    let mut words = head.split_whitespace().peekable();
+    let user = words.next().and_then(|u| u.strip_prefix('@')).ok_or("lines start with @user")?.to_string();
+
+    let (mut chat, mut reply_to, mut press) = (None, None, None);
+    while let Some(word) = words.next() {
+        match word {
+            "in" => {
+                if words.peek() == Some(&"group") { words.next(); }
+                chat = Some(number(words.next(), "in")?);
+            }
+            "reply" => reply_to = Some(number(words.next(), "reply")?),
+            "press" => press    = Some(number(words.next(), "press")?),
+            other   => return Err(format!("unknown word `{other}`")),
+        }
+    }
+
+    let text = body.trim().to_string();
+    Ok(match press {
+        Some(message_id) => Action::Press { user, chat, message_id, data: text },
+        None             => Action::Send  { user, chat, reply_to, text },
+    })
+}
+
+fn number(word: Option<&str>, what: &str) -> Result<i64, String> {
+    word.and_then(|n| n.parse().ok()).ok_or(format!("`{what}` needs a number"))
+}
+
+/// Same name → same id for the whole session
+struct Users(HashMap<String, FakeUser>);
+
+impl Users {
+    fn get(&mut self, name: &str) -> FakeUser {
+        let next_id = 1000 + self.0.len() as i64 + 1;
+        self.0.entry(name.to_string())
+            .or_insert_with(|| {
+                let mut first = name.to_string();
+                if let Some(c) = first.get_mut(0..1) { c.make_ascii_uppercase(); }
+                FakeUser::new(next_id, &first).username(name)
+            })
+            .clone()
+    }
+}
+
+fn print_call(call: &ApiCall) {
+    let id = call.result["message_id"].as_i64()
+        .or_else(|| call.params["message_id"].as_i64())
+        .map(|id| format!(" #{id}"))
+        .unwrap_or_default();
+    match call.params["text"].as_str() {
+        Some(text) => {
+            println!("🤖 {}{id}:", call.method);
+            for line in text.lines() {
+                println!("   │ {line}");
+            }
+        }
+        None => println!("🤖 {}{id} {}", call.method, call.params),
+    }
+    let rows = call.params["reply_markup"]["inline_keyboard"].as_array().cloned().unwrap_or_default();
+    for row in rows {
+        let buttons: Vec<String> = row.as_array().into_iter().flatten()
+            .map(|b| match (b["callback_data"].as_str(), b["url"].as_str()) {
+                (Some(data), _) => format!("[{} → {data}]", b["text"].as_str().unwrap_or("")),
+                (_, Some(url))  => format!("[{} ↗ {url}]",  b["text"].as_str().unwrap_or("")),
+                _               => format!("[{}]", b["text"].as_str().unwrap_or("")),
+            })
+            .collect();
+        println!("   └ {}", buttons.join(" "));
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let api   = MockApi::start().await;
+    let bot   = api.bot().await;
+    let state = new_state(Box::new(MemoryStorage::default()));
+    state.lock().await.middleware = Arc::new(middleware::from_env());
+    let mut users    = Users(HashMap::new());
+    let mut messages: HashMap<i64, FakeMessage> = HashMap::new();
+    api.take_calls();
+
+    println!("🧪  bot-sim — type `@alice in group -100: /help`, `:admin @alice -100` or `:quit`\n");
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 { break; }
+        if line.trim().is_empty() || line.trim_start().starts_with('#') { continue; }
+
+        let update = match parse_line(&line) {
+            Err(e)           => { eprintln!("⚠️   {e}"); continue; }
+            Ok(Action::Quit) => break,
+            Ok(Action::Admin { user, chat }) => {
+                let user = users.get(&user);
+                api.set_admin(chat, user.id);
+                // Otherwise a cached admin list from earlier in the session would hide it
+                state.lock().await.admins.invalidate(chat);
+                println!("👮 {} is now an admin of {chat}", user.first_name);
+                continue;
+            }
+            Ok(Action::Send { user, chat, reply_to, text }) => {
+                let user = users.get(&user);
+                let mut msg = FakeMessage::new(chat.unwrap_or(user.id), &user, &text);
+                if let Some(id) = reply_to {
+                    match messages.get(&id) {
+                        Some(target) => msg = msg.reply_to(target),
+                        None => { eprintln!("⚠️   No message #{id} in this session"); continue; }
+                    }
+                }
+                println!("💬 #{}", msg.id());
+                messages.insert(msg.id(), msg.clone());
+                msg.into_update()
+            }
+            Ok(Action::Press { user, chat, message_id, data }) => {
+                let user = users.get(&user);
+                callback(chat.unwrap_or(user.id), &user, message_id, &data)
+            }
+        };
+
+        handler::handle_update(bot.clone(), update, Arc::clone(&state)).await;
+
+        for call in api.take_calls() {
+            print_call(&call);
+            if let Some(id) = call.result["message_id"].as_i64() {
+                messages.insert(id, FakeMessage::from_json(call.result));
+            }
+        }
+    }
+}
@@ -1,106 +1,295 @@
-use tgbotrs::{Bot, ChatMember, ChatPermissions, Message};
+use tgbotrs::{Bot, ChatMember, ChatPermissions, InputFile, Message, User};
 use tgbotrs::gen_methods::{
-    PinChatMessageParams, PromoteChatMemberParams, SendMessageParams, UnbanChatMemberParams,
+    PinChatMessageParams, SendDocumentParams, SendMessageParams,
+    UnbanChatMemberParams,
 };
-use crate::state::SharedState;
+use crate::admin_cache;
+use crate::cmd::settings;
+use crate::duration;
+use crate::modlog::{self, Entry};
+use crate::punish::{temp_secs, Punishment};
+use crate::registry::escape;
+use crate::state::{Action, AuditEntry, ChatSettings, SharedState, Warning};
+use crate::storage::Storage;
 
 // ─── Guard helpers ────────────────────────────────────────────────────────────
 
-async fn is_admin(bot: &Bot, chat_id: i64, user_id: i64) -> bool {
-    matches!(
-        bot.get_chat_member(chat_id, user_id).await,
-        Ok(ChatMember::ChatMemberOwner(_)) | Ok(ChatMember::ChatMemberAdministrator(_))
-    )
+/// Whether the user owns or administers the chat, going by the cached admin list. Chats
+/// without one, like private chats, are asked about the member directly.
+pub(crate) async fn is_admin(bot: &Bot, chat_id: i64, user_id: i64, state: &SharedState) -> bool {
+    match admin_cache::admins(bot, chat_id, state).await {
+        Ok(admins) => admins.iter().any(|a| a.user.id == user_id),
+        Err(_) => matches!(
+            bot.get_chat_member(chat_id, user_id).await,
+            Ok(ChatMember::ChatMemberOwner(_)) | Ok(ChatMember::ChatMemberAdministrator(_))
+        ),
+    }
 }
 
-/// Returns (chat_id, sender_id, target_user) — sends an error if preconditions fail.
-async fn require_reply_target(
-    bot: &Bot,
-    msg: &Message,
-) -> Option<(i64, i64, Box<tgbotrs::User>)> {
-    let sender_id = msg.from.as_ref()?.id;
-    let chat_id   = msg.chat.id;
-    let reply = match msg.reply_to_message.as_deref() {
-        Some(r) => r,
-        None => {
-            let _ = bot.send_message(
-                chat_id,
-                "❌ Reply to a user's message to use this command.",
-                None,
-            ).await;
-            return None;
+/// Whether the user created the chat, going by the cached admin list.
+pub(crate) async fn is_owner(bot: &Bot, chat_id: i64, user_id: i64, state: &SharedState) -> bool {
+    admin_cache::admins(bot, chat_id, state).await
+        .is_ok_and(|admins| admins.iter().any(|a| a.is_owner() && a.user.id == user_id))
+}
+
+/// Whether automatic enforcement — antiflood, locks, the blocklist — leaves the user alone:
+/// admins and anyone `/approve`d. Manual admin commands only spare admins ([`is_admin`]).
+pub(crate) async fn is_exempt(bot: &Bot, chat_id: i64, user_id: i64, state: &SharedState) -> bool {
+    state.lock().await.store.is_approved(chat_id, user_id) || is_admin(bot, chat_id, user_id, state).await
+}
+
+/// Who a moderation command acts on, and the arguments left once they're taken off.
+pub(crate) struct Target {
+    pub chat_id: i64,
+    pub user:    Box<User>,
+    /// Duration and/or reason, whatever followed the target
+    pub rest:    String,
+}
+
+/// `"1h spamming links"` → `("1h", "spamming links")`
+fn first_word(args: &str) -> (&str, &str) {
+    let args = args.trim();
+    match args.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (args, ""),
+    }
+}
+
+/// Byte index of a UTF-16 offset, which is how message entities are measured.
+fn utf16_to_byte(text: &str, offset: i64) -> Option<usize> {
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units == offset { return Some(i); }
+        units += c.len_utf16() as i64;
+    }
+    (units == offset).then_some(text.len())
+}
+
+/// A text mention (a user without a username, picked from the mention list) right after
+/// the command, with the text that follows it.
+fn text_mention(msg: &Message) -> Option<(User, String)> {
+    let text = msg.text.as_deref()?;
+    msg.entities.iter().flatten()
+        .filter(|e| e.r#type == "text_mention")
+        .find_map(|e| {
+            let start = utf16_to_byte(text, e.offset)?;
+            let end   = utf16_to_byte(text, e.offset + e.length)?;
+            if text[..start].split_whitespace().count() != 1 { return None; }
+            Some((*e.user.clone()?, text[end..].trim().to_string()))
+        })
+}
+
+pub(crate) fn member_user(member: ChatMember) -> User {
+    match member {
+        ChatMember::ChatMemberOwner(m)         => m.user,
+        ChatMember::ChatMemberAdministrator(m) => m.user,
+        ChatMember::ChatMemberMember(m)        => m.user,
+        ChatMember::ChatMemberRestricted(m)    => m.user,
+        ChatMember::ChatMemberLeft(m)          => m.user,
+        ChatMember::ChatMemberBanned(m)        => m.user,
+    }
+}
+
+/// A target given by ID or `@username`, not looked up yet
+struct Unresolved {
+    user_id: i64,
+    /// `@username` if that's how it was given
+    username: Option<String>,
+    rest: String,
+}
+
+/// Finds the target: the sender of the replied-to message, a text mention, or a numeric
+/// user ID / `@username` as the first argument. Sends an error if there's none.
+pub(crate) async fn require_target(bot: &Bot, msg: &Message, args: &str, state: &SharedState) -> Option<Target> {
+    let chat_id = msg.chat.id;
+    let Unresolved { user_id, rest, .. } = match find_target(bot, msg, args, state).await? {
+        Ok(target) => return Some(target),
+        Err(unresolved) => unresolved,
+    };
+    match bot.get_chat_member(chat_id, user_id).await {
+        Ok(member) => Some(Target { chat_id, user: Box::new(member_user(member)), rest }),
+        Err(_) => {
+            let _ = bot.send_message(chat_id, format!("❌ I couldn't find user {user_id} in this chat."), None).await;
+            None
         }
+    }
+}
+
+/// Like [`require_target`], but the user needn't be in this chat — for `/fban`, which is
+/// meant to catch people before they join. Someone who can't be looked up here goes by
+/// their `@username` or ID.
+pub(crate) async fn require_user(bot: &Bot, msg: &Message, args: &str, state: &SharedState) -> Option<Target> {
+    let chat_id = msg.chat.id;
+    let Unresolved { user_id, username, rest } = match find_target(bot, msg, args, state).await? {
+        Ok(target) => return Some(target),
+        Err(unresolved) => unresolved,
     };
-    let target = match reply.from.as_deref() {
-        Some(u) => Box::new(u.clone()),
-        None => {
-            let _ = bot.send_message(chat_id, "❌ Could not identify the target user.", None).await;
-            return None;
+    let user = match bot.get_chat_member(chat_id, user_id).await {
+        Ok(member) => member_user(member),
+        Err(_) => {
+            let name = username.map_or_else(|| user_id.to_string(), |u| format!("@{u}"));
+            serde_json::from_value(serde_json::json!({ "id": user_id, "is_bot": false, "first_name": name }))
+                .expect("a bare User always deserializes")
         }
     };
-    Some((chat_id, sender_id, target))
+    Some(Target { chat_id, user: Box::new(user), rest })
 }
 
-/// Same as above but also checks caller is admin and target is not.
-async fn require_admin(bot: &Bot, msg: &Message) -> Option<(i64, i64, Box<tgbotrs::User>)> {
-    let (chat_id, sender_id, target) = require_reply_target(bot, msg).await?;
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to use this command.", None).await;
-        return None;
+/// The target when the message itself names it, or the ID still to be looked up.
+async fn find_target(bot: &Bot, msg: &Message, args: &str, state: &SharedState) -> Option<Result<Target, Unresolved>> {
+    let chat_id = msg.chat.id;
+    let fail = |text: String| async move {
+        let _ = bot.send_message(chat_id, text, None).await;
+        None
+    };
+
+    if let Some(reply) = msg.reply_to_message.as_deref() {
+        let Some(user) = reply.from.clone() else {
+            return fail("❌ Could not identify the target user.".into()).await;
+        };
+        return Some(Ok(Target { chat_id, user, rest: args.trim().to_string() }));
+    }
+    if let Some((user, rest)) = text_mention(msg) {
+        return Some(Ok(Target { chat_id, user: Box::new(user), rest }));
     }
-    if is_admin(bot, chat_id, target.id).await {
-        let _ = bot.send_message(chat_id, "❌ You can't use admin commands on another admin.", None).await;
+
+    let (word, rest) = first_word(args);
+    let rest = rest.to_string();
+    if let Some(username) = word.strip_prefix('@') {
+        let known = state.lock().await.store.user_id_by_username(username);
+        match known {
+            Some(user_id) => Some(Err(Unresolved { user_id, username: Some(username.to_string()), rest })),
+            None => fail(format!(
+                "❌ I haven't seen @{username} yet — reply to one of their messages or use their user ID."
+            )).await,
+        }
+    } else if let Ok(user_id) = word.parse::<i64>() {
+        Some(Err(Unresolved { user_id, username: None, rest }))
+    } else {
+        fail("❌ Reply to a user's message, or give their @username or user ID.".into()).await
+    }
+}
+
+/// Same as above but also checks the target is not an admin.
+/// The caller's own admin rights are checked by the registry before the command runs.
+pub(crate) async fn require_admin(bot: &Bot, msg: &Message, args: &str, state: &SharedState) -> Option<Target> {
+    let target = require_target(bot, msg, args, state).await?;
+    if is_admin(bot, target.chat_id, target.user.id, state).await {
+        let _ = bot.send_message(target.chat_id, "❌ You can't use admin commands on another admin.", None).await;
         return None;
     }
-    Some((chat_id, sender_id, target))
+    Some(target)
 }
 
 // ─── Commands ─────────────────────────────────────────────────────────────────
 
-pub async fn cmd_ban(bot: &Bot, msg: &Message) {
-    let Some((chat_id, _, target)) = require_admin(bot, msg).await else { return };
+/// Splits a leading duration off the arguments — optional for `/ban` and `/mute`, required
+/// for `/tban` and `/tmute` — and returns it with the rest as the reason.
+/// `None` means it was invalid and the user has been told.
+async fn duration_arg<'a>(bot: &Bot, chat_id: i64, args: &'a str, required: bool) -> Option<(Option<u64>, &'a str)> {
+    let (word, rest) = first_word(args);
+    if let Some(secs) = temp_secs(word) {
+        return Some((Some(secs), rest));
+    }
+    let out_of_range = duration::parse(word).is_some();
+    if !required && !out_of_range {
+        return Some((None, args.trim()));
+    }
+    let text = if out_of_range {
+        "❌ Durations must be between 30 seconds and 366 days."
+    } else {
+        "❌ Give a duration like 30m, 2h, 7d or 1d12h."
+    };
+    let _ = bot.send_message(chat_id, text, None).await;
+    None
+}
+
+/// "\n⏰ Until …" line for timed punishments, empty otherwise
+fn until_line(action: Punishment) -> String {
+    action.until_date()
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| format!("\n⏰ Until {}", t.format("%Y-%m-%d %H:%M UTC")))
+        .unwrap_or_default()
+}
+
+/// "\nReason: …" when one was given, empty otherwise
+fn reason_line(reason: &str) -> String {
+    if reason.is_empty() { String::new() } else { format!("\nReason: {reason}") }
+}
+
+async fn ban(bot: &Bot, msg: &Message, args: &str, state: &SharedState, timed: bool) {
+    let Some(Target { chat_id, user: target, rest, .. }) = require_admin(bot, msg, args, state).await else { return };
+    let Some((secs, reason)) = duration_arg(bot, chat_id, &rest, timed).await else { return };
+    let action = secs.map_or(Punishment::Ban, Punishment::TempBan);
     let name = &target.first_name;
-    match bot.ban_chat_member(chat_id, target.id, None).await {
-        Ok(_)  => { let _ = bot.send_message(chat_id, format!("🔨 *{name}* has been banned."), Some(SendMessageParams::new().parse_mode("Markdown"))).await; }
+    match action.apply(bot, chat_id, target.id).await {
+        Ok(_)  => {
+            let _ = bot.send_message(chat_id, format!("🔨 *{name}* has been {}.{}{}", action.label(), until_line(action), reason_line(reason)), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+            Entry::new(Action::Ban).target(&target).reason(reason).duration(secs).record(bot, state, msg).await;
+        }
         Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to ban: {e}"), None).await; }
     }
 }
 
-pub async fn cmd_kick(bot: &Bot, msg: &Message) {
-    let Some((chat_id, _, target)) = require_admin(bot, msg).await else { return };
+pub async fn cmd_ban(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    ban(bot, msg, args, state, false).await;
+}
+
+pub async fn cmd_tban(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    ban(bot, msg, args, state, true).await;
+}
+
+pub async fn cmd_kick(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, rest, .. }) = require_admin(bot, msg, args, state).await else { return };
     let name = target.first_name.clone();
     if bot.ban_chat_member(chat_id, target.id, None).await.is_ok() {
         let _ = bot.unban_chat_member(
             chat_id, target.id,
             Some(UnbanChatMemberParams::new().only_if_banned(true)),
         ).await;
-        let _ = bot.send_message(chat_id, format!("👢 *{name}* has been kicked."), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+        let _ = bot.send_message(chat_id, format!("👢 *{name}* has been kicked.{}", reason_line(&rest)), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+        Entry::new(Action::Kick).target(&target).reason(&rest).record(bot, state, msg).await;
     } else {
         let _ = bot.send_message(chat_id, "❌ Failed to kick user.", None).await;
     }
 }
 
-pub async fn cmd_mute(bot: &Bot, msg: &Message) {
-    let Some((chat_id, _, target)) = require_admin(bot, msg).await else { return };
-    let no_perms = all_perms(false);
+async fn mute(bot: &Bot, msg: &Message, args: &str, state: &SharedState, timed: bool) {
+    let Some(Target { chat_id, user: target, rest, .. }) = require_admin(bot, msg, args, state).await else { return };
+    let Some((secs, reason)) = duration_arg(bot, chat_id, &rest, timed).await else { return };
+    let action = secs.map_or(Punishment::Mute, Punishment::TempMute);
     let name = &target.first_name;
-    match bot.restrict_chat_member(chat_id, target.id, no_perms, None).await {
-        Ok(_)  => { let _ = bot.send_message(chat_id, format!("🔇 *{name}* has been muted."), Some(SendMessageParams::new().parse_mode("Markdown"))).await; }
+    match action.apply(bot, chat_id, target.id).await {
+        Ok(_)  => {
+            let _ = bot.send_message(chat_id, format!("🔇 *{name}* has been {}.{}{}", action.label(), until_line(action), reason_line(reason)), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+            Entry::new(Action::Mute).target(&target).reason(reason).duration(secs).record(bot, state, msg).await;
+        }
         Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to mute: {e}"), None).await; }
     }
 }
 
-pub async fn cmd_unmute(bot: &Bot, msg: &Message) {
-    let Some((chat_id, _, target)) = require_admin(bot, msg).await else { return };
+pub async fn cmd_mute(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    mute(bot, msg, args, state, false).await;
+}
+
+pub async fn cmd_tmute(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    mute(bot, msg, args, state, true).await;
+}
+
+pub async fn cmd_unmute(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, .. }) = require_admin(bot, msg, args, state).await else { return };
     let full_perms = all_perms(true);
     let name = &target.first_name;
     match bot.restrict_chat_member(chat_id, target.id, full_perms, None).await {
-        Ok(_)  => { let _ = bot.send_message(chat_id, format!("🔊 *{name}* has been unmuted."), Some(SendMessageParams::new().parse_mode("Markdown"))).await; }
+        Ok(_)  => {
+            let _ = bot.send_message(chat_id, format!("🔊 *{name}* has been unmuted."), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+            Entry::new(Action::Unmute).target(&target).record(bot, state, msg).await;
+        }
         Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to unmute: {e}"), None).await; }
     }
 }
 
-fn all_perms(allow: bool) -> ChatPermissions {
+pub(crate) fn all_perms(allow: bool) -> ChatPermissions {
     ChatPermissions {
         can_send_messages:      Some(allow),
         can_send_audios:        Some(allow),
@@ -112,6 +301,7 @@ fn all_perms(allow: bool) -> ChatPermissions {
         can_send_polls:         Some(allow),
         can_send_other_messages:Some(allow),
         can_add_web_page_previews:Some(allow),
+        can_edit_tag:           None,
         can_change_info:        None,
         can_invite_users:       None,
         can_pin_messages:       None,
@@ -119,162 +309,310 @@ fn all_perms(allow: bool) -> ChatPermissions {
     }
 }
 
-pub async fn cmd_warn(bot: &Bot, msg: &Message, reason: &str, state: &SharedState) {
-    let Some((chat_id, _, target)) = require_admin(bot, msg).await else { return };
+/// Forgets warnings older than the chat's expiry, if it has one.
+fn expire_warnings(store: &mut dyn Storage, chat_id: i64, settings: &ChatSettings) {
+    if settings.warn_expiry_days > 0 {
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(settings.warn_expiry_days) * 86_400;
+        store.expire_warnings(chat_id, cutoff);
+    }
+}
+
+pub async fn cmd_warn(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { user: target, rest, .. }) = require_admin(bot, msg, args, state).await else { return };
+    let Some(issuer) = msg.from.as_deref() else { return };
+    warn(bot, msg, issuer, &target, &rest, state).await;
+}
+
+/// Gives `target` a warning from `issuer`, taking the chat's warn action once they reach
+/// the limit. `msg` is what the warning is about: the admin's command, or the offending
+/// message when the bot warns on its own.
+pub(crate) async fn warn(bot: &Bot, msg: &Message, issuer: &User, target: &User, reason: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
     let name = target.first_name.clone();
-    let key  = (chat_id, target.id);
-    let reason_text = if reason.trim().is_empty() { "No reason given" } else { reason.trim() };
+    let reason_text = if reason.is_empty() { "No reason given" } else { reason };
+    let warning = Warning {
+        reason:         reason_text.to_string(),
+        issued_by:      issuer.id,
+        issued_by_name: issuer.first_name.clone(),
+        issued_at:      chrono::Utc::now().timestamp(),
+    };
 
-    let (count, max) = {
+    let (count, settings) = {
         let mut st = state.lock().await;
-        let warns  = st.warnings.entry(key).or_default();
-        warns.push(reason_text.to_string());
-        (warns.len(), 3usize)
+        let settings = st.store.chat_settings(chat_id);
+        expire_warnings(st.store.as_mut(), chat_id, &settings);
+        (st.store.add_warning(chat_id, target.id, &warning), settings)
     };
+    let max = settings.warn_limit as usize;
 
-    if count >= max {
-        let _ = bot.ban_chat_member(chat_id, target.id, None).await;
-        state.lock().await.warnings.remove(&key);
-        let _ = bot.send_message(
-            chat_id,
-            format!("🚫 *{name}* reached {max}/{max} warnings and has been *banned*.\nReason: {reason_text}"),
-            Some(SendMessageParams::new().parse_mode("Markdown")),
-        ).await;
-    } else {
+    if count < max {
         let _ = bot.send_message(
             chat_id,
             format!("⚠️ *{name}* warned! ({count}/{max})\nReason: {reason_text}"),
             Some(SendMessageParams::new().parse_mode("Markdown")),
         ).await;
+        Entry::new(Action::Warn).by(issuer).target(target).reason(reason).record(bot, state, msg).await;
+        return;
     }
-}
 
-pub async fn cmd_warns(bot: &Bot, msg: &Message, state: &SharedState) {
-    let Some((chat_id, _, target)) = require_reply_target(bot, msg).await else { return };
-    let name = target.first_name.clone();
-    let key  = (chat_id, target.id);
-    let st   = state.lock().await;
-    match st.warnings.get(&key) {
-        None => {
-            let _ = bot.send_message(chat_id, format!("✅ *{name}* has no warnings."), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
-        }
-        Some(w) if w.is_empty() => {
-            let _ = bot.send_message(chat_id, format!("✅ *{name}* has no warnings."), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
-        }
-        Some(w) => {
-            let list = w.iter().enumerate().map(|(i, r)| format!("{}. {r}", i + 1)).collect::<Vec<_>>().join("\n");
+    let action = settings.warn_action;
+    match action.apply(bot, chat_id, target.id).await {
+        Ok(()) => {
+            state.lock().await.store.clear_warnings(chat_id, target.id);
             let _ = bot.send_message(
                 chat_id,
-                format!("⚠️ *{name}* has {}/{} warnings:\n{list}", w.len(), 3),
+                format!(
+                    "🚫 *{name}* reached {max}/{max} warnings and has been *{}*.{}\nReason: {reason_text}",
+                    action.label(), until_line(action),
+                ),
                 Some(SendMessageParams::new().parse_mode("Markdown")),
             ).await;
+            let reason = format!("Reached {max}/{max} warnings — last: {reason_text}");
+            Entry::new(action.into()).by(issuer).target(target).reason(&reason).duration(action.secs()).record(bot, state, msg).await;
+        }
+        Err(e) => {
+            let _ = bot.send_message(
+                chat_id,
+                format!("❌ {name} reached {max}/{max} warnings, but I couldn't act on it: {e}"),
+                None,
+            ).await;
         }
     }
 }
 
-pub async fn cmd_clearwarns(bot: &Bot, msg: &Message, state: &SharedState) {
-    let Some((chat_id, _, target)) = require_admin(bot, msg).await else { return };
+pub async fn cmd_warns(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, .. }) = require_target(bot, msg, args, state).await else { return };
+    let name = target.first_name.clone();
+    let (warns, settings) = {
+        let mut st = state.lock().await;
+        let settings = st.store.chat_settings(chat_id);
+        expire_warnings(st.store.as_mut(), chat_id, &settings);
+        (st.store.warnings(chat_id, target.id), settings)
+    };
+    if warns.is_empty() {
+        let _ = bot.send_message(chat_id, format!("✅ *{name}* has no warnings."), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+        return;
+    }
+
+    let list = warns.iter().enumerate().map(|(i, w)| {
+        let by = if w.issued_by_name.is_empty() { "unknown" } else { w.issued_by_name.as_str() };
+        let at = chrono::DateTime::from_timestamp(w.issued_at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        format!("{}. {} — by {by}, {at}", i + 1, w.reason)
+    }).collect::<Vec<_>>().join("\n");
+    let max = settings.warn_limit;
+    let mut policy = format!("At {max} the user is {}.", settings.warn_action.label());
+    if settings.warn_expiry_days > 0 {
+        policy.push_str(&format!(" Warnings expire after {} day(s).", settings.warn_expiry_days));
+    }
+    let _ = bot.send_message(
+        chat_id,
+        format!("⚠️ *{name}* has {}/{max} warnings:\n{list}\n\n_{policy}_", warns.len()),
+        Some(SendMessageParams::new().parse_mode("Markdown")),
+    ).await;
+}
+
+pub async fn cmd_clearwarns(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, .. }) = require_admin(bot, msg, args, state).await else { return };
     let name = &target.first_name;
-    state.lock().await.warnings.remove(&(chat_id, target.id));
+    state.lock().await.store.clear_warnings(chat_id, target.id);
     let _ = bot.send_message(
         chat_id,
         format!("✅ Warnings cleared for *{name}*."),
         Some(SendMessageParams::new().parse_mode("Markdown")),
     ).await;
+    Entry::new(Action::ClearWarns).target(&target).record(bot, state, msg).await;
 }
 
-pub async fn cmd_pin(bot: &Bot, msg: &Message) {
-    let sender_id = match msg.from.as_ref() { Some(u) => u.id, None => return };
-    let chat_id   = msg.chat.id;
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to pin messages.", None).await;
-        return;
-    }
+pub async fn cmd_warnlimit(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let range   = ChatSettings::WARN_LIMIT_RANGE;
+    let reply = match args.trim().parse::<u32>() {
+        Ok(n) if range.contains(&n) => {
+            settings::update(state, chat_id, |s| s.warn_limit = n).await;
+            format!("✅ Warn limit set to {n}.")
+        }
+        _ => format!(
+            "Usage: /warnlimit <{}–{}>\nCurrently: {}",
+            range.start(), range.end(),
+            state.lock().await.store.chat_settings(chat_id).warn_limit,
+        ),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+pub async fn cmd_warnmode(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let mut words = args.split_whitespace();
+    let reply = match words.next().and_then(|mode| Punishment::parse(mode, words.next())) {
+        Some(action) => {
+            settings::update(state, chat_id, |s| s.warn_action = action).await;
+            format!("✅ Users who reach the warn limit will be {}.", action.label())
+        }
+        None => format!(
+            "Usage: /warnmode ban | kick | mute | tban <duration> | tmute <duration>\n\
+             Durations look like 30m, 2h or 1d12h.\nCurrently: {}",
+            state.lock().await.store.chat_settings(chat_id).warn_action.label(),
+        ),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+pub async fn cmd_warnexpiry(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let arg     = args.trim().to_lowercase();
+    let days = match arg.as_str() {
+        "off" | "never" => Some(0),
+        n => n.trim_end_matches('d').parse::<u32>().ok().filter(|&d| d <= 3650),
+    };
+    let reply = match days {
+        Some(0) => {
+            settings::update(state, chat_id, |s| s.warn_expiry_days = 0).await;
+            "✅ Warnings no longer expire.".to_string()
+        }
+        Some(d) => {
+            settings::update(state, chat_id, |s| s.warn_expiry_days = d).await;
+            format!("✅ Warnings now expire after {d} day(s).")
+        }
+        None => {
+            let current = state.lock().await.store.chat_settings(chat_id).warn_expiry_days;
+            let current = if current == 0 { "never".to_string() } else { format!("{current} day(s)") };
+            format!("Usage: /warnexpiry <days|off>\nCurrently: {current}")
+        }
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+pub async fn cmd_pin(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
     let reply_id = match msg.reply_to_message.as_ref() {
         Some(r) => r.message_id,
         None => { let _ = bot.send_message(chat_id, "❌ Reply to the message you want to pin.", None).await; return; }
     };
     match bot.pin_chat_message(chat_id, reply_id, Some(PinChatMessageParams::new().disable_notification(false))).await {
-        Ok(_)  => { let _ = bot.send_message(chat_id, "📌 Message pinned!", None).await; }
+        Ok(_)  => {
+            let _ = bot.send_message(chat_id, "📌 Message pinned!", None).await;
+            Entry::new(Action::Pin).record(bot, state, msg).await;
+        }
         Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to pin: {e}"), None).await; }
     }
 }
 
 pub async fn cmd_unpin(bot: &Bot, msg: &Message) {
-    let sender_id = match msg.from.as_ref() { Some(u) => u.id, None => return };
-    let chat_id   = msg.chat.id;
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to unpin messages.", None).await;
-        return;
-    }
+    let chat_id = msg.chat.id;
     match bot.unpin_chat_message(chat_id, None).await {
         Ok(_)  => { let _ = bot.send_message(chat_id, "📌 Message unpinned!", None).await; }
         Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to unpin: {e}"), None).await; }
     }
 }
 
-pub async fn cmd_del(bot: &Bot, msg: &Message) {
-    let sender_id = match msg.from.as_ref() { Some(u) => u.id, None => return };
-    let chat_id   = msg.chat.id;
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to delete messages.", None).await;
-        return;
-    }
-    let reply_id = match msg.reply_to_message.as_ref() {
-        Some(r) => r.message_id,
+pub async fn cmd_del(bot: &Bot, msg: &Message, reason: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let reply = match msg.reply_to_message.as_deref() {
+        Some(r) => r,
         None => { let _ = bot.send_message(chat_id, "❌ Reply to the message you want to delete.", None).await; return; }
     };
     let _ = bot.delete_message(chat_id, msg.message_id).await;
-    let _ = bot.delete_message(chat_id, reply_id).await;
+    if bot.delete_message(chat_id, reply.message_id).await.is_ok() {
+        let mut entry = Entry::new(Action::Delete).reason(reason);
+        if let Some(author) = reply.from.as_deref() {
+            entry = entry.target(author);
+        }
+        entry.record(bot, state, msg).await;
+    }
 }
 
-pub async fn cmd_promote(bot: &Bot, msg: &Message) {
-    let Some((chat_id, sender_id, target)) = require_reply_target(bot, msg).await else { return };
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to promote users.", None).await;
+/// Bots can't delete messages older than this
+const DELETE_WINDOW_SECS: i64 = 48 * 3600;
+/// Most message IDs `deleteMessages` takes at once
+const DELETE_BATCH: usize = 100;
+/// Most messages one `/purge` goes through
+const MAX_PURGE: i64 = 1000;
+/// Most one-by-one `deleteMessage` calls one `/purge` falls back to
+const MAX_SINGLE_DELETES: usize = 200;
+
+/// `/purge` deletes from the replied-to message up to the command; `/purge N` the N messages
+/// before the command, or N messages from the replied-to one. `silent` is `/spurge`.
+async fn purge(bot: &Bot, msg: &Message, args: &str, state: &SharedState, silent: bool) {
+    let chat_id = msg.chat.id;
+    let reply   = msg.reply_to_message.as_deref();
+    let count   = args.trim().parse::<i64>().ok().filter(|n| *n > 0);
+    let (first, last) = match (reply, count) {
+        (Some(r), None)    => (r.message_id, msg.message_id - 1),
+        (Some(r), Some(n)) => (r.message_id, (r.message_id + n - 1).min(msg.message_id - 1)),
+        (None, Some(n))    => (msg.message_id - n, msg.message_id - 1),
+        (None, None) => {
+            let _ = bot.send_message(chat_id, "❌ Reply to the first message to delete, or give a count: /purge 20", None).await;
+            return;
+        }
+    };
+    if last - first >= MAX_PURGE {
+        let _ = bot.send_message(chat_id, format!("❌ I can purge at most {MAX_PURGE} messages at once."), None).await;
         return;
     }
-    let name   = &target.first_name;
-    let params = PromoteChatMemberParams::new()
-        .can_manage_chat(true)
-        .can_delete_messages(true)
-        .can_restrict_members(true)
-        .can_invite_users(true)
-        .can_pin_messages(true)
-        .can_manage_video_chats(true);
-    match bot.promote_chat_member(chat_id, target.id, Some(params)).await {
-        Ok(_)  => { let _ = bot.send_message(chat_id, format!("⬆️ *{name}* promoted to admin."), Some(SendMessageParams::new().parse_mode("Markdown"))).await; }
-        Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to promote: {e}"), None).await; }
+
+    let _ = bot.delete_message(chat_id, msg.message_id).await;
+    // Newest first, so the part of the range past the 48-hour limit comes last
+    let mut ids: Vec<i64> = (first.max(1)..=last).rev().collect();
+    let cutoff = chrono::Utc::now().timestamp() - DELETE_WINDOW_SECS;
+    let reply_too_old = reply.is_some_and(|r| r.date < cutoff);
+    if let Some(r) = reply.filter(|_| reply_too_old) {
+        ids.retain(|&id| id != r.message_id);
     }
-}
+    // A reply newer than the limit dates the whole range; a bare count could reach anywhere
+    let may_be_too_old = reply.is_none_or(|_| reply_too_old);
 
-pub async fn cmd_demote(bot: &Bot, msg: &Message) {
-    let Some((chat_id, sender_id, target)) = require_reply_target(bot, msg).await else { return };
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to demote users.", None).await;
-        return;
+    // Telegram skips IDs in a batch that are already gone, so those are only "up to"
+    let mut swept = 0;
+    let mut confirmed = 0;
+    let mut failed = 0;
+    let mut singles_left = MAX_SINGLE_DELETES;
+    let mut past_cutoff = false;
+    for batch in ids.chunks(DELETE_BATCH) {
+        if bot.delete_messages(chat_id, batch.to_vec()).await.is_ok() {
+            swept += batch.len();
+            continue;
+        }
+        // Something in the batch can't go — delete one by one and skip those, unless an
+        // earlier batch found nothing deletable past the limit and this one is older still
+        let tries = if past_cutoff { 0 } else { batch.len().min(singles_left) };
+        singles_left -= tries;
+        let mut ok = 0;
+        for &id in &batch[..tries] {
+            if bot.delete_message(chat_id, id).await.is_ok() {
+                ok += 1;
+            }
+        }
+        confirmed += ok;
+        failed += batch.len() - ok;
+        past_cutoff |= ok == 0 && may_be_too_old;
     }
-    let name   = &target.first_name;
-    let params = PromoteChatMemberParams::new()
-        .can_manage_chat(false)
-        .can_delete_messages(false)
-        .can_restrict_members(false)
-        .can_invite_users(false)
-        .can_pin_messages(false)
-        .can_manage_video_chats(false);
-    match bot.promote_chat_member(chat_id, target.id, Some(params)).await {
-        Ok(_)  => { let _ = bot.send_message(chat_id, format!("⬇️ *{name}* demoted."), Some(SendMessageParams::new().parse_mode("Markdown"))).await; }
-        Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to demote: {e}"), None).await; }
+
+    let deleted = swept + confirmed;
+    let counted = if swept > 0 { format!("up to {deleted}") } else { deleted.to_string() };
+    let reason = format!("Purged {counted} messages");
+    Entry::new(Action::Delete).reason(&reason).record(bot, state, msg).await;
+    if silent { return; }
+    let mut text = format!("🧹 Purged {counted} message{}.", if deleted == 1 { "" } else { "s" });
+    if reply_too_old || (failed > 0 && may_be_too_old) {
+        text.push_str("\nOlder messages were left alone — bots can only delete messages from the last 48 hours.");
+    } else if failed > 0 {
+        text.push_str(&format!("\n{failed} couldn't be deleted."));
     }
+    let _ = bot.send_message(chat_id, text, None).await;
+}
+
+pub async fn cmd_purge(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    purge(bot, msg, args, state, false).await;
+}
+
+pub async fn cmd_spurge(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    purge(bot, msg, args, state, true).await;
 }
 
 pub async fn cmd_invite(bot: &Bot, msg: &Message) {
-    let sender_id = match msg.from.as_ref() { Some(u) => u.id, None => return };
-    let chat_id   = msg.chat.id;
-    if !is_admin(bot, chat_id, sender_id).await {
-        let _ = bot.send_message(chat_id, "❌ You need to be an admin to generate invite links.", None).await;
-        return;
-    }
+    let chat_id = msg.chat.id;
     match bot.export_chat_invite_link(chat_id).await {
         Ok(link) => {
             let _ = bot.send_message(
@@ -286,3 +624,151 @@ pub async fn cmd_invite(bot: &Bot, msg: &Message) {
         Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to generate link: {e}"), None).await; }
     }
 }
+
+// ─── Admin list ───────────────────────────────────────────────────────────────
+
+pub async fn cmd_admincache(bot: &Bot, msg: &Message, state: &SharedState) {
+    let reply = match admin_cache::refresh(bot, msg.chat.id, state).await {
+        Ok(admins) => format!("✅ Admin list refreshed — {} admin(s).", admins.len()),
+        Err(e) => format!("❌ Couldn't fetch the admin list: {e}"),
+    };
+    let _ = bot.send_message(msg.chat.id, reply, None).await;
+}
+
+/// Owner first, then the other admins, each with their custom title. Anonymous admins are
+/// only counted, since listing them would give them away.
+pub async fn cmd_adminlist(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let admins = match admin_cache::admins(bot, chat_id, state).await {
+        Ok(admins) => admins,
+        Err(e) => {
+            let _ = bot.send_message(chat_id, format!("❌ Couldn't fetch the admin list: {e}"), None).await;
+            return;
+        }
+    };
+
+    let mut lines = Vec::new();
+    let mut anonymous = 0;
+    for admin in &admins {
+        if admin.is_anonymous {
+            anonymous += 1;
+            continue;
+        }
+        let icon = if admin.is_owner() { "👑" } else if admin.user.is_bot { "🤖" } else { "•" };
+        let mut line = format!("{icon} {}", escape(&admin.user.first_name));
+        if let Some(title) = admin.custom_title.as_deref().filter(|t| !t.is_empty()) {
+            line.push_str(&format!(" — <i>{}</i>", escape(title)));
+        }
+        if admin.is_owner() { lines.insert(0, line) } else { lines.push(line) }
+    }
+    let chat = msg.chat.title.as_deref().unwrap_or("this chat");
+    let mut text = format!("👮 <b>Admins in {}</b>\n{}", escape(chat), lines.join("\n"));
+    if anonymous > 0 {
+        text.push_str(&format!("\n<i>+ {anonymous} anonymous</i>"));
+    }
+    let _ = bot.send_message(chat_id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
+
+// ─── Moderation log ───────────────────────────────────────────────────────────
+
+pub async fn cmd_setlog(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let arg     = args.trim();
+    let channel = if let Ok(id) = arg.parse::<i64>() {
+        id
+    } else if arg.starts_with('@') {
+        match bot.get_chat(arg).await {
+            Ok(chat) => chat.id,
+            Err(_) => { let _ = bot.send_message(chat_id, format!("❌ I can't find {arg}."), None).await; return; }
+        }
+    } else {
+        let text = match state.lock().await.store.chat_settings(chat_id).log_channel {
+            Some(id) => format!("📋 Moderation actions are logged to {id}.\nUse /setlog <channel> to change it or /unsetlog to stop."),
+            None => "Usage: /setlog <channel ID or @channel>\nAdd me to the channel as an admin first.".to_string(),
+        };
+        let _ = bot.send_message(chat_id, text, None).await;
+        return;
+    };
+
+    let title = msg.chat.title.as_deref().unwrap_or("this chat");
+    let hello = format!("📋 This channel now receives the moderation log for <b>{}</b>.", escape(title));
+    if let Err(e) = bot.send_message(channel, hello, Some(SendMessageParams::new().parse_mode("HTML"))).await {
+        let _ = bot.send_message(chat_id, format!("❌ I can't post there ({e}). Add me to the channel as an admin first."), None).await;
+        return;
+    }
+    settings::update(state, chat_id, |s| s.log_channel = Some(channel)).await;
+    let _ = bot.send_message(chat_id, format!("✅ Moderation actions will be logged to {channel}."), None).await;
+}
+
+pub async fn cmd_unsetlog(bot: &Bot, msg: &Message, state: &SharedState) {
+    settings::update(state, msg.chat.id, |s| s.log_channel = None).await;
+    let _ = bot.send_message(msg.chat.id, "✅ Moderation actions are no longer logged.", None).await;
+}
+
+/// Entries `/modlog` shows in the chat; the CSV export has all of them
+const MODLOG_SHOWN: usize = 20;
+
+/// `/modlog [@user|id] [action] [since] [csv]` — filters in any order; `since` is a
+/// duration back from now (`7d`) or a date (`2026-01-31`). Replying filters by that user.
+pub async fn cmd_modlog(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let mut user   = msg.reply_to_message.as_ref().and_then(|r| r.from.as_ref()).map(|u| u.id);
+    let mut action = None;
+    let mut since  = 0;
+    let mut csv    = false;
+    for word in args.split_whitespace() {
+        if word.eq_ignore_ascii_case("csv") {
+            csv = true;
+        } else if let Some(a) = Action::parse(word) {
+            action = Some(a);
+        } else if let Some(d) = duration::parse(word) {
+            since = chrono::Utc::now().timestamp() - d.as_secs() as i64;
+        } else if let Ok(date) = chrono::NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+            since = date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp();
+        } else if let Some(username) = word.strip_prefix('@') {
+            let Some(id) = state.lock().await.store.user_id_by_username(username) else {
+                let _ = bot.send_message(chat_id, format!("❌ I haven't seen @{username} yet — use their user ID."), None).await;
+                return;
+            };
+            user = Some(id);
+        } else if let Ok(id) = word.parse::<i64>() {
+            user = Some(id);
+        } else {
+            let _ = bot.send_message(
+                chat_id,
+                "Usage: /modlog [@user|ID] [action] [since] [csv]\n\
+                 Actions: ban, kick, mute, unmute, restrict, unrestrict, warn, clearwarns, promote, demote, approve, unapprove, delete, pin\n\
+                 Since: a duration like 7d, or a date like 2026-01-31",
+                None,
+            ).await;
+            return;
+        }
+    }
+
+    let entries: Vec<AuditEntry> = state.lock().await.store.audit_log(chat_id, since)
+        .into_iter()
+        .filter(|e| user.is_none_or(|id| e.target_id == Some(id)))
+        .filter(|e| action.is_none_or(|a| e.action == a))
+        .collect();
+
+    if csv {
+        let file   = InputFile::memory(format!("modlog{chat_id}.csv"), modlog::to_csv(&entries).into_bytes());
+        let params = SendDocumentParams::new().caption(format!("📜 {} moderation action(s)", entries.len()));
+        if let Err(e) = bot.send_document(chat_id, file, Some(params)).await {
+            let _ = bot.send_message(chat_id, format!("❌ Failed to export the log: {e}"), None).await;
+        }
+        return;
+    }
+    if entries.is_empty() {
+        let _ = bot.send_message(chat_id, "📜 No moderation actions match.", None).await;
+        return;
+    }
+    let shown = &entries[entries.len().saturating_sub(MODLOG_SHOWN)..];
+    let mut text = format!("📜 <b>Moderation log</b> — {} action(s)", entries.len());
+    if shown.len() < entries.len() {
+        text.push_str(&format!(", latest {MODLOG_SHOWN} shown. Add <code>csv</code> for all."));
+    }
+    text.push_str("\n\n");
+    text.push_str(&shown.iter().rev().map(modlog::summary).collect::<Vec<_>>().join("\n"));
+    let _ = bot.send_message(chat_id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
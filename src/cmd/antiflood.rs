@@ -0,0 +1,155 @@
+//! Antiflood: members who send more than `flood_limit` messages within `flood_window_secs`
+//! get the chat's `flood_action`.
+//!
+//! Every message `handle_update` sees is counted, not just commands. The counters live in
+//! `BotState::flood` and are not persisted.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+use tgbotrs::{Bot, Message};
+use tgbotrs::gen_methods::SendMessageParams;
+use crate::cmd::admin::is_exempt;
+use crate::cmd::settings;
+use crate::duration;
+use crate::modlog::Entry;
+use crate::punish::Punishment;
+use crate::registry::escape;
+use crate::state::SharedState;
+
+/// Allowed values for `/setflood`
+pub const LIMIT_RANGE: RangeInclusive<u32> = 2..=100;
+pub const WINDOW_RANGE: RangeInclusive<u64> = 1..=300;
+
+/// Past this many tracked members, idle ones are dropped on the next message.
+const PRUNE_AT: usize = 10_000;
+
+/// Recent message times per (chat_id, user_id) — a sliding window for each member.
+#[derive(Debug, Default)]
+pub struct FloodCounter {
+    recent: HashMap<(i64, i64), VecDeque<Instant>>,
+}
+
+impl FloodCounter {
+    /// Counts one message and returns whether the member has now sent more than `limit`
+    /// within `window`. The member's count starts over once they've tripped it.
+    pub fn hit(&mut self, key: (i64, i64), now: Instant, limit: u32, window: Duration) -> bool {
+        if self.recent.len() >= PRUNE_AT {
+            self.recent.retain(|_, times| times.back().is_some_and(|&t| now.duration_since(t) <= window));
+        }
+        let times = self.recent.entry(key).or_default();
+        times.push_back(now);
+        while times.front().is_some_and(|&t| now.duration_since(t) > window) {
+            times.pop_front();
+        }
+        if times.len() > limit as usize {
+            self.recent.remove(&key);
+            return true;
+        }
+        false
+    }
+}
+
+// ─── Checking messages ────────────────────────────────────────────────────────
+
+/// Counts `msg` towards its sender's window and punishes them if they're flooding.
+/// Returns `true` when action was taken, so the caller stops handling the message.
+pub async fn check(bot: &Bot, msg: &Message, state: &SharedState) -> bool {
+    let Some(user) = msg.from.as_deref().filter(|u| !u.is_bot) else { return false };
+    let chat_id = msg.chat.id;
+    let (flooding, action) = {
+        let mut st = state.lock().await;
+        let settings = st.store.chat_settings(chat_id);
+        if settings.flood_limit == 0 {
+            return false;
+        }
+        let window = Duration::from_secs(settings.flood_window_secs);
+        (st.flood.hit((chat_id, user.id), Instant::now(), settings.flood_limit, window), settings.flood_action)
+    };
+    // Only asked once someone trips the limit, so ordinary chatter costs no API calls
+    if !flooding || is_exempt(bot, chat_id, user.id, state).await {
+        return false;
+    }
+
+    if let Err(e) = action.apply(bot, chat_id, user.id).await {
+        eprintln!("⚠️   Antiflood: couldn't punish {} in {chat_id}: {e}", user.id);
+        return false;
+    }
+    let text = format!(
+        "🌊 <a href=\"tg://user?id={}\">{}</a> has been {} for flooding.",
+        user.id, escape(&user.first_name), action.label(),
+    );
+    let _ = bot.send_message(chat_id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+    Entry::new(action.into()).by(&bot.me).target(user).reason("Flooding").duration(action.secs()).record(bot, state, msg).await;
+    true
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+fn describe(limit: u32, window_secs: u64) -> String {
+    format!("more than {limit} messages in {}", duration::format(Duration::from_secs(window_secs)))
+}
+
+/// `/setflood <n|off> [window]` — the window defaults to what's set, 10s out of the box.
+pub async fn cmd_setflood(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let mut words = args.split_whitespace();
+    let limit = match words.next().map(str::to_lowercase).as_deref() {
+        Some("off" | "0") => Some(0),
+        Some(n) => n.parse::<u32>().ok().filter(|n| LIMIT_RANGE.contains(n)),
+        None => None,
+    };
+    let window = match words.next() {
+        Some(w) => duration::parse(w).map(|d| d.as_secs()).filter(|s| WINDOW_RANGE.contains(s)),
+        None => Some(state.lock().await.store.chat_settings(chat_id).flood_window_secs),
+    };
+    let reply = match (limit, window) {
+        (Some(0), _) => {
+            settings::update(state, chat_id, |s| s.flood_limit = 0).await;
+            "✅ Antiflood off.".to_string()
+        }
+        (Some(limit), Some(window)) => {
+            settings::update(state, chat_id, |s| {
+                s.flood_limit = limit;
+                s.flood_window_secs = window;
+            }).await;
+            format!("✅ Members who send {} will be dealt with.", describe(limit, window))
+        }
+        _ => format!(
+            "Usage: /setflood <messages|off> [window]\n\
+             Between {} and {} messages, within 1s to 5m — e.g. /setflood 5 10s",
+            LIMIT_RANGE.start(), LIMIT_RANGE.end(),
+        ),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+pub async fn cmd_setfloodmode(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let mut words = args.split_whitespace();
+    let reply = match words.next().and_then(|mode| Punishment::parse(mode, words.next())) {
+        Some(action) => {
+            settings::update(state, chat_id, |s| s.flood_action = action).await;
+            format!("✅ Members who flood will be {}.", action.label())
+        }
+        None => format!(
+            "Usage: /setfloodmode ban | kick | mute | tban <duration> | tmute <duration>\n\
+             Durations look like 30m, 2h or 1d12h.\nCurrently: {}",
+            state.lock().await.store.chat_settings(chat_id).flood_action.label(),
+        ),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+pub async fn cmd_flood(bot: &Bot, msg: &Message, state: &SharedState) {
+    let s = state.lock().await.store.chat_settings(msg.chat.id);
+    let reply = if s.flood_limit == 0 {
+        "🌊 Antiflood is off. Turn it on with /setflood <messages> [window].".to_string()
+    } else {
+        format!(
+            "🌊 Members who send {} will be {}.",
+            describe(s.flood_limit, s.flood_window_secs), s.flood_action.label(),
+        )
+    };
+    let _ = bot.send_message(msg.chat.id, reply, None).await;
+}
@@ -0,0 +1,91 @@
+//! Approved users: trusted regulars and partner bots that antiflood, locks and the
+//! blocklist leave alone, as if they were admins (see `admin::is_exempt`). Approval gives
+//! no admin rights and doesn't protect anyone from manual admin commands.
+
+use tgbotrs::{Bot, Message};
+use tgbotrs::gen_methods::SendMessageParams;
+use crate::cmd::admin::{is_admin, is_owner, require_target, Target};
+use crate::modlog::{timestamp, Entry};
+use crate::registry::escape;
+use crate::state::{Action, Approval, SharedState};
+
+fn html() -> SendMessageParams {
+    SendMessageParams::new().parse_mode("HTML")
+}
+
+/// `/approve [user]`
+pub async fn cmd_approve(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, .. }) = require_target(bot, msg, args, state).await else { return };
+    let name = escape(&target.first_name);
+    if is_admin(bot, chat_id, target.id, state).await {
+        let _ = bot.send_message(chat_id, format!("❌ <b>{name}</b> is an admin — approvals are for regular members."), Some(html())).await;
+        return;
+    }
+    let approval = Approval {
+        user_id:     target.id,
+        user_name:   target.first_name.clone(),
+        approved_by: msg.from.as_ref().map_or(0, |u| u.id),
+        at:          chrono::Utc::now().timestamp(),
+    };
+    let already = {
+        let mut st = state.lock().await;
+        let already = st.store.is_approved(chat_id, target.id);
+        if !already { st.store.approve(chat_id, &approval); }
+        already
+    };
+    if already {
+        let _ = bot.send_message(chat_id, format!("✅ <b>{name}</b> is already approved."), Some(html())).await;
+        return;
+    }
+    let _ = bot.send_message(chat_id, format!(
+        "✅ <b>{name}</b> is approved — antiflood, locks and the blocklist will leave them alone."
+    ), Some(html())).await;
+    Entry::new(Action::Approve).target(&target).record(bot, state, msg).await;
+}
+
+/// `/unapprove [user]`
+pub async fn cmd_unapprove(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, .. }) = require_target(bot, msg, args, state).await else { return };
+    let name = escape(&target.first_name);
+    let removed = state.lock().await.store.unapprove(chat_id, target.id);
+    if !removed {
+        let _ = bot.send_message(chat_id, format!("❌ <b>{name}</b> isn't approved."), Some(html())).await;
+        return;
+    }
+    let _ = bot.send_message(chat_id, format!("✅ <b>{name}</b> is no longer approved."), Some(html())).await;
+    Entry::new(Action::Unapprove).target(&target).record(bot, state, msg).await;
+}
+
+/// `/approved` — everyone approved in the chat, oldest first.
+pub async fn cmd_approved(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let approvals = state.lock().await.store.approvals(chat_id);
+    if approvals.is_empty() {
+        let _ = bot.send_message(chat_id, "Nobody is approved in this chat. Reply to someone with /approve to add them.", None).await;
+        return;
+    }
+    let mut lines = vec![format!("✅ <b>Approved users</b> ({})", approvals.len())];
+    lines.extend(approvals.iter().map(|a| format!(
+        "• <a href=\"tg://user?id={}\">{}</a> — since {}",
+        a.user_id, escape(&a.user_name), timestamp(a.at, "%Y-%m-%d"),
+    )));
+    let _ = bot.send_message(chat_id, lines.join("\n"), Some(html())).await;
+}
+
+/// `/unapproveall` — the chat's owner only, since it can't be undone.
+pub async fn cmd_unapproveall(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(sender) = msg.from.as_deref() else { return };
+    if !is_owner(bot, chat_id, sender.id, state).await {
+        let _ = bot.send_message(chat_id, "❌ Only the chat's owner can remove every approval.", None).await;
+        return;
+    }
+    let removed = state.lock().await.store.unapprove_all(chat_id);
+    if removed == 0 {
+        let _ = bot.send_message(chat_id, "Nobody is approved in this chat.", None).await;
+        return;
+    }
+    let _ = bot.send_message(chat_id, format!("✅ Removed {removed} approval(s)."), None).await;
+    let reason = format!("All {removed} approval(s) removed");
+    Entry::new(Action::Unapprove).reason(&reason).record(bot, state, msg).await;
+}
@@ -0,0 +1,264 @@
+//! Blocklist: messages whose text or caption contains a listed word, phrase or `/regex/`
+//! are deleted, and the sender optionally warned, muted or banned. Admins and
+//! `/approve`d users are exempt.
+//!
+//! Each chat's triggers are compiled once into a [`Blocklist`], cached in
+//! `BotState::blocklists` and rebuilt only after `/addblacklist` or `/unblacklist`.
+
+use std::collections::HashMap;
+use regex::{RegexBuilder, RegexSet, RegexSetBuilder};
+use tgbotrs::{Bot, Message};
+use tgbotrs::gen_methods::SendMessageParams;
+use crate::cmd::admin::{self, is_exempt};
+use crate::cmd::settings;
+use crate::modlog::Entry;
+use crate::punish::Punishment;
+use crate::registry::escape;
+use crate::state::{BlockedTrigger, BlocklistAction, SharedState};
+
+/// Most triggers one chat can have
+pub const MAX_TRIGGERS: usize = 500;
+
+/// Reason given in warnings and the moderation log
+const REASON: &str = "Blocklisted word";
+
+/// Stay under Telegram's 4096-character message limit when listing
+const LIST_CHUNK: usize = 3_500;
+
+/// Compiled size allowed for one chat's patterns, so a handful of huge ones can't eat memory
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+/// The pattern inside a `/regex/` trigger
+fn regex_source(trigger: &str) -> Option<&str> {
+    trigger.strip_prefix('/')?.strip_suffix('/').filter(|s| !s.is_empty())
+}
+
+/// Why `source` won't compile, in one line; `None` if it does.
+fn pattern_error(source: &str) -> Option<String> {
+    let e = RegexBuilder::new(source).case_insensitive(true).size_limit(PATTERN_SIZE_LIMIT).build().err()?;
+    Some(match e {
+        regex::Error::CompiledTooBig(_) => "it's too big".to_string(),
+        // The rest of the message repeats the pattern with a caret under the problem
+        e => e.to_string().lines().last().unwrap_or_default().trim_start_matches("error: ").to_string(),
+    })
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// A chat's triggers, ready to match against messages.
+#[derive(Debug, Default)]
+pub struct Blocklist {
+    /// Single words, looked up for each word of the message
+    words: HashMap<String, BlockedTrigger>,
+    /// Anything else that isn't a pattern, matched as a substring
+    phrases: Vec<BlockedTrigger>,
+    /// Every `/regex/` trigger at once, ignoring case; `None` when there are none
+    patterns: Option<RegexSet>,
+    /// The trigger behind each pattern in the set, in the same order
+    pattern_triggers: Vec<BlockedTrigger>,
+}
+
+impl Blocklist {
+    pub fn compile(entries: Vec<BlockedTrigger>) -> Blocklist {
+        let mut list = Blocklist::default();
+        for entry in entries {
+            if let Some(source) = regex_source(&entry.trigger) {
+                match pattern_error(source) {
+                    None => list.pattern_triggers.push(entry),
+                    Some(e) => eprintln!("⚠️   Blocklist: skipping {}: {e}", entry.trigger),
+                }
+            } else if entry.trigger.chars().all(is_word) {
+                list.words.insert(entry.trigger.clone(), entry);
+            } else {
+                list.phrases.push(entry);
+            }
+        }
+        if !list.pattern_triggers.is_empty() {
+            let sources = list.pattern_triggers.iter().filter_map(|e| regex_source(&e.trigger));
+            match RegexSetBuilder::new(sources).case_insensitive(true).size_limit(PATTERN_SIZE_LIMIT).build() {
+                Ok(set) => list.patterns = Some(set),
+                Err(e) => eprintln!("⚠️   Blocklist: skipping every pattern: {e}"),
+            }
+        }
+        list
+    }
+
+    /// The first trigger found in `text`
+    pub fn find(&self, text: &str) -> Option<&BlockedTrigger> {
+        let lowered = text.to_lowercase();
+        if let Some(hit) = lowered.split(|c: char| !is_word(c)).find_map(|word| self.words.get(word)) {
+            return Some(hit);
+        }
+        if let Some(hit) = self.phrases.iter().find(|p| lowered.contains(&p.trigger)) {
+            return Some(hit);
+        }
+        let first = self.patterns.as_ref()?.matches(text).into_iter().next()?;
+        self.pattern_triggers.get(first)
+    }
+}
+
+// ─── Checking messages ────────────────────────────────────────────────────────
+
+/// Acts on `msg` if its text or caption hits the chat's blocklist. Returns `true` when the
+/// message was dealt with, so the caller stops handling it.
+pub async fn check(bot: &Bot, msg: &Message, state: &SharedState) -> bool {
+    let Some(text) = msg.text.as_deref().or(msg.caption.as_deref()) else { return false };
+    let Some(user) = msg.from.as_deref().filter(|u| !u.is_bot) else { return false };
+    let chat_id = msg.chat.id;
+    let action = {
+        let mut guard = state.lock().await;
+        let st = &mut *guard;
+        let list = st.blocklists.entry(chat_id).or_insert_with(|| Blocklist::compile(st.store.blocklist(chat_id)));
+        let Some(hit) = list.find(text) else { return false };
+        hit.action.unwrap_or_else(|| st.store.chat_settings(chat_id).blocklist_action)
+    };
+    // Only asked on a hit, so clean messages cost no API calls
+    if is_exempt(bot, chat_id, user.id, state).await {
+        return false;
+    }
+
+    if let Err(e) = bot.delete_message(chat_id, msg.message_id).await {
+        eprintln!("⚠️   Blocklist: couldn't delete a message in {chat_id}: {e}");
+    }
+    let punishment = match action {
+        BlocklistAction::Delete => return true,
+        BlocklistAction::Warn => {
+            admin::warn(bot, msg, &bot.me, user, REASON, state).await;
+            return true;
+        }
+        BlocklistAction::Mute => Punishment::Mute,
+        BlocklistAction::Ban  => Punishment::Ban,
+    };
+    match punishment.apply(bot, chat_id, user.id).await {
+        Ok(()) => {
+            let text = format!(
+                "🚫 <a href=\"tg://user?id={}\">{}</a> has been {} for using a blocklisted word.",
+                user.id, escape(&user.first_name), punishment.label(),
+            );
+            let _ = bot.send_message(chat_id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+            Entry::new(punishment.into()).by(&bot.me).target(user).reason(REASON).record(bot, state, msg).await;
+        }
+        Err(e) => eprintln!("⚠️   Blocklist: couldn't {} {} in {chat_id}: {e}", action.label(), user.id),
+    }
+    true
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Words and phrases are kept lowercase with single spaces; patterns as typed.
+fn normalize(trigger: &str) -> String {
+    let trigger = trigger.trim();
+    if regex_source(trigger).is_some() {
+        trigger.to_string()
+    } else {
+        trigger.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+    }
+}
+
+/// `<word|phrase|/regex/> [action]` — the error explains a pattern that doesn't compile.
+fn parse_trigger(args: &str) -> Option<Result<BlockedTrigger, String>> {
+    let args = args.trim();
+    let (rest, action) = match args.rsplit_once(char::is_whitespace) {
+        Some((rest, last)) => match BlocklistAction::parse(last) {
+            Some(action) => (rest, Some(action)),
+            None => (args, None),
+        },
+        None => (args, None),
+    };
+    let trigger = normalize(rest);
+    if trigger.is_empty() || trigger == "//" {
+        return None;
+    }
+    if let Some(e) = regex_source(&trigger).and_then(pattern_error) {
+        return Some(Err(e));
+    }
+    Some(Ok(BlockedTrigger { trigger, action }))
+}
+
+pub async fn cmd_addblacklist(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let reply = match parse_trigger(args) {
+        None => "Usage: /addblacklist <word|phrase|/regex/> [delete|warn|mute|ban]\n\
+                 Words match whole words, phrases match anywhere, /regex/ ignores case."
+            .to_string(),
+        Some(Err(e)) => format!("❌ That pattern doesn't work: {}", escape(&e)),
+        Some(Ok(entry)) => {
+            let mut st = state.lock().await;
+            let list = st.store.blocklist(chat_id);
+            if list.len() >= MAX_TRIGGERS && !list.iter().any(|e| e.trigger == entry.trigger) {
+                format!("❌ This chat already has {MAX_TRIGGERS} blocklist triggers — remove some first.")
+            } else {
+                st.store.add_blocked(chat_id, &entry);
+                st.blocklists.remove(&chat_id);
+                let action = entry.action.map(|a| format!(" (action: {})", a.label())).unwrap_or_default();
+                format!("✅ Added <code>{}</code> to the blocklist{action}.", escape(&entry.trigger))
+            }
+        }
+    };
+    let _ = bot.send_message(chat_id, reply, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
+
+pub async fn cmd_unblacklist(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let trigger = normalize(args);
+    let reply = if trigger.is_empty() {
+        "Usage: /unblacklist <word|phrase|/regex/> — as listed by /blacklist".to_string()
+    } else {
+        let mut st = state.lock().await;
+        if st.store.remove_blocked(chat_id, &trigger) {
+            st.blocklists.remove(&chat_id);
+            format!("✅ Removed <code>{}</code> from the blocklist.", escape(&trigger))
+        } else {
+            format!("❌ <code>{}</code> isn't on the blocklist.", escape(&trigger))
+        }
+    };
+    let _ = bot.send_message(chat_id, reply, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
+
+pub async fn cmd_blacklist(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let (list, default) = {
+        let st = state.lock().await;
+        (st.store.blocklist(chat_id), st.store.chat_settings(chat_id).blocklist_action)
+    };
+    if list.is_empty() {
+        let _ = bot.send_message(chat_id, "🚫 The blocklist is empty. Add to it with /addblacklist.", None).await;
+        return;
+    }
+
+    let mut chunks = vec![format!("🚫 <b>Blocklist</b> ({}) — action: <b>{}</b>", list.len(), default.label())];
+    for entry in &list {
+        let action = entry.action.map(|a| format!(" → {}", a.label())).unwrap_or_default();
+        let line = format!("\n• <code>{}</code>{action}", escape(&entry.trigger));
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() + line.len() <= LIST_CHUNK => chunk.push_str(&line),
+            _ => chunks.push(line.trim_start().to_string()),
+        }
+    }
+    for chunk in chunks {
+        let _ = bot.send_message(chat_id, chunk, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+    }
+}
+
+pub async fn cmd_blacklistmode(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let reply = match BlocklistAction::parse(args.trim()) {
+        Some(action) => {
+            settings::update(state, chat_id, |s| s.blocklist_action = action).await;
+            let sender = match action {
+                BlocklistAction::Delete => "",
+                BlocklistAction::Warn   => " and the sender warned",
+                BlocklistAction::Mute   => " and the sender muted",
+                BlocklistAction::Ban    => " and the sender banned",
+            };
+            format!("✅ Blocklisted messages will be deleted{sender}.")
+        }
+        None => format!(
+            "Usage: /blacklistmode delete|warn|mute|ban\nCurrently: {}",
+            state.lock().await.store.chat_settings(chat_id).blocklist_action.label(),
+        ),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
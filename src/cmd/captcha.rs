@@ -0,0 +1,268 @@
+//! Join captcha: new members are muted until they solve a challenge, and kicked if they
+//! haven't within the chat's timeout.
+//!
+//! Callback data is `cap:<user_id>:<answer>`, so only the member who joined can solve it.
+//! Pending challenges are kept in storage with their deadline, and the `scheduler` tick
+//! kicks whoever runs out of time — including anyone left over from before a restart.
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tgbotrs::{Bot, CallbackQuery, Chat, InlineKeyboardButton, MaybeInaccessibleMessage, Message, User};
+use tgbotrs::gen_methods::{AnswerCallbackQueryParams, SendMessageParams};
+use crate::admin_cache;
+use crate::cmd::admin::all_perms;
+use crate::cmd::{settings, welcome};
+use crate::duration;
+use crate::kb::{btn, kb};
+use crate::punish::Punishment;
+use crate::registry::escape;
+use crate::state::{PendingCaptcha, SharedState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaMode {
+    #[default]
+    Off,
+    /// Press "I'm human"
+    Button,
+    /// Pick the sum of two numbers
+    Math,
+    /// Pick the named emoji out of six
+    Emoji,
+}
+
+impl CaptchaMode {
+    pub fn parse(text: &str) -> Option<CaptchaMode> {
+        match text.to_lowercase().as_str() {
+            "off"    => Some(CaptchaMode::Off),
+            "button" => Some(CaptchaMode::Button),
+            "math"   => Some(CaptchaMode::Math),
+            "emoji"  => Some(CaptchaMode::Emoji),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CaptchaMode::Off    => "off",
+            CaptchaMode::Button => "button",
+            CaptchaMode::Math   => "math",
+            CaptchaMode::Emoji  => "emoji",
+        }
+    }
+}
+
+/// Allowed range for `/captchatime` — no shorter than the scheduler's tick
+pub const TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 60..=86_400;
+
+const TRIES: u32 = 3;
+
+const EMOJIS: [(&str, &str); 12] = [
+    ("🍎", "apple"), ("🚗", "car"), ("🐶", "dog"), ("🌵", "cactus"), ("🎸", "guitar"), ("⚽", "ball"),
+    ("🍕", "pizza"), ("🚀", "rocket"), ("🐱", "cat"), ("🌙", "moon"), ("🔑", "key"), ("☂️", "umbrella"),
+];
+
+// ─── Joining ──────────────────────────────────────────────────────────────────
+
+/// Mutes `user` and posts a challenge if the chat has a captcha on. Returns `false` when
+/// nothing was started (captcha off, or the bot can't restrict), so the caller greets as usual.
+///
+/// Only plain members are challenged: someone who comes back still restricted would be
+/// unmuted by passing, so they're left as they are.
+pub async fn challenge(bot: &Bot, chat: &Chat, user: &User, state: &SharedState) -> bool {
+    let settings = state.lock().await.store.chat_settings(chat.id);
+    if settings.captcha_mode == CaptchaMode::Off {
+        return false;
+    }
+    match admin_cache::member(bot, chat.id, user.id).await {
+        Ok(m) if m.status == "member" => {}
+        Ok(_) => return false,
+        Err(e) => {
+            eprintln!("⚠️   Captcha: couldn't look up {} in {}: {e}", user.id, chat.id);
+            return false;
+        }
+    }
+    if let Err(e) = bot.restrict_chat_member(chat.id, user.id, all_perms(false), None).await {
+        eprintln!("⚠️   Captcha: couldn't mute {} in {}: {e}", user.id, chat.id);
+        return false;
+    }
+
+    let (question, answer, rows) = puzzle(settings.captcha_mode, user.id);
+    let text = format!(
+        "👋 Welcome, <a href=\"tg://user?id={}\">{}</a>! {question}\n\
+         <i>You have {} — otherwise you'll be removed.</i>",
+        user.id, escape(&user.first_name), duration::format(Duration::from_secs(settings.captcha_timeout_secs)),
+    );
+    let params = SendMessageParams::new().parse_mode("HTML").reply_markup(kb(rows));
+    let message_id = match bot.send_message(chat.id, text, Some(params)).await {
+        Ok(sent) => sent.message_id,
+        Err(e) => {
+            eprintln!("⚠️   Captcha: couldn't post a challenge in {}: {e}", chat.id);
+            let _ = bot.restrict_chat_member(chat.id, user.id, all_perms(true), None).await;
+            return false;
+        }
+    };
+    let deadline = Utc::now().timestamp() + settings.captcha_timeout_secs as i64;
+    let pending = PendingCaptcha { user_id: user.id, answer, message_id, tries_left: TRIES, deadline };
+    state.lock().await.store.save_captcha(chat.id, &pending);
+    true
+}
+
+/// Kicks everyone whose captcha deadline has passed. Run by the `scheduler`, so members
+/// go within a `TICK` of their time running out.
+pub async fn tick(bot: &Bot, state: &SharedState, now: DateTime<Utc>) {
+    let expired = {
+        let mut st = state.lock().await;
+        let expired = st.store.expired_captchas(now.timestamp());
+        for (chat_id, pending) in &expired {
+            st.store.delete_captcha(*chat_id, pending.user_id);
+        }
+        expired
+    };
+    for (chat_id, pending) in expired {
+        remove(bot, chat_id, pending.user_id, pending.message_id).await;
+    }
+}
+
+/// Question text, the right answer and the keyboard for one challenge.
+fn puzzle(mode: CaptchaMode, user_id: i64) -> (String, String, Vec<Vec<InlineKeyboardButton>>) {
+    let mut rng = rand::thread_rng();
+    let data = |answer: &str| format!("cap:{user_id}:{answer}");
+    match mode {
+        CaptchaMode::Off | CaptchaMode::Button => (
+            "Tap the button below to show you're human.".into(),
+            "ok".into(),
+            vec![vec![btn("✅ I'm human", &data("ok"))]],
+        ),
+        CaptchaMode::Math => {
+            let (a, b) = (rng.gen_range(1..=20), rng.gen_range(1..=20));
+            let sum: u32 = a + b;
+            let mut options = vec![sum];
+            while options.len() < 4 {
+                let wrong = rng.gen_range(2..=40);
+                if !options.contains(&wrong) { options.push(wrong); }
+            }
+            options.shuffle(&mut rng);
+            let row = options.iter().map(|n| btn(&n.to_string(), &data(&n.to_string()))).collect();
+            (format!("What is <b>{a} + {b}</b>?"), sum.to_string(), vec![row])
+        }
+        CaptchaMode::Emoji => {
+            let picks: Vec<usize> = rand::seq::index::sample(&mut rng, EMOJIS.len(), 6).into_vec();
+            let target = picks[rng.gen_range(0..picks.len())];
+            let rows = picks.chunks(3)
+                .map(|chunk| chunk.iter().map(|&i| btn(EMOJIS[i].0, &data(&i.to_string()))).collect())
+                .collect();
+            (format!("Tap the <b>{}</b>.", EMOJIS[target].1), target.to_string(), rows)
+        }
+    }
+}
+
+/// Kicks a member who failed or ran out of time, and cleans up the challenge.
+async fn remove(bot: &Bot, chat_id: i64, user_id: i64, message_id: i64) {
+    if let Err(e) = Punishment::Kick.apply(bot, chat_id, user_id).await {
+        eprintln!("⚠️   Captcha: couldn't kick {user_id} from {chat_id}: {e}");
+    }
+    let _ = bot.delete_message(chat_id, message_id).await;
+}
+
+// ─── Answers ──────────────────────────────────────────────────────────────────
+
+enum Outcome {
+    Solved,
+    Wrong(u32),
+    Failed,
+    Gone,
+}
+
+/// Handles `cap:<user_id>:<answer>` presses; answers the callback itself.
+pub async fn on_callback(bot: &Bot, cbq: &CallbackQuery, data: &str, state: &SharedState) {
+    let reply = |text: &str, alert: bool| {
+        let params = AnswerCallbackQueryParams::new().text(text).show_alert(alert);
+        bot.answer_callback_query(&cbq.id, Some(params))
+    };
+    let Some(MaybeInaccessibleMessage::Message(m)) = cbq.message.as_deref() else {
+        let _ = reply("This captcha has expired.", true).await;
+        return;
+    };
+    let Some((user_id, answer)) = data.split_once(':').and_then(|(id, a)| Some((id.parse::<i64>().ok()?, a))) else {
+        let _ = reply("This captcha has expired.", true).await;
+        return;
+    };
+    if cbq.from.id != user_id {
+        let _ = reply("This captcha is for someone else.", true).await;
+        return;
+    }
+
+    let chat_id = m.chat.id;
+    let outcome = {
+        let mut st = state.lock().await;
+        match st.store.captcha(chat_id, user_id).filter(|p| p.message_id == m.message_id) {
+            None => Outcome::Gone,
+            Some(p) if p.answer == answer => { st.store.delete_captcha(chat_id, user_id); Outcome::Solved }
+            Some(mut p) if p.tries_left > 1 => {
+                p.tries_left -= 1;
+                st.store.save_captcha(chat_id, &p);
+                Outcome::Wrong(p.tries_left)
+            }
+            Some(_) => { st.store.delete_captcha(chat_id, user_id); Outcome::Failed }
+        }
+    };
+
+    match outcome {
+        Outcome::Solved => {
+            let _ = reply("✅ Welcome!", false).await;
+            let _ = bot.restrict_chat_member(m.chat.id, user_id, all_perms(true), None).await;
+            let _ = bot.delete_message(m.chat.id, m.message_id).await;
+            welcome::greet(bot, &m.chat, &cbq.from, state).await;
+        }
+        Outcome::Wrong(left) => {
+            let tries = if left == 1 { "try" } else { "tries" };
+            let _ = reply(&format!("❌ Wrong answer — {left} {tries} left."), true).await;
+        }
+        Outcome::Failed => {
+            let _ = reply("❌ Wrong answer.", true).await;
+            remove(bot, m.chat.id, user_id, m.message_id).await;
+        }
+        Outcome::Gone => { let _ = reply("This captcha has expired.", true).await; }
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+pub async fn cmd_captcha(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let reply = match CaptchaMode::parse(args.trim()) {
+        Some(CaptchaMode::Off) => {
+            settings::update(state, chat_id, |s| s.captcha_mode = CaptchaMode::Off).await;
+            "✅ Captcha off — new members can talk right away.".to_string()
+        }
+        Some(mode) => {
+            settings::update(state, chat_id, |s| s.captcha_mode = mode).await;
+            format!("✅ New members will be muted until they pass a {} captcha.", mode.label())
+        }
+        None => {
+            let s = state.lock().await.store.chat_settings(chat_id);
+            format!(
+                "Usage: /captcha off|button|math|emoji\nCurrently: {}, {} to answer",
+                s.captcha_mode.label(), duration::format(Duration::from_secs(s.captcha_timeout_secs)),
+            )
+        }
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+pub async fn cmd_captchatime(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let secs = duration::parse(args).map(|d| d.as_secs()).filter(|s| TIMEOUT_RANGE.contains(s));
+    let reply = match secs {
+        Some(secs) => {
+            settings::update(state, chat_id, |s| s.captcha_timeout_secs = secs).await;
+            format!("✅ New members now have {} to solve the captcha.", duration::format(Duration::from_secs(secs)))
+        }
+        None => "Usage: /captchatime <duration> — between 1m and 1d, e.g. 5m".to_string(),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
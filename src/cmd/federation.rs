@@ -0,0 +1,326 @@
+//! Federations: groups that share one ban list.
+//!
+//! Anyone can create a federation with `/newfed`; a chat's owner adds the chat with
+//! `/joinfed <id>`. The federation's owner and the admins they `/fpromote` can `/fban`
+//! someone, which bans them in every chat of the federation at once and keeps them out
+//! of those chats when they try to join again. Commands act on the chat's federation,
+//! or in private chats on the one the sender owns.
+
+use tgbotrs::{Bot, Chat, InputFile, Message, User};
+use tgbotrs::gen_methods::{SendDocumentParams, SendMessageParams, UnbanChatMemberParams};
+use crate::cmd::admin::{is_owner, member_user, require_target, require_user, Target};
+use crate::modlog::{csv_field, timestamp, Entry};
+use crate::registry::escape;
+use crate::state::{Action, FedBan, Federation, SharedState};
+
+/// Longest federation name `/newfed` accepts
+pub const MAX_NAME: usize = 64;
+
+fn html() -> SendMessageParams {
+    SendMessageParams::new().parse_mode("HTML")
+}
+
+fn mention(user_id: i64, name: &str) -> String {
+    format!("<a href=\"tg://user?id={user_id}\">{}</a>", escape(name))
+}
+
+/// The federation a command in `msg`'s chat acts on; tells the sender when there's none.
+async fn current(bot: &Bot, msg: &Message, state: &SharedState) -> Option<Federation> {
+    let chat_id = msg.chat.id;
+    let fed = {
+        let st = state.lock().await;
+        if msg.chat.r#type == "private" {
+            msg.from.as_ref().and_then(|u| st.store.owned_federation(u.id))
+        } else {
+            st.store.chat_federation(chat_id).and_then(|id| st.store.federation(&id))
+        }
+    };
+    if fed.is_none() {
+        let text = if msg.chat.r#type == "private" {
+            "❌ You don't own a federation — create one with /newfed <name>."
+        } else {
+            "❌ This chat isn't in a federation."
+        };
+        let _ = bot.send_message(chat_id, text, None).await;
+    }
+    fed
+}
+
+/// Whether the sender owns the chat; tells them otherwise.
+async fn require_chat_owner(bot: &Bot, msg: &Message, state: &SharedState) -> bool {
+    let chat_id = msg.chat.id;
+    if msg.chat.r#type == "private" {
+        let _ = bot.send_message(chat_id, "❌ Use this in the group itself.", None).await;
+        return false;
+    }
+    let Some(sender) = msg.from.as_deref() else { return false };
+    let owner = is_owner(bot, chat_id, sender.id, state).await;
+    if !owner {
+        let _ = bot.send_message(chat_id, "❌ Only the chat's owner can change its federation.", None).await;
+    }
+    owner
+}
+
+// ─── Creating and joining ─────────────────────────────────────────────────────
+
+pub async fn cmd_newfed(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(owner) = msg.from.as_deref() else { return };
+    let name = args.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME {
+        let _ = bot.send_message(chat_id, format!("Usage: /newfed <name> — up to {MAX_NAME} characters"), None).await;
+        return;
+    }
+    let existing = state.lock().await.store.owned_federation(owner.id);
+    if let Some(fed) = existing {
+        let _ = bot.send_message(chat_id, format!(
+            "❌ You already own <b>{}</b> (<code>{}</code>).", escape(&fed.name), fed.id,
+        ), Some(html())).await;
+        return;
+    }
+    let fed = Federation {
+        id: format!("{:016x}", rand::random::<u64>()),
+        name: name.to_string(),
+        owner_id: owner.id,
+        admins: Default::default(),
+    };
+    state.lock().await.store.save_federation(&fed);
+    let _ = bot.send_message(chat_id, format!(
+        "✅ Federation <b>{}</b> created.\nID: <code>{}</code>\nThe owner of each group can add it with /joinfed {}",
+        escape(&fed.name), fed.id, fed.id,
+    ), Some(html())).await;
+}
+
+pub async fn cmd_joinfed(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    if !require_chat_owner(bot, msg, state).await {
+        return;
+    }
+    let fed_id = args.trim();
+    let Some(fed) = state.lock().await.store.federation(fed_id) else {
+        let _ = bot.send_message(chat_id, "Usage: /joinfed <federation ID> — ask its owner for the ID", None).await;
+        return;
+    };
+    let previous = {
+        let mut st = state.lock().await;
+        let previous = st.store.chat_federation(chat_id);
+        st.store.set_chat_federation(chat_id, Some(&fed.id));
+        previous
+    };
+    let text = if previous.as_deref() == Some(fed.id.as_str()) {
+        format!("✅ This chat is already in <b>{}</b>.", escape(&fed.name))
+    } else {
+        format!("✅ This chat joined <b>{}</b> — its bans now apply here.", escape(&fed.name))
+    };
+    let _ = bot.send_message(chat_id, text, Some(html())).await;
+}
+
+pub async fn cmd_leavefed(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    if !require_chat_owner(bot, msg, state).await {
+        return;
+    }
+    let fed = {
+        let mut st = state.lock().await;
+        let fed = st.store.chat_federation(chat_id).and_then(|id| st.store.federation(&id));
+        st.store.set_chat_federation(chat_id, None);
+        fed
+    };
+    let text = match fed {
+        Some(fed) => format!("✅ This chat left <b>{}</b>.", escape(&fed.name)),
+        None => "❌ This chat isn't in a federation.".to_string(),
+    };
+    let _ = bot.send_message(chat_id, text, Some(html())).await;
+}
+
+// ─── Federation admins ────────────────────────────────────────────────────────
+
+/// A name for `user_id`, if they're in this chat; their ID otherwise.
+async fn name_of(bot: &Bot, chat_id: i64, user_id: i64) -> String {
+    match bot.get_chat_member(chat_id, user_id).await {
+        Ok(member) => member_user(member).first_name,
+        Err(_)     => user_id.to_string(),
+    }
+}
+
+pub async fn cmd_fedadmins(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(fed) = current(bot, msg, state).await else { return };
+    let mut lines = vec![format!("🌐 <b>{}</b> admins", escape(&fed.name))];
+    let owner = name_of(bot, chat_id, fed.owner_id).await;
+    lines.push(format!("👑 {}", mention(fed.owner_id, &owner)));
+    for &id in &fed.admins {
+        let name = name_of(bot, chat_id, id).await;
+        lines.push(format!("• {}", mention(id, &name)));
+    }
+    let _ = bot.send_message(chat_id, lines.join("\n"), Some(html())).await;
+}
+
+/// `/fpromote` and `/fdemote`, which only the federation's owner may use.
+async fn set_fed_admin(bot: &Bot, msg: &Message, args: &str, state: &SharedState, promote: bool) {
+    let chat_id = msg.chat.id;
+    let Some(mut fed) = current(bot, msg, state).await else { return };
+    if msg.from.as_ref().is_none_or(|u| u.id != fed.owner_id) {
+        let _ = bot.send_message(chat_id, "❌ Only the federation's owner can change its admins.", None).await;
+        return;
+    }
+    let Some(Target { user: target, .. }) = require_target(bot, msg, args, state).await else { return };
+    if target.id == fed.owner_id {
+        let _ = bot.send_message(chat_id, "❌ That's the federation's owner.", None).await;
+        return;
+    }
+    let changed = if promote { fed.admins.insert(target.id) } else { fed.admins.remove(&target.id) };
+    if changed {
+        state.lock().await.store.save_federation(&fed);
+    }
+    let (name, fed_name) = (escape(&target.first_name), escape(&fed.name));
+    let text = match (promote, changed) {
+        (true, true)   => format!("✅ <b>{name}</b> is now an admin of <b>{fed_name}</b>."),
+        (true, false)  => format!("✅ <b>{name}</b> is already an admin of <b>{fed_name}</b>."),
+        (false, true)  => format!("✅ <b>{name}</b> is no longer an admin of <b>{fed_name}</b>."),
+        (false, false) => format!("❌ <b>{name}</b> isn't an admin of <b>{fed_name}</b>."),
+    };
+    let _ = bot.send_message(chat_id, text, Some(html())).await;
+}
+
+pub async fn cmd_fpromote(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    set_fed_admin(bot, msg, args, state, true).await;
+}
+
+pub async fn cmd_fdemote(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    set_fed_admin(bot, msg, args, state, false).await;
+}
+
+// ─── Bans ─────────────────────────────────────────────────────────────────────
+
+/// The chat's federation, if the sender may ban in it; tells them otherwise.
+async fn require_fed_admin(bot: &Bot, msg: &Message, state: &SharedState) -> Option<Federation> {
+    let fed = current(bot, msg, state).await?;
+    if msg.from.as_ref().is_some_and(|u| fed.is_admin(u.id)) {
+        return Some(fed);
+    }
+    let _ = bot.send_message(msg.chat.id, format!("❌ Only admins of <b>{}</b> can do that.", escape(&fed.name)), Some(html())).await;
+    None
+}
+
+pub async fn cmd_fban(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(fed) = require_fed_admin(bot, msg, state).await else { return };
+    let Some(Target { user: target, rest: reason, .. }) = require_user(bot, msg, args, state).await else { return };
+    let Some(admin) = msg.from.as_deref() else { return };
+    if target.id == bot.me.id || fed.is_admin(target.id) {
+        let _ = bot.send_message(chat_id, "❌ I won't federation-ban myself or a federation admin.", None).await;
+        return;
+    }
+
+    let ban = FedBan {
+        user_id: target.id,
+        user_name: target.first_name.clone(),
+        reason: reason.clone(),
+        banned_by: admin.id,
+        at: chrono::Utc::now().timestamp(),
+    };
+    let (chats, already) = {
+        let mut st = state.lock().await;
+        let already = st.store.fed_ban(&fed.id, target.id).is_some();
+        st.store.add_fed_ban(&fed.id, &ban);
+        (st.store.federation_chats(&fed.id), already)
+    };
+    let mut banned = 0;
+    for &chat in &chats {
+        match bot.ban_chat_member(chat, target.id, None).await {
+            Ok(_)  => banned += 1,
+            Err(e) => eprintln!("⚠️   Fed ban: couldn't ban {} in {chat}: {e}", target.id),
+        }
+    }
+    if chats.contains(&chat_id) {
+        let reason = format!("Federation ban: {}", if reason.is_empty() { "no reason given" } else { &reason });
+        Entry::new(Action::Ban).target(&target).reason(&reason).record(bot, state, msg).await;
+    }
+
+    let verb = if already { "is still banned — reason updated" } else { "is now banned" };
+    let mut text = format!(
+        "🔨 <b>{}</b> {verb} in <b>{}</b>: {banned} of {} chat(s).",
+        escape(&target.first_name), escape(&fed.name), chats.len(),
+    );
+    if !reason.is_empty() {
+        text.push_str(&format!("\nReason: {}", escape(&reason)));
+    }
+    let _ = bot.send_message(chat_id, text, Some(html())).await;
+}
+
+pub async fn cmd_unfban(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(fed) = require_fed_admin(bot, msg, state).await else { return };
+    let Some(Target { user: target, .. }) = require_user(bot, msg, args, state).await else { return };
+    let (removed, chats) = {
+        let mut st = state.lock().await;
+        (st.store.remove_fed_ban(&fed.id, target.id), st.store.federation_chats(&fed.id))
+    };
+    let name = escape(&target.first_name);
+    if !removed {
+        let _ = bot.send_message(chat_id, format!("❌ <b>{name}</b> isn't banned in <b>{}</b>.", escape(&fed.name)), Some(html())).await;
+        return;
+    }
+    for &chat in &chats {
+        let params = UnbanChatMemberParams::new().only_if_banned(true);
+        let _ = bot.unban_chat_member(chat, target.id, Some(params)).await;
+    }
+    let _ = bot.send_message(chat_id, format!(
+        "✅ <b>{name}</b> is no longer banned in <b>{}</b> and can rejoin its chats.", escape(&fed.name),
+    ), Some(html())).await;
+}
+
+/// The federation's bans as CSV with a header row.
+pub fn bans_csv(bans: &[FedBan]) -> String {
+    let mut csv = String::from("user_id,user,reason,banned_by,time\n");
+    for b in bans {
+        let row = [
+            b.user_id.to_string(),
+            csv_field(&b.user_name),
+            csv_field(&b.reason),
+            b.banned_by.to_string(),
+            timestamp(b.at, "%Y-%m-%dT%H:%M:%SZ"),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub async fn cmd_fedbanlist(bot: &Bot, msg: &Message, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(fed) = require_fed_admin(bot, msg, state).await else { return };
+    let bans = state.lock().await.store.fed_bans(&fed.id);
+    if bans.is_empty() {
+        let _ = bot.send_message(chat_id, format!("✅ Nobody is banned in <b>{}</b>.", escape(&fed.name)), Some(html())).await;
+        return;
+    }
+    let file   = InputFile::memory(format!("fedbans-{}.csv", fed.id), bans_csv(&bans).into_bytes());
+    let params = SendDocumentParams::new().caption(format!("🌐 {} — {} ban(s)", fed.name, bans.len()));
+    if let Err(e) = bot.send_document(chat_id, file, Some(params)).await {
+        let _ = bot.send_message(chat_id, format!("❌ Failed to export the ban list: {e}"), None).await;
+    }
+}
+
+// ─── Joins ────────────────────────────────────────────────────────────────────
+
+/// Bans `user` if they're banned in the chat's federation; returns whether they were.
+/// Runs for every new member, before the captcha and the welcome.
+pub async fn check_join(bot: &Bot, chat: &Chat, user: &User, state: &SharedState) -> bool {
+    let found = {
+        let st = state.lock().await;
+        st.store.chat_federation(chat.id).and_then(|id| Some((st.store.federation(&id)?, st.store.fed_ban(&id, user.id)?)))
+    };
+    let Some((fed, ban)) = found else { return false };
+    if let Err(e) = bot.ban_chat_member(chat.id, user.id, None).await {
+        eprintln!("⚠️   Fed ban: couldn't ban {} in {}: {e}", user.id, chat.id);
+        return false;
+    }
+    let mut text = format!("🔨 {} is banned in the federation <b>{}</b> and was removed.", mention(user.id, &user.first_name), escape(&fed.name));
+    if !ban.reason.is_empty() {
+        text.push_str(&format!("\nReason: {}", escape(&ban.reason)));
+    }
+    let _ = bot.send_message(chat.id, text, Some(html())).await;
+    true
+}
@@ -108,7 +108,7 @@ pub async fn cmd_dice(bot: &Bot, msg: &Message) {
 }
 
 pub async fn cmd_roll(bot: &Bot, msg: &Message, args: &str) {
-    let sides: u32 = args.trim().parse().unwrap_or(6).max(2).min(1000);
+    let sides: u32 = args.trim().parse().unwrap_or(6).clamp(2, 1000);
     let roll = rand::thread_rng().gen_range(1..=sides);
     let _ = bot.send_message(
         msg.chat.id,
@@ -228,7 +228,7 @@ pub async fn cmd_rate(bot: &Bot, msg: &Message, thing: &str) {
 
 /// /password [length]
 pub async fn cmd_password(bot: &Bot, msg: &Message, args: &str) {
-    let len: usize = args.trim().parse().unwrap_or(16).max(6).min(64);
+    let len: usize = args.trim().parse().unwrap_or(16).clamp(6, 64);
     const CHARSET: &[u8] =
         b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghjkmnpqrstuvwxyz23456789!@#$%^&*";
 
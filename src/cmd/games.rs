@@ -1,6 +1,7 @@
 use rand::Rng;
 use tgbotrs::{Bot, Message};
 use tgbotrs::gen_methods::SendMessageParams;
+use crate::i18n::{fill, tr, Text};
 use crate::state::{NumberGame, SharedState};
 
 pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
@@ -18,12 +19,14 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
     // ─── Start a new game ───────────────────────────────────────────────
     if args.trim().is_empty() {
         let mut st = state.lock().await;
+        let settings = st.store.chat_settings(chat_id);
 
-        if st.games.contains_key(&chat_id) {
+        if st.store.game(chat_id).is_some() {
+            drop(st);
             let _ = bot
                 .send_message(
                     chat_id,
-                    "🎮 A game is already running! Use `/guess <number>` to guess, or /giveup to quit.",
+                    tr(settings.language, Text::GameRunning),
                     Some(SendMessageParams::new().parse_mode("Markdown")),
                 )
                 .await;
@@ -31,26 +34,23 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
         }
 
         let secret = rand::thread_rng().gen_range(1u32..=100);
-        st.games.insert(
-            chat_id,
-            NumberGame {
-                secret,
-                attempts: 0,
-                max_attempts: 7,
-                _player_id: player_id,
-                player_name: player_name.clone(),
-            },
-        );
+        let max = settings.guess_attempts;
+        let game = NumberGame {
+            secret,
+            attempts: 0,
+            max_attempts: max,
+            _player_id: player_id,
+            player_name: player_name.clone(),
+        };
+        st.store.save_game(chat_id, &game);
+        drop(st);
 
         let _ = bot
             .send_message(
                 chat_id,
-                format!(
-                    "🎮 *Number Guessing Game started!*\n\n\
-                     {player_name} is playing!\n\
-                     I'm thinking of a number between *1* and *100*.\n\
-                     You have *7 attempts*.\n\n\
-                     Use `/guess <number>` to guess!"
+                fill(
+                    tr(settings.language, Text::GameStarted),
+                    &[("name", player_name.clone()), ("max", max.to_string())],
                 ),
                 Some(SendMessageParams::new().parse_mode("Markdown")),
             )
@@ -58,12 +58,14 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
         return;
     }
 
+    let lang = state.lock().await.store.chat_settings(chat_id).language;
+
     // ─── Parse guess ────────────────────────────────────────────────────
     let guess: u32 = match args.trim().parse() {
         Ok(n) => n,
         Err(_) => {
             let _ = bot
-                .send_message(chat_id, "❌ Please enter a valid number between 1 and 100.", None)
+                .send_message(chat_id, tr(lang, Text::NotANumber), None)
                 .await;
             return;
         }
@@ -71,7 +73,7 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
 
     if !(1..=100).contains(&guess) {
         let _ = bot
-            .send_message(chat_id, "❌ Number must be between 1 and 100!", None)
+            .send_message(chat_id, tr(lang, Text::OutOfRange), None)
             .await;
         return;
     }
@@ -79,7 +81,7 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
     // ─── Game logic (NO await inside lock) ───────────────────────────────
     let result = {
         let mut st = state.lock().await;
-        let game = match st.games.get_mut(&chat_id) {
+        let mut game = match st.store.game(chat_id) {
             Some(g) => g,
             None => {
                 return;
@@ -93,23 +95,28 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
         let name = game.player_name.clone();
 
         if guess == secret {
-            st.games.remove(&chat_id);
-            format!(
-                "🎉 *Correct!* The number was *{secret}*!\n{name} got it in *{attempts}/{max}* attempts! 🏆"
+            st.store.delete_game(chat_id);
+            fill(
+                tr(lang, Text::GameWon),
+                &[
+                    ("secret", secret.to_string()),
+                    ("name", name),
+                    ("attempts", attempts.to_string()),
+                    ("max", max.to_string()),
+                ],
             )
         } else if attempts >= max {
-            st.games.remove(&chat_id);
-            format!(
-                "💀 *Game over!* You used all {max} attempts.\nThe number was *{secret}*. Better luck next time!"
-            )
+            st.store.delete_game(chat_id);
+            fill(tr(lang, Text::GameLost), &[("secret", secret.to_string()), ("max", max.to_string())])
         } else {
-            let hint = if guess < secret { "📈 Too low!" } else { "📉 Too high!" };
+            st.store.save_game(chat_id, &game);
+            let hint = tr(lang, if guess < secret { Text::TooLow } else { Text::TooHigh });
             let remaining = max - attempts;
             let bar_fill = "█".repeat(attempts as usize);
             let bar_empty = "░".repeat(remaining as usize);
-            format!(
-                "{hint} Guess {attempts}/{max}\n`[{bar_fill}{bar_empty}]`\n\n{remaining} attempt(s) left. `/guess <number>`"
-            )
+            let count = fill(tr(lang, Text::GuessCount), &[("attempts", attempts.to_string()), ("max", max.to_string())]);
+            let left = fill(tr(lang, Text::AttemptsLeft), &[("remaining", remaining.to_string())]);
+            format!("{hint} {count}\n`[{bar_fill}{bar_empty}]`\n\n{left}")
         }
     };
 
@@ -125,19 +132,23 @@ pub async fn cmd_guess(bot: &Bot, msg: &Message, args: &str, state: &SharedState
 pub async fn cmd_giveup(bot: &Bot, msg: &Message, state: &SharedState) {
     let chat_id = msg.chat.id;
 
-    match state.lock().await.games.remove(&chat_id) {
+    let (removed, lang) = {
+        let mut st = state.lock().await;
+        (st.store.delete_game(chat_id), st.store.chat_settings(chat_id).language)
+    };
+    match removed {
         Some(game) => {
             let _ = bot
                 .send_message(
                     chat_id,
-                    format!("🏳️ Game over! The number was *{}*.", game.secret),
+                    fill(tr(lang, Text::GaveUp), &[("secret", game.secret.to_string())]),
                     Some(SendMessageParams::new().parse_mode("Markdown")),
                 )
                 .await;
         }
         None => {
             let _ = bot
-                .send_message(chat_id, "❌ No game is currently running.", None)
+                .send_message(chat_id, tr(lang, Text::NoGame), None)
                 .await;
         }
     }
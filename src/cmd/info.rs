@@ -1,6 +1,7 @@
 use tgbotrs::{Bot, Message, InlineKeyboardMarkup};
 use tgbotrs::gen_methods::{EditMessageTextParams, SendMessageParams};
 use crate::kb::{btn, url_btn, kb};
+use crate::registry::{self, Category};
 
 // ─── /start ───────────────────────────────────────────────────────────────────
 
@@ -11,7 +12,7 @@ pub async fn cmd_start(bot: &Bot, msg: &Message) {
         format!(
             "👋 Hello, <b>{name}</b>!\n\n\
              I'm a <b>multi-purpose bot</b> powered by \
-             <a href=\"https://github.com/ankit-chaubey/tgbotrs\">tgbotrs v0.1.4</a> 🦀\n\n\
+             <a href=\"https://github.com/ankit-chaubey/tgbotrs\">tgbotrs v0.1.7</a> 🦀\n\n\
              I can help with fun, utilities, group admin, games, notes, polls and more!\n\n\
              📦 <a href=\"https://crates.io/crates/tgbotrs\">crates.io</a>  \
              📖 <a href=\"https://docs.rs/tgbotrs\">docs.rs</a>  \
@@ -61,101 +62,33 @@ pub async fn send_help_section(bot: &Bot, chat_id: i64, message_id: i64, section
 }
 
 fn help_kb(section: &str) -> tgbotrs::ReplyMarkup {
-    let nav = vec![
-        vec![btn("🎉 Fun", "help:fun"),     btn("🔧 Utility", "help:util")],
-        vec![btn("👮 Admin", "help:admin"), btn("🎮 Games", "help:games")],
-        vec![btn("📝 Notes", "help:notes"), btn("📊 Polls", "help:polls")],
-        vec![btn("ℹ️ Info", "help:info")],
-    ];
-    match section {
-        "main" => kb(nav),
-        _ => {
-            let mut rows = nav;
-            rows.push(vec![btn("⬅️ Back", "help:main")]);
-            kb(rows)
-        }
+    let mut rows: Vec<_> = Category::ALL
+        .chunks(2)
+        .map(|pair| pair.iter().map(|c| btn(c.button(), &format!("help:{}", c.key()))).collect())
+        .collect();
+    if section != "main" {
+        rows.push(vec![btn("⬅️ Back", "help:main")]);
     }
+    kb(rows)
 }
 
-fn help_content(section: &str) -> (&'static str, &'static str) {
-    match section {
-        "fun"   => ("🎉 <b>Fun Commands</b>",
-            "/dice — Animated Telegram dice 🎲\n\
-             /roll [N] — Roll N-sided die (default d6)\n\
-             /flip — Flip a coin 🪙\n\
-             /joke — Random programming joke 😂\n\
-             /quote — Inspiring developer quote 💭\n\
-             /fact — Random tech/programming fact 🤓\n\
-             /8ball &lt;question&gt; — Magic 8-ball 🎱\n\
-             /rps — Rock Paper Scissors (inline buttons)\n\
-             /choose &lt;a&gt; | &lt;b&gt; | &lt;c&gt; — Pick randomly from options\n\
-             /rate &lt;anything&gt; — Rate something out of 10\n\
-             /password [length] — Secure random password\n\
-             /mock &lt;text&gt; — aLtErNaTiNg CaSe\n\
-             /clap &lt;text&gt; — Add 👏 between words\n\
-             /shrug — ¯\\_(ツ)_/¯\n\
-             /tableflip — (╯°□°）╯︵ ┻━┻\n\
-             /unflip — ┬─┬ノ( º _ ºノ)"),
-
-        "util"  => ("🔧 <b>Utility Commands</b>",
-            "/echo &lt;text&gt; — Echo text\n\
-             /reverse &lt;text&gt; — Reverse text\n\
-             /upper &lt;text&gt; — UPPERCASE\n\
-             /lower &lt;text&gt; — lowercase\n\
-             /count &lt;text&gt; — Count chars/words/lines\n\
-             /calc &lt;expr&gt; — Calculator (+−×÷^ with sqrt/abs/floor/ceil/round)\n\
-             /b64 encode/decode &lt;text&gt; — Base64\n\
-             /repeat &lt;N&gt; &lt;text&gt; — Repeat N times (max 10)\n\
-             /ascii &lt;text&gt; — ASCII codes of characters\n\
-             /binary &lt;text&gt; — Text to binary\n\
-             /time — Current UTC time + unix timestamp\n\
-             /id — Your Telegram ID (reply to see another user's)"),
-
-        "admin" => ("👮 <b>Admin Commands</b> <i>(groups only, reply to a user)</i>",
-            "/ban — Permanently ban user\n\
-             /kick — Kick (ban + immediate unban)\n\
-             /mute — Remove all send permissions\n\
-             /unmute — Restore all send permissions\n\
-             /warn [reason] — Warn user (auto-bans at 3 warnings)\n\
-             /warns — Check user's warning count &amp; reasons\n\
-             /clearwarns — Clear all user's warnings\n\
-             /pin — Pin replied message\n\
-             /unpin — Unpin latest pinned message\n\
-             /del — Delete replied message\n\
-             /promote — Grant admin rights\n\
-             /demote — Remove admin rights\n\
-             /members — Show member count\n\
-             /invite — Generate new invite link"),
-
-        "games" => ("🎮 <b>Game Commands</b>",
-            "/guess — Start a number guessing game (1–100, 7 attempts)\n\
-             /guess &lt;number&gt; — Make a guess\n\
-             /giveup — Reveal the number and end the game"),
-
-        "notes" => ("📝 <b>Notes Commands</b>",
-            "/save &lt;name&gt; &lt;content&gt; — Save a note\n\
-             /get &lt;name&gt; — Retrieve a note\n\
-             /notes — List all notes in this chat\n\
-             /delnote &lt;name&gt; — Delete a note"),
-
-        "polls" => ("📊 <b>Poll Commands</b>",
-            "/poll &lt;question&gt; | &lt;opt1&gt; | &lt;opt2&gt; ... — Create a poll\n\
-             /quiz &lt;question&gt; | &lt;correct&gt; | &lt;wrong1&gt; ... — Create a quiz\n\
-             <i>Separate everything with</i> <code>|</code>"),
-
-        "info" | "about" => ("ℹ️ <b>Info Commands</b>",
-            "/start — Welcome screen with links\n\
-             /help — Interactive help menu\n\
-             /about — About this bot &amp; library\n\
-             /ping — Check bot response time\n\
-             /id — Your user/chat ID\n\
-             /userinfo — User info (reply to see another's)\n\
-             /chatinfo — Current chat info\n\
-             /members — Member count\n\
-             /source — Library source links"),
-
-        _ => ("📚 <b>Command Categories</b>",
-            "Pick a category below to browse commands."),
+/// Built from the command registry, so every registered command shows up here.
+fn help_content(section: &str) -> (String, String) {
+    let section = if section == "about" { "info" } else { section };
+    match Category::from_key(section) {
+        Some(category) => {
+            let mut body = registry::in_category(category)
+                .map(|c| c.help_line())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Some(footer) = category.footer() {
+                body.push('\n');
+                body.push_str(footer);
+            }
+            (category.title().to_string(), body)
+        }
+        None => ("📚 <b>Command Categories</b>".to_string(),
+            "Pick a category below to browse commands.".to_string()),
     }
 }
 
@@ -165,7 +98,7 @@ pub async fn cmd_about(bot: &Bot, msg: &Message) {
     let _ = bot.send_message(
         msg.chat.id,
         "🤖 <b>Multi-Purpose Bot v0.2.0</b>\n\n\
-         Built with <code>tgbotrs v0.1.4</code> — a fully auto-generated \
+         Built with <code>tgbotrs v0.1.7</code> — a fully auto-generated \
          Rust Telegram Bot API library.\n\n\
          <b>Library details:</b>\n\
          • 285 types · 165 methods\n\
@@ -199,7 +132,7 @@ pub async fn cmd_source(bot: &Bot, msg: &Message) {
          💻 <a href=\"https://github.com/ankit-chaubey/tgbotrs\">github.com/ankit-chaubey/tgbotrs</a>\n\
          📖 <a href=\"https://docs.rs/tgbotrs\">docs.rs/tgbotrs</a>\n\
          🌐 <a href=\"https://core.telegram.org/bots/api\">Telegram Bot API spec</a>\n\n\
-         <code>tgbotrs = { version = \"0.1.4\" }</code>",
+         <code>tgbotrs = { version = \"0.1.7\" }</code>",
         Some(SendMessageParams::new()
             .parse_mode("HTML")
             .reply_markup(kb(vec![vec![
@@ -0,0 +1,163 @@
+//! Content locks: `/lock stickers` and friends make the bot delete every message of that
+//! kind from members who aren't admins or approved.
+//!
+//! Everything is enforced by inspecting messages rather than through `ChatPermissions`,
+//! so locks never fight with the chat's own permissions or with restrictions on members,
+//! and the same exemption for admins and `/approve`d users applies to all of them.
+
+use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use tgbotrs::{Bot, Message};
+use tgbotrs::gen_methods::SendMessageParams;
+use crate::cmd::admin::is_exempt;
+use crate::cmd::settings;
+use crate::state::SharedState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lock {
+    /// Links, written out or hidden behind text
+    Url,
+    Sticker,
+    Gif,
+    Photo,
+    /// Videos and round video notes
+    Video,
+    Voice,
+    Forward,
+    /// Messages sent through an inline bot (`@gif cats`)
+    Inline,
+    Poll,
+    /// Messages members post as one of their channels
+    Channel,
+}
+
+impl Lock {
+    /// Order of the `/locks` list
+    pub const ALL: [Lock; 10] = [
+        Lock::Url, Lock::Sticker, Lock::Gif, Lock::Photo, Lock::Video,
+        Lock::Voice, Lock::Forward, Lock::Inline, Lock::Poll, Lock::Channel,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lock::Url     => "url",
+            Lock::Sticker => "sticker",
+            Lock::Gif     => "gif",
+            Lock::Photo   => "photo",
+            Lock::Video   => "video",
+            Lock::Voice   => "voice",
+            Lock::Forward => "forward",
+            Lock::Inline  => "inline",
+            Lock::Poll    => "poll",
+            Lock::Channel => "channel",
+        }
+    }
+
+    /// A [`name`](Lock::name), its plural, or a common alias like `links`
+    pub fn parse(text: &str) -> Option<Lock> {
+        let text = text.to_lowercase();
+        let singular = text.strip_suffix('s').unwrap_or(&text);
+        match singular {
+            "link" | "url"      => Some(Lock::Url),
+            "via" | "inlinebot" => Some(Lock::Inline),
+            _ => Lock::ALL.into_iter().find(|lock| lock.name() == singular),
+        }
+    }
+
+    /// Whether `msg` is the kind of message this lock forbids
+    pub fn forbids(self, msg: &Message) -> bool {
+        match self {
+            Lock::Url => msg.entities.iter().chain(&msg.caption_entities).flatten()
+                .any(|e| e.r#type == "url" || e.r#type == "text_link"),
+            Lock::Sticker => msg.sticker.is_some(),
+            Lock::Gif     => msg.animation.is_some(),
+            Lock::Photo   => msg.photo.is_some(),
+            Lock::Video   => msg.video.is_some() || msg.video_note.is_some(),
+            Lock::Voice   => msg.voice.is_some(),
+            // A linked channel's posts are forwarded into its discussion group automatically
+            Lock::Forward => msg.forward_origin.is_some() && msg.is_automatic_forward != Some(true),
+            Lock::Inline  => msg.via_bot.is_some(),
+            Lock::Poll    => msg.poll.is_some(),
+            Lock::Channel => msg.sender_chat.as_ref().is_some_and(|c| c.id != msg.chat.id)
+                && msg.is_automatic_forward != Some(true),
+        }
+    }
+}
+
+// ─── Checking messages ────────────────────────────────────────────────────────
+
+/// Deletes `msg` if it breaks one of the chat's locks. Returns `true` when it did, so the
+/// caller stops handling the message.
+pub async fn check(bot: &Bot, msg: &Message, state: &SharedState) -> bool {
+    let chat_id = msg.chat.id;
+    let locks = state.lock().await.store.chat_settings(chat_id).locks;
+    let Some(lock) = locks.into_iter().find(|lock| lock.forbids(msg)) else { return false };
+    // Anonymous admins post as the group itself
+    if msg.sender_chat.as_ref().is_some_and(|c| c.id == chat_id) {
+        return false;
+    }
+    let Some(from) = msg.from.as_deref() else { return false };
+    // Only asked once a lock is hit, so ordinary messages cost no API calls
+    if lock != Lock::Channel && is_exempt(bot, chat_id, from.id, state).await {
+        return false;
+    }
+    if let Err(e) = bot.delete_message(chat_id, msg.message_id).await {
+        eprintln!("⚠️   Locks: couldn't delete a {} message in {chat_id}: {e}", lock.name());
+    }
+    true
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+fn usage(command: &str) -> String {
+    let names: Vec<&str> = Lock::ALL.iter().map(|lock| lock.name()).collect();
+    format!("Usage: /{command} <type…|all>\nTypes: {}", names.join(", "))
+}
+
+/// The lock types named in `args`, or `None` if any of them isn't one.
+fn parse_locks(args: &str) -> Option<Vec<Lock>> {
+    if args.trim().eq_ignore_ascii_case("all") {
+        return Some(Lock::ALL.to_vec());
+    }
+    let locks: Option<Vec<Lock>> = args.split([' ', ',']).filter(|w| !w.is_empty()).map(Lock::parse).collect();
+    locks.filter(|l| !l.is_empty())
+}
+
+fn names(locks: &[Lock]) -> String {
+    locks.iter().map(|lock| lock.name()).collect::<Vec<_>>().join(", ")
+}
+
+pub async fn cmd_lock(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let reply = match parse_locks(args) {
+        Some(locks) => {
+            settings::update(state, msg.chat.id, |s| s.locks.extend(&locks)).await;
+            format!("🔒 Locked: {}. Messages like that from non-admins will be deleted.", names(&locks))
+        }
+        None => usage("lock"),
+    };
+    let _ = bot.send_message(msg.chat.id, reply, None).await;
+}
+
+pub async fn cmd_unlock(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let reply = match parse_locks(args) {
+        Some(locks) => {
+            settings::update(state, msg.chat.id, |s| s.locks.retain(|lock| !locks.contains(lock))).await;
+            format!("🔓 Unlocked: {}.", names(&locks))
+        }
+        None => usage("unlock"),
+    };
+    let _ = bot.send_message(msg.chat.id, reply, None).await;
+}
+
+pub async fn cmd_locks(bot: &Bot, msg: &Message, state: &SharedState) {
+    let locks: BTreeSet<Lock> = state.lock().await.store.chat_settings(msg.chat.id).locks;
+    let lines: Vec<String> = Lock::ALL.iter()
+        .map(|lock| {
+            let icon = if locks.contains(lock) { "🔒" } else { "🔓" };
+            format!("{icon} <code>{}</code>", lock.name())
+        })
+        .collect();
+    let text = format!("<b>Locks in this chat</b>\n{}", lines.join("\n"));
+    let _ = bot.send_message(msg.chat.id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
@@ -1,7 +1,19 @@
 pub mod admin;
+pub mod antiflood;
+pub mod approvals;
+pub mod blocklist;
+pub mod captcha;
+pub mod federation;
 pub mod fun;
 pub mod games;
 pub mod info;
+pub mod locks;
+pub mod nightmode;
 pub mod notes;
 pub mod polls;
+pub mod promote;
+pub mod reports;
+pub mod restrict;
+pub mod settings;
 pub mod util;
+pub mod welcome;
@@ -0,0 +1,248 @@
+//! The chat's default permissions (`/setperms`) and night mode, which locks the chat — or
+//! just media — between set hours and unlocks it afterwards.
+//!
+//! Timezones are fixed UTC offsets like `UTC+2` or `+05:30`, so chats that observe
+//! daylight saving need to adjust theirs twice a year. [`sync`] is run for every chat by
+//! `scheduler` once a minute; whether the chat is currently locked is kept in
+//! `ChatSettings::night_active`, so a restart mid-night still unlocks it in the morning.
+
+use std::collections::BTreeSet;
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tgbotrs::{Bot, BotError, Message};
+use tgbotrs::gen_methods::SetChatPermissionsParams;
+use crate::cmd::admin::all_perms;
+use crate::cmd::restrict::{names, permissions, Perm};
+use crate::cmd::settings;
+use crate::state::{ChatSettings, SharedState};
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NightScope {
+    /// Nobody can send anything
+    All,
+    /// Only `Perm::MEDIA` is taken away
+    Media,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NightMode {
+    /// Local time it starts, in minutes after midnight
+    pub start: u32,
+    /// Local time it ends; earlier than `start` when it runs past midnight
+    pub end: u32,
+    /// Minutes ahead of UTC
+    pub utc_offset: i32,
+    pub scope: NightScope,
+}
+
+impl NightMode {
+    /// `23:00-07:00 [UTC+2] [all|media]`. `Err` is the message for the admin.
+    pub fn parse(args: &str) -> Result<NightMode, String> {
+        let mut words = args.split_whitespace();
+        let hours = words.next().unwrap_or_default();
+        let (start, end) = hours.split_once('-')
+            .and_then(|(s, e)| Some((parse_clock(s)?, parse_clock(e)?)))
+            .ok_or("❌ Give the hours like 23:00-07:00.")?;
+        if start == end {
+            return Err("❌ Night mode has to start and end at different times.".into());
+        }
+        let mut night = NightMode { start, end, utc_offset: 0, scope: NightScope::All };
+        for word in words {
+            match word.to_lowercase().as_str() {
+                "all"   => night.scope = NightScope::All,
+                "media" => night.scope = NightScope::Media,
+                other => night.utc_offset = parse_offset(other)
+                    .ok_or(format!("❌ Unknown timezone: {word}. Use a UTC offset like UTC+2, UTC-5 or +05:30."))?,
+            }
+        }
+        Ok(night)
+    }
+
+    /// Whether `now` falls between `start` (included) and `end` (excluded), local time
+    pub fn is_night(&self, now: DateTime<Utc>) -> bool {
+        let utc = (now.hour() * 60 + now.minute()) as i32;
+        let local = (utc + self.utc_offset).rem_euclid(MINUTES_PER_DAY) as u32;
+        if self.start < self.end {
+            (self.start..self.end).contains(&local)
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+
+    /// "23:00–07:00 UTC+02:00"
+    pub fn describe(&self) -> String {
+        format!("{}–{} {}", clock(self.start), clock(self.end), offset(self.utc_offset))
+    }
+}
+
+/// `"7:30"` → 450
+fn parse_clock(text: &str) -> Option<u32> {
+    let (h, m) = text.split_once(':').unwrap_or((text, "0"));
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+/// `"UTC+5:30"`, `"gmt-3"`, `"+02:00"`, `"utc"` → minutes ahead of UTC
+fn parse_offset(text: &str) -> Option<i32> {
+    let rest = text.strip_prefix("utc").or_else(|| text.strip_prefix("gmt")).unwrap_or(text);
+    if rest.is_empty() {
+        return Some(0);
+    }
+    let (sign, rest) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (h, m) = rest.split_once(':').unwrap_or((rest, "0"));
+    let (h, m): (i32, i32) = (h.parse().ok()?, m.parse().ok()?);
+    let minutes = sign * (h * 60 + m);
+    ((-12 * 60..=14 * 60).contains(&minutes) && m < 60).then_some(minutes)
+}
+
+fn clock(minutes: u32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn offset(minutes: i32) -> String {
+    if minutes == 0 {
+        return "UTC".to_string();
+    }
+    let sign = if minutes < 0 { '-' } else { '+' };
+    format!("UTC{sign}{:02}:{:02}", minutes.abs() / 60, minutes.abs() % 60)
+}
+
+// ─── Applying permissions ─────────────────────────────────────────────────────
+
+async fn set_permissions(bot: &Bot, chat_id: i64, perms: tgbotrs::ChatPermissions) -> Result<(), BotError> {
+    let params = SetChatPermissionsParams::new().use_independent_chat_permissions(true);
+    bot.set_chat_permissions(chat_id, perms, Some(params)).await.map(|_| ())
+}
+
+/// The chat's permissions outside night mode
+async fn unlock(bot: &Bot, chat_id: i64, settings: &ChatSettings) -> Result<(), BotError> {
+    set_permissions(bot, chat_id, permissions(&settings.default_denied)).await
+}
+
+async fn lock(bot: &Bot, chat_id: i64, settings: &ChatSettings, night: &NightMode) -> Result<(), BotError> {
+    let perms = match night.scope {
+        NightScope::All   => all_perms(false),
+        NightScope::Media => permissions(&settings.default_denied.iter().copied().chain(Perm::MEDIA).collect()),
+    };
+    set_permissions(bot, chat_id, perms).await
+}
+
+/// Locks or unlocks the chat if night mode started or ended since the last call.
+pub async fn sync(bot: &Bot, state: &SharedState, chat_id: i64, now: DateTime<Utc>) {
+    let settings = state.lock().await.store.chat_settings(chat_id);
+    let night = settings.night_mode.as_ref().filter(|n| n.is_night(now));
+    if night.is_some() == settings.night_active {
+        return;
+    }
+    let (result, text) = match night {
+        Some(n) => {
+            let what = match n.scope {
+                NightScope::All   => "the chat is read-only",
+                NightScope::Media => "media is off",
+            };
+            (lock(bot, chat_id, &settings, n).await, format!("🌙 Night mode — {what} until {} {}.", clock(n.end), offset(n.utc_offset)))
+        }
+        None => (unlock(bot, chat_id, &settings).await, "☀️ Night mode is over — good morning!".to_string()),
+    };
+    match result {
+        Ok(()) => {
+            settings::update(state, chat_id, |s| s.night_active = night.is_some()).await;
+            let _ = bot.send_message(chat_id, text, None).await;
+        }
+        Err(e) => eprintln!("⚠️   Night mode: couldn't change the permissions of {chat_id}: {e}"),
+    }
+}
+
+/// Runs [`sync`] for every chat with saved settings.
+pub async fn tick(bot: &Bot, state: &SharedState, now: DateTime<Utc>) {
+    let chats = state.lock().await.store.configured_chats();
+    for chat_id in chats {
+        sync(bot, state, chat_id, now).await;
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// `/setperms <perm…|all|none>` — what members may send by default.
+pub async fn cmd_setperms(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let words: Vec<&str> = args.split_whitespace().collect();
+    if words.is_empty() {
+        let s = state.lock().await.store.chat_settings(chat_id);
+        let allowed: BTreeSet<Perm> = Perm::ALL.into_iter().filter(|p| !s.default_denied.contains(p)).collect();
+        let _ = bot.send_message(chat_id, format!(
+            "Usage: /setperms <permissions…|all|none> — what members may send\nCurrently: {}",
+            names(&allowed),
+        ), None).await;
+        return;
+    }
+
+    let mut allowed = BTreeSet::new();
+    for word in &words {
+        match Perm::parse(word) {
+            Some(perms) => allowed.extend(perms),
+            None if word.eq_ignore_ascii_case("none") => {}
+            None => {
+                let all = Perm::ALL.map(Perm::name).join(", ");
+                let _ = bot.send_message(chat_id, format!("❌ Unknown permission: {word}. Choose from media, {all}, all or none."), None).await;
+                return;
+            }
+        }
+    }
+    let denied: BTreeSet<Perm> = Perm::ALL.into_iter().filter(|p| !allowed.contains(p)).collect();
+    let settings = state.lock().await.store.chat_settings(chat_id);
+    if settings.night_active {
+        settings::update(state, chat_id, |s| s.default_denied = denied).await;
+        let _ = bot.send_message(chat_id, format!("✅ Members will be able to send {} once night mode ends.", names(&allowed)), None).await;
+        return;
+    }
+    let reply = match set_permissions(bot, chat_id, permissions(&denied)).await {
+        Ok(()) => {
+            settings::update(state, chat_id, |s| s.default_denied = denied).await;
+            format!("✅ Members may send: {}.", names(&allowed))
+        }
+        Err(e) => format!("❌ Failed to set the chat's permissions: {e}"),
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
+
+/// `/nightmode [off | HH:MM-HH:MM [UTC±H] [all|media]]`
+pub async fn cmd_nightmode(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let args = args.trim();
+    let reply = if args.is_empty() {
+        let s = state.lock().await.store.chat_settings(chat_id);
+        let current = match &s.night_mode {
+            Some(n) => format!("{}, locking {}{}", n.describe(),
+                if n.scope == NightScope::All { "everything" } else { "media" },
+                if s.night_active { " — active now" } else { "" }),
+            None => "off".to_string(),
+        };
+        format!("Usage: /nightmode 23:00-07:00 [UTC+2] [all|media], or /nightmode off\nCurrently: {current}")
+    } else if args.eq_ignore_ascii_case("off") {
+        let s = settings::update(state, chat_id, |s| s.night_mode = None).await;
+        if s.night_active {
+            // Unlocks now rather than on the scheduler's next pass
+            sync(bot, state, chat_id, Utc::now()).await;
+        }
+        "✅ Night mode is off.".to_string()
+    } else {
+        match NightMode::parse(args) {
+            Ok(night) => {
+                let what = if night.scope == NightScope::All { "everything" } else { "media" };
+                let text = format!("✅ Night mode: {what} is locked {} every night.", night.describe());
+                settings::update(state, chat_id, |s| s.night_mode = Some(night)).await;
+                text
+            }
+            Err(text) => text,
+        }
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
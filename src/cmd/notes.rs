@@ -1,14 +1,30 @@
 use tgbotrs::{Bot, Message};
 use tgbotrs::gen_methods::SendMessageParams;
+use crate::cmd::admin::is_admin;
 use crate::state::SharedState;
 
+/// `/save` and `/delnote` are open to everyone unless the chat's settings restrict them.
+async fn can_edit_notes(bot: &Bot, msg: &Message, state: &SharedState) -> bool {
+    let admin_only = state.lock().await.store.chat_settings(msg.chat.id).notes_admin_only;
+    if !admin_only {
+        return true;
+    }
+    let Some(user_id) = msg.from.as_ref().map(|u| u.id) else { return false };
+    if is_admin(bot, msg.chat.id, user_id, state).await {
+        return true;
+    }
+    let _ = bot.send_message(msg.chat.id, "❌ Only admins can change notes in this chat.", None).await;
+    false
+}
+
 pub async fn cmd_save(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
     let chat_id = msg.chat.id;
+    if !can_edit_notes(bot, msg, state).await { return; }
     let parts: Vec<&str> = args.splitn(2, ' ').collect();
     match parts.as_slice() {
         [name, content] if !name.trim().is_empty() && !content.trim().is_empty() => {
-            let key = (chat_id, name.trim().to_lowercase());
-            state.lock().await.notes.insert(key, content.trim().to_string());
+            let key = name.trim().to_lowercase();
+            state.lock().await.store.save_note(chat_id, &key, content.trim());
             let _ = bot.send_message(
                 chat_id,
                 format!("📝 Note *{}* saved!", name.trim()),
@@ -32,8 +48,8 @@ pub async fn cmd_get(bot: &Bot, msg: &Message, args: &str, state: &SharedState)
         let _ = bot.send_message(chat_id, "Usage: `/get <name>`", Some(SendMessageParams::new().parse_mode("Markdown"))).await;
         return;
     }
-    let st = state.lock().await;
-    match st.notes.get(&(chat_id, name.clone())) {
+    let note = state.lock().await.store.note(chat_id, &name);
+    match note {
         Some(content) => {
             let _ = bot.send_message(
                 chat_id,
@@ -53,12 +69,9 @@ pub async fn cmd_get(bot: &Bot, msg: &Message, args: &str, state: &SharedState)
 
 pub async fn cmd_notes(bot: &Bot, msg: &Message, state: &SharedState) {
     let chat_id = msg.chat.id;
-    let st      = state.lock().await;
-    let mut notes: Vec<String> = st.notes.keys()
-        .filter(|(cid, _)| *cid == chat_id)
-        .map(|(_, name)| format!("• <code>{name}</code>"))
+    let notes: Vec<String> = state.lock().await.store.note_names(chat_id).iter()
+        .map(|name| format!("• <code>{name}</code>"))
         .collect();
-    notes.sort();
     if notes.is_empty() {
         let _ = bot.send_message(
             chat_id,
@@ -77,13 +90,14 @@ pub async fn cmd_notes(bot: &Bot, msg: &Message, state: &SharedState) {
 
 pub async fn cmd_delnote(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
     let chat_id = msg.chat.id;
+    if !can_edit_notes(bot, msg, state).await { return; }
     let name    = args.trim().to_lowercase();
     if name.is_empty() {
         let _ = bot.send_message(chat_id, "Usage: `/delnote <name>`", Some(SendMessageParams::new().parse_mode("Markdown"))).await;
         return;
     }
-    let removed = state.lock().await.notes.remove(&(chat_id, name.clone()));
-    if removed.is_some() {
+    let removed = state.lock().await.store.delete_note(chat_id, &name);
+    if removed {
         let _ = bot.send_message(
             chat_id,
             format!("🗑️ Note `{name}` deleted."),
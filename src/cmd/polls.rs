@@ -43,7 +43,7 @@ pub async fn cmd_quiz(bot: &Bot, msg: &Message, args: &str) {
         .collect();
     let params = SendPollParams::new()
         .r#type("quiz".to_string())
-        .correct_option_id(0i64)
+        .correct_option_ids(vec![0i64])
         .is_anonymous(false);
     if let Err(e) = bot.send_poll(chat_id, question, options, Some(params)).await {
         let _ = bot.send_message(chat_id, format!("❌ Failed to create quiz: {e}"), None).await;
@@ -0,0 +1,356 @@
+//! `/promote` with chosen rights and a custom title, and `/demote`.
+//!
+//! Rights can be listed after the user (`/promote @bob Helper delete pin`); without them
+//! the promoting admin gets a checklist to toggle. Either way nobody can hand out a right
+//! they don't hold themselves, since the bot would otherwise grant it on their behalf.
+//! Callback data is `pro:<right|ok|cancel>`; open checklists live in
+//! `BotState::promotions` and are not persisted.
+
+use std::collections::BTreeSet;
+use tgbotrs::{Bot, CallbackQuery, InlineKeyboardButton, MaybeInaccessibleMessage, Message, User};
+use tgbotrs::gen_methods::{AnswerCallbackQueryParams, EditMessageTextParams, PromoteChatMemberParams, SendMessageParams};
+use crate::admin_cache::{self, Admin};
+use crate::cmd::admin::{require_target, Target};
+use crate::kb::{btn, kb, markup};
+use crate::modlog::Entry;
+use crate::registry::escape;
+use crate::state::{Action, SharedState};
+
+/// Telegram's limit on custom admin titles
+pub const MAX_TITLE: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Right {
+    ChangeInfo,
+    Delete,
+    Restrict,
+    Invite,
+    Pin,
+    Topics,
+    VideoChats,
+    /// Post, edit and delete stories
+    Stories,
+    /// Add admins with a subset of their own rights
+    Promote,
+}
+
+impl Right {
+    /// Order of the checklist
+    pub const ALL: [Right; 9] = [
+        Right::ChangeInfo, Right::Delete, Right::Restrict, Right::Invite, Right::Pin,
+        Right::Topics, Right::VideoChats, Right::Stories, Right::Promote,
+    ];
+
+    /// Ticked on a new checklist — what `/promote` used to grant
+    pub const DEFAULT: [Right; 5] = [Right::Delete, Right::Restrict, Right::Invite, Right::Pin, Right::VideoChats];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Right::ChangeInfo => "info",
+            Right::Delete     => "delete",
+            Right::Restrict   => "restrict",
+            Right::Invite     => "invite",
+            Right::Pin        => "pin",
+            Right::Topics     => "topics",
+            Right::VideoChats => "videochats",
+            Right::Stories    => "stories",
+            Right::Promote    => "promote",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Right::ChangeInfo => "Change info",
+            Right::Delete     => "Delete messages",
+            Right::Restrict   => "Ban users",
+            Right::Invite     => "Invite users",
+            Right::Pin        => "Pin messages",
+            Right::Topics     => "Manage topics",
+            Right::VideoChats => "Video chats",
+            Right::Stories    => "Stories",
+            Right::Promote    => "Add admins",
+        }
+    }
+
+    /// A [`name`](Right::name) or a common alias like `ban` or `vc`
+    pub fn parse(text: &str) -> Option<Right> {
+        match text.to_lowercase().as_str() {
+            "changeinfo" | "info"             => Some(Right::ChangeInfo),
+            "del" | "delete"                  => Some(Right::Delete),
+            "ban" | "restrict"                => Some(Right::Restrict),
+            "invite" | "invites"              => Some(Right::Invite),
+            "pin" | "pins"                    => Some(Right::Pin),
+            "topic" | "topics"                => Some(Right::Topics),
+            "vc" | "videochat" | "videochats" => Some(Right::VideoChats),
+            "story" | "stories"               => Some(Right::Stories),
+            "promote" | "addadmins"           => Some(Right::Promote),
+            _ => None,
+        }
+    }
+
+    fn held_by(self, admin: &Admin) -> bool {
+        if admin.is_owner() {
+            return true;
+        }
+        match self {
+            Right::ChangeInfo => admin.can_change_info,
+            Right::Delete     => admin.can_delete_messages,
+            Right::Restrict   => admin.can_restrict_members,
+            Right::Invite     => admin.can_invite_users,
+            Right::Pin        => admin.can_pin_messages,
+            Right::Topics     => admin.can_manage_topics,
+            Right::VideoChats => admin.can_manage_video_chats,
+            Right::Stories    => admin.can_post_stories,
+            Right::Promote    => admin.can_promote_members,
+        }
+    }
+}
+
+fn names(rights: &BTreeSet<Right>) -> String {
+    if rights.is_empty() {
+        return "none".to_string();
+    }
+    rights.iter().map(|r| r.name()).collect::<Vec<_>>().join(", ")
+}
+
+/// Every right set explicitly, so promoting an existing admin also takes away what's unticked.
+fn params(rights: &BTreeSet<Right>) -> PromoteChatMemberParams {
+    let has = |right| rights.contains(&right);
+    PromoteChatMemberParams::new()
+        .can_manage_chat(!rights.is_empty())
+        .can_change_info(has(Right::ChangeInfo))
+        .can_delete_messages(has(Right::Delete))
+        .can_restrict_members(has(Right::Restrict))
+        .can_invite_users(has(Right::Invite))
+        .can_pin_messages(has(Right::Pin))
+        .can_manage_topics(has(Right::Topics))
+        .can_manage_video_chats(has(Right::VideoChats))
+        .can_post_stories(has(Right::Stories))
+        .can_edit_stories(has(Right::Stories))
+        .can_delete_stories(has(Right::Stories))
+        .can_promote_members(has(Right::Promote))
+}
+
+/// The rights `user_id` could pass on, or `None` if they can't add admins at all.
+async fn grantable(bot: &Bot, chat_id: i64, user_id: i64, state: &SharedState) -> Option<BTreeSet<Right>> {
+    let admins = admin_cache::admins(bot, chat_id, state).await.ok()?;
+    let admin = admins.iter().find(|a| a.user.id == user_id)?;
+    if !Right::Promote.held_by(admin) {
+        return None;
+    }
+    Some(Right::ALL.into_iter().filter(|r| r.held_by(admin)).collect())
+}
+
+/// `[title] [rights…|all]` — rights are taken off the end, whatever is left is the title.
+/// `None` for the rights means none were listed.
+fn parse_args(args: &str) -> (String, Option<BTreeSet<Right>>) {
+    let mut words: Vec<&str> = args.split_whitespace().collect();
+    let mut rights = BTreeSet::new();
+    let mut all = false;
+    while let Some(&last) = words.last() {
+        if last.eq_ignore_ascii_case("all") {
+            all = true;
+        } else if let Some(right) = Right::parse(last) {
+            rights.insert(right);
+        } else {
+            break;
+        }
+        words.pop();
+    }
+    if all {
+        rights.extend(Right::ALL);
+    }
+    (words.join(" "), (!rights.is_empty()).then_some(rights))
+}
+
+/// A checklist waiting for its admin to press Promote.
+#[derive(Debug, Clone)]
+pub struct PendingPromotion {
+    /// The `/promote` command, for the moderation log
+    pub command: Message,
+    pub target: User,
+    pub title: String,
+    /// Rights the promoting admin holds, so the only ones offered
+    pub offered: BTreeSet<Right>,
+    pub ticked: BTreeSet<Right>,
+}
+
+fn checklist_text(p: &PendingPromotion) -> String {
+    let mut text = format!("⬆️ Promoting <b>{}</b>", escape(&p.target.first_name));
+    if !p.title.is_empty() {
+        text.push_str(&format!(" as <i>{}</i>", escape(&p.title)));
+    }
+    text.push_str("\nTick the rights to give, then press Promote.");
+    text
+}
+
+fn checklist_rows(p: &PendingPromotion) -> Vec<Vec<InlineKeyboardButton>> {
+    let toggles: Vec<InlineKeyboardButton> = p.offered.iter()
+        .map(|r| {
+            let icon = if p.ticked.contains(r) { "✅" } else { "▫️" };
+            btn(&format!("{icon} {}", r.label()), &format!("pro:{}", r.name()))
+        })
+        .collect();
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = toggles.chunks(2).map(<[_]>::to_vec).collect();
+    rows.push(vec![btn("⬆️ Promote", "pro:ok"), btn("✖️ Cancel", "pro:cancel")]);
+    rows
+}
+
+// ─── Promoting ────────────────────────────────────────────────────────────────
+
+/// Promotes `target` and sets their title; returns the announcement, or what went wrong.
+async fn promote(
+    bot: &Bot, command: &Message, admin: &User, target: &User, title: &str, rights: &BTreeSet<Right>, state: &SharedState,
+) -> String {
+    let chat_id = command.chat.id;
+    if let Err(e) = bot.promote_chat_member(chat_id, target.id, Some(params(rights))).await {
+        return format!("❌ Failed to promote: {}", escape(&e.to_string()));
+    }
+    state.lock().await.admins.invalidate(chat_id);
+    let mut text = format!("⬆️ <b>{}</b> promoted to admin.\nRights: {}", escape(&target.first_name), names(rights));
+    if !title.is_empty() {
+        match bot.set_chat_administrator_custom_title(chat_id, target.id, title).await {
+            Ok(_)  => text.push_str(&format!("\nTitle: <i>{}</i>", escape(title))),
+            Err(e) => text.push_str(&format!("\n❌ Couldn't set the title: {}", escape(&e.to_string()))),
+        }
+    }
+    let reason = format!("Rights: {}", names(rights));
+    Entry::new(Action::Promote).by(admin).target(target).reason(&reason).record(bot, state, command).await;
+    text
+}
+
+pub async fn cmd_promote(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, rest }) = require_target(bot, msg, args, state).await else { return };
+    let Some(admin) = msg.from.as_deref() else { return };
+    let html = || SendMessageParams::new().parse_mode("HTML");
+
+    let Some(offered) = grantable(bot, chat_id, admin.id, state).await else {
+        let _ = bot.send_message(chat_id, "❌ You need the right to add new admins to promote anyone.", None).await;
+        return;
+    };
+    let (title, rights) = parse_args(&rest);
+    if title.chars().count() > MAX_TITLE {
+        let _ = bot.send_message(chat_id, format!("❌ Admin titles can be at most {MAX_TITLE} characters."), None).await;
+        return;
+    }
+
+    if let Some(rights) = rights {
+        let missing: BTreeSet<Right> = rights.difference(&offered).copied().collect();
+        let text = if missing.is_empty() {
+            promote(bot, msg, admin, &target, &title, &rights, state).await
+        } else {
+            format!("❌ You can't grant rights you don't have: {}.", names(&missing))
+        };
+        let _ = bot.send_message(chat_id, text, Some(html())).await;
+        return;
+    }
+
+    let ticked = Right::DEFAULT.into_iter().filter(|r| offered.contains(r)).collect();
+    let pending = PendingPromotion { command: msg.clone(), target: *target, title, offered, ticked };
+    let params = html().reply_markup(kb(checklist_rows(&pending)));
+    if let Ok(sent) = bot.send_message(chat_id, checklist_text(&pending), Some(params)).await {
+        state.lock().await.promotions.insert((chat_id, sent.message_id), pending);
+    }
+}
+
+/// Handles `pro:<right|ok|cancel>` presses; answers the callback itself.
+pub async fn on_callback(bot: &Bot, cbq: &CallbackQuery, action: &str, state: &SharedState) {
+    let reply = |text: &str, alert: bool| {
+        let params = AnswerCallbackQueryParams::new().text(text).show_alert(alert);
+        bot.answer_callback_query(&cbq.id, Some(params))
+    };
+    let ack = || bot.answer_callback_query(&cbq.id, Some(AnswerCallbackQueryParams::new()));
+    let Some(MaybeInaccessibleMessage::Message(m)) = cbq.message.as_deref() else {
+        let _ = reply("This checklist has expired.", true).await;
+        return;
+    };
+    let key = (m.chat.id, m.message_id);
+    let Some(pending) = state.lock().await.promotions.get(&key).cloned() else {
+        let _ = reply("This checklist has expired.", true).await;
+        return;
+    };
+    if pending.command.from.as_ref().is_none_or(|u| u.id != cbq.from.id) {
+        let _ = reply("Only the admin who started this can use it.", true).await;
+        return;
+    }
+    let edit = |text: String, rows: Option<Vec<Vec<InlineKeyboardButton>>>| {
+        let mut params = EditMessageTextParams::new().chat_id(key.0).message_id(key.1).parse_mode("HTML");
+        if let Some(rows) = rows {
+            params = params.reply_markup(Box::new(markup(rows)));
+        }
+        bot.edit_message_text(text, Some(params))
+    };
+
+    match action {
+        "cancel" => {
+            state.lock().await.promotions.remove(&key);
+            let _ = reply("Cancelled", false).await;
+            let _ = edit("✖️ Promotion cancelled.".to_string(), None).await;
+        }
+        "ok" if pending.ticked.is_empty() => {
+            let _ = reply("Tick at least one right.", true).await;
+        }
+        "ok" => {
+            // Rights may have changed since the checklist was posted
+            let offered = grantable(bot, key.0, cbq.from.id, state).await.unwrap_or_default();
+            let missing: BTreeSet<Right> = pending.ticked.difference(&offered).copied().collect();
+            if !missing.is_empty() {
+                let _ = reply(&format!("You can't grant rights you don't have: {}.", names(&missing)), true).await;
+                return;
+            }
+            state.lock().await.promotions.remove(&key);
+            let _ = ack().await;
+            let text = promote(bot, &pending.command, &cbq.from, &pending.target, &pending.title, &pending.ticked, state).await;
+            let _ = edit(text, None).await;
+        }
+        name => {
+            let Some(right) = Right::parse(name).filter(|r| pending.offered.contains(r)) else {
+                let _ = ack().await;
+                return;
+            };
+            let updated = {
+                let mut st = state.lock().await;
+                let Some(p) = st.promotions.get_mut(&key) else { return };
+                if !p.ticked.remove(&right) {
+                    p.ticked.insert(right);
+                }
+                p.clone()
+            };
+            let _ = ack().await;
+            let _ = edit(checklist_text(&updated), Some(checklist_rows(&updated))).await;
+        }
+    }
+}
+
+// ─── Demoting ─────────────────────────────────────────────────────────────────
+
+/// Needs the same right to add admins as `/promote`; the owner and the caller themself
+/// can't be demoted.
+pub async fn cmd_demote(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, .. }) = require_target(bot, msg, args, state).await else { return };
+    let Some(admin) = msg.from.as_deref() else { return };
+    let refusal = if grantable(bot, chat_id, admin.id, state).await.is_none() {
+        Some("❌ You need the right to add new admins to demote anyone.")
+    } else if target.id == admin.id {
+        Some("❌ You can't demote yourself.")
+    } else if admin_cache::admins(bot, chat_id, state).await
+        .is_ok_and(|admins| admins.iter().any(|a| a.user.id == target.id && a.is_owner()))
+    {
+        Some("❌ The chat's owner can't be demoted.")
+    } else {
+        None
+    };
+    if let Some(text) = refusal {
+        let _ = bot.send_message(chat_id, text, None).await;
+        return;
+    }
+    let name = &target.first_name;
+    match bot.promote_chat_member(chat_id, target.id, Some(params(&BTreeSet::new()))).await {
+        Ok(_)  => {
+            state.lock().await.admins.invalidate(chat_id);
+            let _ = bot.send_message(chat_id, format!("⬇️ *{name}* demoted."), Some(SendMessageParams::new().parse_mode("Markdown"))).await;
+            Entry::new(Action::Demote).target(&target).record(bot, state, msg).await;
+        }
+        Err(e) => { let _ = bot.send_message(chat_id, format!("❌ Failed to demote: {e}"), None).await; }
+    }
+}
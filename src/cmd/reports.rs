@@ -0,0 +1,260 @@
+//! Reports: members reply to a message with `/report [reason]` or a mention of `@admin`,
+//! and the bot pings the chat's admins with buttons to act on it.
+//!
+//! The notice goes to the group, and also by DM to admins who turned that on with
+//! `/reports dm on`. Callback data is `rep:<chat_id>:<message_id>:<action>`; the chat comes
+//! from the data because DM'd notices aren't in the group. Open reports live in
+//! `BotState::reports` and are not persisted.
+
+use tgbotrs::{Bot, CallbackQuery, InlineKeyboardButton, Message, User};
+use tgbotrs::gen_methods::{AnswerCallbackQueryParams, EditMessageTextParams, SendMessageParams};
+use crate::admin_cache;
+use crate::cmd::admin::{self, is_admin};
+use crate::cmd::settings;
+use crate::kb::{btn, kb};
+use crate::modlog::{message_link, Entry};
+use crate::punish::Punishment;
+use crate::registry::escape;
+use crate::state::{Action, SharedState};
+
+/// Open reports are forgotten after this long, buttons and all
+const REPORT_TTL_SECS: i64 = 24 * 3600;
+
+/// A reported message waiting for an admin to act.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The offending message
+    pub message: Message,
+    pub reason: String,
+    /// Every notice sent about it: (chat_id, message_id, text), edited once it's handled
+    pub notices: Vec<(i64, i64, String)>,
+    /// Unix time
+    pub at: i64,
+}
+
+fn mention(user: &User) -> String {
+    format!("<a href=\"tg://user?id={}\">{}</a>", user.id, escape(&user.first_name))
+}
+
+/// Whether `text` calls for the admins with `@admin` or `@admins`
+pub fn mentions_admins(text: &str) -> bool {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | '.' | '!' | '?'))
+        .any(|word| word.eq_ignore_ascii_case("@admin") || word.eq_ignore_ascii_case("@admins"))
+}
+
+fn buttons(chat_id: i64, message_id: i64) -> Vec<Vec<InlineKeyboardButton>> {
+    let data = |action: &str| format!("rep:{chat_id}:{message_id}:{action}");
+    vec![
+        vec![btn("🔨 Ban", &data("ban")), btn("👢 Kick", &data("kick")), btn("🔇 Mute", &data("mute"))],
+        vec![btn("⚠️ Warn", &data("warn")), btn("🗑️ Delete", &data("del"))],
+    ]
+}
+
+// ─── Reporting ────────────────────────────────────────────────────────────────
+
+/// Reports the message `msg` replies to. `explicit` is `/report`, which explains itself
+/// when it can't; a bare `@admin` mention stays quiet instead.
+pub async fn report(bot: &Bot, msg: &Message, reason: &str, state: &SharedState, explicit: bool) {
+    let chat_id = msg.chat.id;
+    let say = |text: &'static str| async move {
+        if explicit {
+            let _ = bot.send_message(chat_id, text, None).await;
+        }
+    };
+    if msg.chat.r#type == "private" {
+        return say("❌ Reports only work in groups.").await;
+    }
+    let settings = state.lock().await.store.chat_settings(chat_id);
+    if !settings.reports_enabled {
+        return say("❌ Reports are turned off in this chat.").await;
+    }
+    let Some(reporter) = msg.from.as_deref() else { return };
+    let Some(offending) = msg.reply_to_message.as_deref() else {
+        return say("❌ Reply to the message you want to report.").await;
+    };
+    let Some(target) = offending.from.as_deref() else { return };
+    if target.id == reporter.id || target.id == bot.me.id {
+        return say("❌ You can't report that message.").await;
+    }
+
+    let key = (chat_id, offending.message_id);
+    let already = {
+        let mut st = state.lock().await;
+        let cutoff = chrono::Utc::now().timestamp() - REPORT_TTL_SECS;
+        st.reports.retain(|_, r| r.at >= cutoff);
+        st.reports.contains_key(&key)
+    };
+    if already {
+        return say("✅ That message has already been reported.").await;
+    }
+    if is_admin(bot, chat_id, reporter.id, state).await {
+        return say("❌ You're an admin — act on it yourself.").await;
+    }
+    if is_admin(bot, chat_id, target.id, state).await {
+        return say("❌ Admins can't be reported.").await;
+    }
+
+    let admins: Vec<User> = match admin_cache::admins(bot, chat_id, state).await {
+        Ok(admins) => admins.into_iter().map(|a| a.user).filter(|u| !u.is_bot).collect(),
+        Err(e) => {
+            eprintln!("⚠️   Reports: couldn't list the admins of {chat_id}: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut text = format!("🚨 <b>Report</b>\n<b>User:</b> {}\n<b>Reported by:</b> {}", mention(target), mention(reporter));
+    if !reason.is_empty() {
+        text.push_str(&format!("\n<b>Reason:</b> {}", escape(reason)));
+    }
+    if let Some(link) = message_link(offending) {
+        text.push_str(&format!("\n<a href=\"{link}\">Go to message</a>"));
+    }
+
+    let mut notices = Vec::new();
+    let mut dmed = Vec::new();
+    let chat = escape(msg.chat.title.as_deref().unwrap_or("a group"));
+    for admin in admins.iter().filter(|a| settings.report_dm_admins.contains(&a.id)) {
+        let dm = format!("{text}\n<b>Chat:</b> {chat}");
+        let params = SendMessageParams::new().parse_mode("HTML").reply_markup(kb(buttons(key.0, key.1)));
+        // Fails for admins who never started the bot; the group notice still pings them
+        if let Ok(sent) = bot.send_message(admin.id, dm.clone(), Some(params)).await {
+            notices.push((admin.id, sent.message_id, dm));
+            dmed.push(admin.id);
+        }
+    }
+
+    // Zero-width mentions notify the admins without cluttering the notice
+    let pings: String = admins.iter()
+        .filter(|a| !dmed.contains(&a.id))
+        .map(|a| format!("<a href=\"tg://user?id={}\">\u{200b}</a>", a.id))
+        .collect();
+    let params = SendMessageParams::new().parse_mode("HTML").reply_markup(kb(buttons(key.0, key.1)));
+    match bot.send_message(chat_id, format!("{text}{pings}"), Some(params)).await {
+        Ok(sent) => notices.push((chat_id, sent.message_id, text)),
+        Err(e) => eprintln!("⚠️   Reports: couldn't post a report in {chat_id}: {e}"),
+    }
+    if notices.is_empty() {
+        return;
+    }
+    let report = Report { message: offending.clone(), reason: reason.to_string(), notices, at: chrono::Utc::now().timestamp() };
+    state.lock().await.reports.insert(key, report);
+}
+
+// ─── Acting on reports ────────────────────────────────────────────────────────
+
+/// Handles `rep:<chat_id>:<message_id>:<action>` presses; answers the callback itself.
+pub async fn on_callback(bot: &Bot, cbq: &CallbackQuery, data: &str, state: &SharedState) {
+    let reply = |text: &str, alert: bool| {
+        let params = AnswerCallbackQueryParams::new().text(text).show_alert(alert);
+        bot.answer_callback_query(&cbq.id, Some(params))
+    };
+    let mut parts = data.splitn(3, ':');
+    let (Some(chat_id), Some(message_id), Some(action)) = (
+        parts.next().and_then(|p| p.parse::<i64>().ok()),
+        parts.next().and_then(|p| p.parse::<i64>().ok()),
+        parts.next(),
+    ) else {
+        let _ = reply("This report has expired.", true).await;
+        return;
+    };
+    let admin = &cbq.from;
+    if !is_admin(bot, chat_id, admin.id, state).await {
+        let _ = reply("Only admins can act on reports.", true).await;
+        return;
+    }
+    let Some(report) = state.lock().await.reports.get(&(chat_id, message_id)).cloned() else {
+        let _ = reply("This report has already been handled or has expired.", true).await;
+        return;
+    };
+    let Some(target) = report.message.from.as_deref() else { return };
+    if is_admin(bot, chat_id, target.id, state).await {
+        let _ = reply("They're an admin now — nothing to do.", true).await;
+        return;
+    }
+
+    let reason = if report.reason.is_empty() { "Reported message".to_string() } else { format!("Reported: {}", report.reason) };
+    let done = match action {
+        "ban" | "kick" | "mute" => {
+            let punishment = match action {
+                "ban"  => Punishment::Ban,
+                "kick" => Punishment::Kick,
+                _      => Punishment::Mute,
+            };
+            match punishment.apply(bot, chat_id, target.id).await {
+                Ok(()) => {
+                    Entry::new(punishment.into()).by(admin).target(target).reason(&reason).record(bot, state, &report.message).await;
+                    Ok(punishment.label())
+                }
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        "warn" => {
+            admin::warn(bot, &report.message, admin, target, &reason, state).await;
+            Ok("warned".to_string())
+        }
+        "del" => match bot.delete_message(chat_id, message_id).await {
+            Ok(_) => {
+                Entry::new(Action::Delete).by(admin).target(target).reason(&reason).record(bot, state, &report.message).await;
+                Ok("deleted".to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        },
+        _ => Err("unknown action".to_string()),
+    };
+    let label = match done {
+        Ok(label) => label,
+        Err(e) => {
+            let _ = reply(&format!("❌ That didn't work: {e}"), true).await;
+            return;
+        }
+    };
+    let _ = reply("✅ Done", false).await;
+
+    state.lock().await.reports.remove(&(chat_id, message_id));
+    let outcome = format!("\n\n✅ {} {label} by {}", escape(&target.first_name), mention(admin));
+    for (notice_chat, notice_id, text) in &report.notices {
+        let params = EditMessageTextParams::new().chat_id(*notice_chat).message_id(*notice_id).parse_mode("HTML");
+        let _ = bot.edit_message_text(format!("{text}{outcome}"), Some(params)).await;
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+pub async fn cmd_report(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    report(bot, msg, args.trim(), state, true).await;
+}
+
+/// `/reports [on|off]` for the chat, `/reports dm on|off` for the admin's own DMs.
+pub async fn cmd_reports(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let Some(admin) = msg.from.as_deref() else { return };
+    let words: Vec<String> = args.split_whitespace().map(str::to_lowercase).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let reply = match words.as_slice() {
+        ["on"] => {
+            settings::update(state, chat_id, |s| s.reports_enabled = true).await;
+            "✅ Members can report messages with /report or @admin.".to_string()
+        }
+        ["off"] => {
+            settings::update(state, chat_id, |s| s.reports_enabled = false).await;
+            "✅ Reports are off.".to_string()
+        }
+        ["dm", "on"] => {
+            settings::update(state, chat_id, |s| { s.report_dm_admins.insert(admin.id); }).await;
+            "✅ You'll get reports from this chat by DM — make sure you've started a chat with me.".to_string()
+        }
+        ["dm", "off"] => {
+            settings::update(state, chat_id, |s| { s.report_dm_admins.remove(&admin.id); }).await;
+            "✅ You'll no longer get reports by DM.".to_string()
+        }
+        _ => {
+            let s = state.lock().await.store.chat_settings(chat_id);
+            format!(
+                "Usage: /reports on|off, or /reports dm on|off for your own DMs\nCurrently: {}, DMs {}",
+                if s.reports_enabled { "on" } else { "off" },
+                if s.report_dm_admins.contains(&admin.id) { "on" } else { "off" },
+            )
+        }
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
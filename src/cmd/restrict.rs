@@ -0,0 +1,406 @@
+//! `/restrict` and `/unrestrict` for single send permissions, and `/perms` to see them.
+//!
+//! Where `/mute` takes away everything, `/restrict @bob media stickers 1d` takes away just
+//! those and leaves the rest as it was. Without permissions the admin gets a keyboard of
+//! toggles starting from the member's current ones. Callback data is
+//! `rst:<perm|ok|cancel>`; open keyboards live in `BotState::restrictions` and are not
+//! persisted.
+
+use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use tgbotrs::{Bot, CallbackQuery, ChatPermissions, InlineKeyboardButton, MaybeInaccessibleMessage, Message, User};
+use tgbotrs::gen_methods::{AnswerCallbackQueryParams, EditMessageTextParams, RestrictChatMemberParams, SendMessageParams};
+use crate::admin_cache::{self, Member};
+use crate::cmd::admin::{all_perms, is_admin, require_admin, require_target, Target};
+use crate::duration;
+use crate::kb::{btn, kb, markup};
+use crate::modlog::Entry;
+use crate::punish::temp_secs;
+use crate::registry::escape;
+use crate::state::{Action, SharedState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Perm {
+    /// Text, contacts, locations and the like
+    Text,
+    Photos,
+    Videos,
+    Audio,
+    Voice,
+    VideoNotes,
+    Documents,
+    /// Stickers, GIFs, games and inline bots
+    Stickers,
+    Polls,
+    /// Link previews
+    Links,
+}
+
+impl Perm {
+    /// Order of the keyboard and of `/perms`
+    pub const ALL: [Perm; 10] = [
+        Perm::Text, Perm::Photos, Perm::Videos, Perm::Audio, Perm::Voice,
+        Perm::VideoNotes, Perm::Documents, Perm::Stickers, Perm::Polls, Perm::Links,
+    ];
+
+    /// What `media` stands for
+    pub const MEDIA: [Perm; 6] = [Perm::Photos, Perm::Videos, Perm::Audio, Perm::Voice, Perm::VideoNotes, Perm::Documents];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Perm::Text       => "text",
+            Perm::Photos     => "photos",
+            Perm::Videos     => "videos",
+            Perm::Audio      => "audio",
+            Perm::Voice      => "voice",
+            Perm::VideoNotes => "videonotes",
+            Perm::Documents  => "documents",
+            Perm::Stickers   => "stickers",
+            Perm::Polls      => "polls",
+            Perm::Links      => "links",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Perm::Text       => "Text",
+            Perm::Photos     => "Photos",
+            Perm::Videos     => "Videos",
+            Perm::Audio      => "Audio",
+            Perm::Voice      => "Voice messages",
+            Perm::VideoNotes => "Video messages",
+            Perm::Documents  => "Files",
+            Perm::Stickers   => "Stickers & GIFs",
+            Perm::Polls      => "Polls",
+            Perm::Links      => "Link previews",
+        }
+    }
+
+    /// A [`name`](Perm::name), a common alias, or `media` / `all` for several at once
+    pub fn parse(text: &str) -> Option<Vec<Perm>> {
+        let perm = match text.to_lowercase().as_str() {
+            "media"                        => return Some(Perm::MEDIA.to_vec()),
+            "all"                          => return Some(Perm::ALL.to_vec()),
+            "text" | "messages" | "msgs"   => Perm::Text,
+            "photo" | "photos"             => Perm::Photos,
+            "video" | "videos"             => Perm::Videos,
+            "audio" | "music"              => Perm::Audio,
+            "voice"                        => Perm::Voice,
+            "videonote" | "videonotes"     => Perm::VideoNotes,
+            "document" | "documents" | "files" | "docs" => Perm::Documents,
+            "sticker" | "stickers" | "gif" | "gifs" | "other" => Perm::Stickers,
+            "poll" | "polls"               => Perm::Polls,
+            "link" | "links" | "previews"  => Perm::Links,
+            _ => return None,
+        };
+        Some(vec![perm])
+    }
+
+    /// Whether `perms` allows this; unset counts as allowed, as for an unrestricted member
+    fn allowed(self, perms: &ChatPermissions) -> bool {
+        let field = match self {
+            Perm::Text       => perms.can_send_messages,
+            Perm::Photos     => perms.can_send_photos,
+            Perm::Videos     => perms.can_send_videos,
+            Perm::Audio      => perms.can_send_audios,
+            Perm::Voice      => perms.can_send_voice_notes,
+            Perm::VideoNotes => perms.can_send_video_notes,
+            Perm::Documents  => perms.can_send_documents,
+            Perm::Stickers   => perms.can_send_other_messages,
+            Perm::Polls      => perms.can_send_polls,
+            Perm::Links      => perms.can_add_web_page_previews,
+        };
+        field.unwrap_or(true)
+    }
+
+    fn set(self, perms: &mut ChatPermissions, allow: bool) {
+        let field = match self {
+            Perm::Text       => &mut perms.can_send_messages,
+            Perm::Photos     => &mut perms.can_send_photos,
+            Perm::Videos     => &mut perms.can_send_videos,
+            Perm::Audio      => &mut perms.can_send_audios,
+            Perm::Voice      => &mut perms.can_send_voice_notes,
+            Perm::VideoNotes => &mut perms.can_send_video_notes,
+            Perm::Documents  => &mut perms.can_send_documents,
+            Perm::Stickers   => &mut perms.can_send_other_messages,
+            Perm::Polls      => &mut perms.can_send_polls,
+            Perm::Links      => &mut perms.can_add_web_page_previews,
+        };
+        *field = Some(allow);
+    }
+}
+
+pub(crate) fn names(perms: &BTreeSet<Perm>) -> String {
+    if perms.is_empty() {
+        return "none".to_string();
+    }
+    perms.iter().map(|p| p.name()).collect::<Vec<_>>().join(", ")
+}
+
+/// Full permissions minus `denied`
+pub(crate) fn permissions(denied: &BTreeSet<Perm>) -> ChatPermissions {
+    let mut perms = all_perms(true);
+    for perm in denied {
+        perm.set(&mut perms, false);
+    }
+    perms
+}
+
+// ─── Reading a member's permissions ───────────────────────────────────────────
+
+/// What the member may not send, and until when; `None` if they couldn't be looked up.
+async fn denied(bot: &Bot, chat_id: i64, user_id: i64) -> Option<(Member, BTreeSet<Perm>)> {
+    let member = admin_cache::member(bot, chat_id, user_id).await.ok()?;
+    let denied = if member.status == "restricted" {
+        Perm::ALL.into_iter().filter(|p| !p.allowed(&member.perms)).collect()
+    } else {
+        BTreeSet::new()
+    };
+    Some((member, denied))
+}
+
+/// `<perm…> [duration]`. `Err` is the message for the admin.
+fn parse_args(args: &str) -> Result<(BTreeSet<Perm>, Option<u64>), String> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    let mut perms = BTreeSet::new();
+    let mut secs = None;
+    for (i, word) in words.iter().enumerate() {
+        if let Some(parsed) = Perm::parse(word) {
+            perms.extend(parsed);
+        } else if i + 1 == words.len() && temp_secs(word).is_some() {
+            secs = temp_secs(word);
+        } else if i + 1 == words.len() && duration::parse(word).is_some() {
+            return Err("❌ Durations must be between 30 seconds and 366 days.".to_string());
+        } else {
+            let all = Perm::ALL.map(Perm::name).join(", ");
+            return Err(format!("❌ Unknown permission: {word}. Choose from media, {all}, or all."));
+        }
+    }
+    Ok((perms, secs))
+}
+
+/// "\n⏰ Until …" for restrictions that end, empty otherwise
+fn until_line(until: Option<i64>) -> String {
+    until.filter(|t| *t > 0)
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| format!("\n⏰ Until {}", t.format("%Y-%m-%d %H:%M UTC")))
+        .unwrap_or_default()
+}
+
+// ─── Restricting ──────────────────────────────────────────────────────────────
+
+/// Leaves the member allowed everything but `denied`; returns the announcement, or what went wrong.
+async fn apply(
+    bot: &Bot, command: &Message, admin: &User, target: &User, denied: &BTreeSet<Perm>, secs: Option<u64>, state: &SharedState,
+) -> String {
+    let chat_id = command.chat.id;
+    let until = secs.map(|s| chrono::Utc::now().timestamp() + s as i64);
+    let mut params = RestrictChatMemberParams::new().use_independent_chat_permissions(true);
+    if let Some(until) = until {
+        params = params.until_date(until);
+    }
+    if let Err(e) = bot.restrict_chat_member(chat_id, target.id, permissions(denied), Some(params)).await {
+        return format!("❌ Failed to restrict: {}", escape(&e.to_string()));
+    }
+    let name = escape(&target.first_name);
+    if denied.is_empty() {
+        Entry::new(Action::Unrestrict).by(admin).target(target).record(bot, state, command).await;
+        return format!("🔓 <b>{name}</b> can send everything again.");
+    }
+    let reason = format!("Can't send: {}", names(denied));
+    Entry::new(Action::Restrict).by(admin).target(target).reason(&reason).duration(secs).record(bot, state, command).await;
+    format!("🔐 <b>{name}</b> restricted.\nCan't send: {}{}", names(denied), until_line(until))
+}
+
+/// A toggle keyboard waiting for its admin to press Apply.
+#[derive(Debug, Clone)]
+pub struct PendingRestriction {
+    /// The `/restrict` command, for the moderation log
+    pub command: Message,
+    pub target: User,
+    pub secs: Option<u64>,
+    /// Ticked off: what the member won't be allowed to send
+    pub denied: BTreeSet<Perm>,
+}
+
+fn toggles_text(p: &PendingRestriction) -> String {
+    let mut text = format!("🔐 Restricting <b>{}</b>", escape(&p.target.first_name));
+    if let Some(secs) = p.secs {
+        text.push_str(&format!(" for {}", duration::format(std::time::Duration::from_secs(secs))));
+    }
+    text.push_str("\nTap what they may not send, then press Apply.");
+    text
+}
+
+fn toggles_rows(p: &PendingRestriction) -> Vec<Vec<InlineKeyboardButton>> {
+    let toggles: Vec<InlineKeyboardButton> = Perm::ALL.iter()
+        .map(|perm| {
+            let icon = if p.denied.contains(perm) { "🚫" } else { "✅" };
+            btn(&format!("{icon} {}", perm.label()), &format!("rst:{}", perm.name()))
+        })
+        .collect();
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = toggles.chunks(2).map(<[_]>::to_vec).collect();
+    rows.push(vec![btn("🔐 Apply", "rst:ok"), btn("✖️ Cancel", "rst:cancel")]);
+    rows
+}
+
+pub async fn cmd_restrict(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, rest }) = require_admin(bot, msg, args, state).await else { return };
+    let Some(admin) = msg.from.as_deref() else { return };
+    let html = || SendMessageParams::new().parse_mode("HTML");
+    let (perms, secs) = match parse_args(&rest) {
+        Ok(parsed) => parsed,
+        Err(text) => {
+            let _ = bot.send_message(chat_id, text, None).await;
+            return;
+        }
+    };
+    let Some((_, mut denied)) = denied(bot, chat_id, target.id).await else {
+        let _ = bot.send_message(chat_id, "❌ I couldn't look up their current permissions.", None).await;
+        return;
+    };
+
+    if !perms.is_empty() {
+        denied.extend(perms);
+        let text = apply(bot, msg, admin, &target, &denied, secs, state).await;
+        let _ = bot.send_message(chat_id, text, Some(html())).await;
+        return;
+    }
+
+    let pending = PendingRestriction { command: msg.clone(), target: *target, secs, denied };
+    let params = html().reply_markup(kb(toggles_rows(&pending)));
+    if let Ok(sent) = bot.send_message(chat_id, toggles_text(&pending), Some(params)).await {
+        state.lock().await.restrictions.insert((chat_id, sent.message_id), pending);
+    }
+}
+
+/// `/unrestrict [user] [perm…]` — gives back the listed permissions, or all of them.
+pub async fn cmd_unrestrict(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let Some(Target { chat_id, user: target, rest }) = require_admin(bot, msg, args, state).await else { return };
+    let Some(admin) = msg.from.as_deref() else { return };
+    let perms = match parse_args(&rest) {
+        Ok((perms, None)) => perms,
+        Ok((_, Some(_))) => {
+            let _ = bot.send_message(chat_id, "❌ /unrestrict takes no duration.", None).await;
+            return;
+        }
+        Err(text) => {
+            let _ = bot.send_message(chat_id, text, None).await;
+            return;
+        }
+    };
+    let Some((member, mut denied)) = denied(bot, chat_id, target.id).await else {
+        let _ = bot.send_message(chat_id, "❌ I couldn't look up their current permissions.", None).await;
+        return;
+    };
+    if denied.is_empty() {
+        let _ = bot.send_message(chat_id, format!("✅ {} isn't restricted.", target.first_name), None).await;
+        return;
+    }
+    if perms.is_empty() {
+        denied.clear();
+    } else {
+        denied.retain(|p| !perms.contains(p));
+    }
+    // What stays restricted keeps its end date
+    let secs = member.until_date
+        .filter(|t| *t > 0 && !denied.is_empty())
+        .and_then(|t| u64::try_from(t - chrono::Utc::now().timestamp()).ok());
+    let text = apply(bot, msg, admin, &target, &denied, secs, state).await;
+    let _ = bot.send_message(chat_id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
+
+/// Handles `rst:<perm|ok|cancel>` presses; answers the callback itself.
+pub async fn on_callback(bot: &Bot, cbq: &CallbackQuery, action: &str, state: &SharedState) {
+    let reply = |text: &str, alert: bool| {
+        let params = AnswerCallbackQueryParams::new().text(text).show_alert(alert);
+        bot.answer_callback_query(&cbq.id, Some(params))
+    };
+    let ack = || bot.answer_callback_query(&cbq.id, Some(AnswerCallbackQueryParams::new()));
+    let Some(MaybeInaccessibleMessage::Message(m)) = cbq.message.as_deref() else {
+        let _ = reply("This keyboard has expired.", true).await;
+        return;
+    };
+    let key = (m.chat.id, m.message_id);
+    let Some(pending) = state.lock().await.restrictions.get(&key).cloned() else {
+        let _ = reply("This keyboard has expired.", true).await;
+        return;
+    };
+    if pending.command.from.as_ref().is_none_or(|u| u.id != cbq.from.id) {
+        let _ = reply("Only the admin who started this can use it.", true).await;
+        return;
+    }
+    let edit = |text: String, rows: Option<Vec<Vec<InlineKeyboardButton>>>| {
+        let mut params = EditMessageTextParams::new().chat_id(key.0).message_id(key.1).parse_mode("HTML");
+        if let Some(rows) = rows {
+            params = params.reply_markup(Box::new(markup(rows)));
+        }
+        bot.edit_message_text(text, Some(params))
+    };
+
+    match action {
+        "cancel" => {
+            state.lock().await.restrictions.remove(&key);
+            let _ = reply("Cancelled", false).await;
+            let _ = edit("✖️ Restriction cancelled.".to_string(), None).await;
+        }
+        "ok" => {
+            state.lock().await.restrictions.remove(&key);
+            let _ = ack().await;
+            let text = apply(bot, &pending.command, &cbq.from, &pending.target, &pending.denied, pending.secs, state).await;
+            let _ = edit(text, None).await;
+        }
+        name => {
+            let Some(perm) = Perm::ALL.into_iter().find(|p| p.name() == name) else {
+                let _ = ack().await;
+                return;
+            };
+            let updated = {
+                let mut st = state.lock().await;
+                let Some(p) = st.restrictions.get_mut(&key) else { return };
+                if !p.denied.remove(&perm) {
+                    p.denied.insert(perm);
+                }
+                p.clone()
+            };
+            let _ = ack().await;
+            let _ = edit(toggles_text(&updated), Some(toggles_rows(&updated))).await;
+        }
+    }
+}
+
+// ─── Showing permissions ──────────────────────────────────────────────────────
+
+/// `/perms [user]` — anyone; without a user, your own.
+pub async fn cmd_perms(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let target = if msg.reply_to_message.is_none() && args.trim().is_empty() {
+        let Some(user) = msg.from.clone() else { return };
+        user
+    } else {
+        let Some(Target { user, .. }) = require_target(bot, msg, args, state).await else { return };
+        user
+    };
+    let name = escape(&target.first_name);
+    let text = if is_admin(bot, chat_id, target.id, state).await {
+        format!("👮 <b>{name}</b> is an admin — restrictions don't apply.")
+    } else {
+        match denied(bot, chat_id, target.id).await {
+            None => "❌ I couldn't look up their permissions.".to_string(),
+            Some((member, _)) if member.status == "left" || member.status == "kicked" => {
+                format!("❌ <b>{name}</b> isn't in this chat.")
+            }
+            Some((_, denied)) if denied.is_empty() => {
+                format!("✅ <b>{name}</b> has no restrictions of their own — only the chat's defaults apply.")
+            }
+            Some((member, denied)) => {
+                let lines: Vec<String> = Perm::ALL.iter()
+                    .map(|p| format!("{} {}", if denied.contains(p) { "🚫" } else { "✅" }, p.label()))
+                    .collect();
+                format!("🔐 <b>{name}</b> is restricted:\n{}{}", lines.join("\n"), until_line(member.until_date))
+            }
+        }
+    };
+    let _ = bot.send_message(chat_id, text, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+}
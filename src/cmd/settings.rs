@@ -0,0 +1,159 @@
+use tgbotrs::{Bot, CallbackQuery, MaybeInaccessibleMessage, Message};
+use tgbotrs::gen_methods::{AnswerCallbackQueryParams, EditMessageTextParams, SendMessageParams};
+use crate::cmd::admin::is_admin;
+use crate::kb::{btn, kb, markup};
+use crate::state::{ChatSettings, SharedState};
+
+// ─── /settings ────────────────────────────────────────────────────────────────
+
+pub async fn cmd_settings(bot: &Bot, msg: &Message, state: &SharedState) {
+    let settings = state.lock().await.store.chat_settings(msg.chat.id);
+    let _ = bot.send_message(
+        msg.chat.id,
+        settings_text(&settings),
+        Some(SendMessageParams::new()
+            .parse_mode("HTML")
+            .reply_markup(kb(settings_rows(&settings)))
+        ),
+    ).await;
+}
+
+/// Loads, edits and saves one chat's settings under a single lock.
+pub async fn update(state: &SharedState, chat_id: i64, edit: impl FnOnce(&mut ChatSettings)) -> ChatSettings {
+    let mut st = state.lock().await;
+    let mut settings = st.store.chat_settings(chat_id);
+    edit(&mut settings);
+    st.store.save_chat_settings(chat_id, &settings);
+    settings
+}
+
+/// Handles `set:<action>` presses. Answers the callback itself so non-admins get an alert.
+pub async fn on_callback(bot: &Bot, cbq: &CallbackQuery, action: &str, state: &SharedState) {
+    let Some(MaybeInaccessibleMessage::Message(m)) = cbq.message.as_deref() else {
+        let _ = bot.answer_callback_query(&cbq.id, Some(AnswerCallbackQueryParams::new())).await;
+        return;
+    };
+    let (chat_id, msg_id) = (m.chat.id, m.message_id);
+
+    if !is_admin(bot, chat_id, cbq.from.id, state).await {
+        let _ = bot.answer_callback_query(
+            &cbq.id,
+            Some(AnswerCallbackQueryParams::new()
+                .text("Only admins can change settings.")
+                .show_alert(true)
+            ),
+        ).await;
+        return;
+    }
+    let _ = bot.answer_callback_query(&cbq.id, Some(AnswerCallbackQueryParams::new())).await;
+
+    if action == "close" {
+        let params = EditMessageTextParams::new().chat_id(chat_id).message_id(msg_id);
+        let _ = bot.edit_message_text("⚙️ Settings saved.", Some(params)).await;
+        return;
+    }
+
+    // NO await inside lock
+    let settings = {
+        let mut st = state.lock().await;
+        let mut settings = st.store.chat_settings(chat_id);
+        if !apply(&mut settings, action) {
+            return;
+        }
+        st.store.save_chat_settings(chat_id, &settings);
+        settings
+    };
+
+    let params = EditMessageTextParams::new()
+        .chat_id(chat_id)
+        .message_id(msg_id)
+        .parse_mode("HTML")
+        .reply_markup(Box::new(markup(settings_rows(&settings))));
+    let _ = bot.edit_message_text(settings_text(&settings), Some(params)).await;
+}
+
+/// Applies one button press; `false` when nothing changed (unknown action or at a bound).
+fn apply(settings: &mut ChatSettings, action: &str) -> bool {
+    let step = |value: &mut u32, range: std::ops::RangeInclusive<u32>, up: bool| {
+        let next = if up { value.saturating_add(1) } else { value.saturating_sub(1) };
+        if !range.contains(&next) {
+            return false;
+        }
+        *value = next;
+        true
+    };
+    match action {
+        "warn:+"  => step(&mut settings.warn_limit, ChatSettings::WARN_LIMIT_RANGE, true),
+        "warn:-"  => step(&mut settings.warn_limit, ChatSettings::WARN_LIMIT_RANGE, false),
+        "guess:+" => step(&mut settings.guess_attempts, ChatSettings::GUESS_ATTEMPTS_RANGE, true),
+        "guess:-" => step(&mut settings.guess_attempts, ChatSettings::GUESS_ATTEMPTS_RANGE, false),
+        "expiry:+" => match ChatSettings::WARN_EXPIRY_PRESETS.into_iter().find(|&d| d > settings.warn_expiry_days) {
+            Some(d) => { settings.warn_expiry_days = d; true }
+            None => false,
+        },
+        "expiry:-" => match ChatSettings::WARN_EXPIRY_PRESETS.into_iter().rev().find(|&d| d < settings.warn_expiry_days) {
+            Some(d) => { settings.warn_expiry_days = d; true }
+            None => false,
+        },
+        "action" => {
+            settings.warn_action = settings.warn_action.next();
+            true
+        }
+        "lang" => {
+            settings.language = settings.language.next();
+            true
+        }
+        "notes" => {
+            settings.notes_admin_only = !settings.notes_admin_only;
+            true
+        }
+        _ => false,
+    }
+}
+
+fn expiry_label(days: u32) -> String {
+    if days == 0 { "never".into() } else { format!("{days}d") }
+}
+
+fn settings_text(s: &ChatSettings) -> String {
+    let notes = if s.notes_admin_only { "admins only" } else { "everyone" };
+    format!(
+        "⚙️ <b>Chat settings</b>\n\n\
+         ⚠️ Warn limit: <b>{}</b> — then the user is <b>{}</b>\n\
+         ⌛ Warnings expire: <b>{}</b>\n\
+         🎮 Guess attempts: <b>{}</b> per /guess game\n\
+         🌐 Game language: <b>{}</b> — other replies stay English\n\
+         📝 Saving/deleting notes: <b>{notes}</b>\n\n\
+         <i>Tap the buttons to adjust.</i>",
+        s.warn_limit,
+        s.warn_action.label(),
+        expiry_label(s.warn_expiry_days),
+        s.guess_attempts,
+        s.language.label(),
+    )
+}
+
+fn settings_rows(s: &ChatSettings) -> Vec<Vec<tgbotrs::InlineKeyboardButton>> {
+    let notes = if s.notes_admin_only { "📝 Notes: admins only" } else { "📝 Notes: everyone" };
+    vec![
+        vec![
+            btn("➖", "set:warn:-"),
+            btn(&format!("⚠️ Warn limit: {}", s.warn_limit), "set:noop"),
+            btn("➕", "set:warn:+"),
+        ],
+        vec![btn(&format!("🔨 At limit: {}", s.warn_action.label()), "set:action")],
+        vec![
+            btn("➖", "set:expiry:-"),
+            btn(&format!("⌛ Expiry: {}", expiry_label(s.warn_expiry_days)), "set:noop"),
+            btn("➕", "set:expiry:+"),
+        ],
+        vec![
+            btn("➖", "set:guess:-"),
+            btn(&format!("🎮 Guesses: {}", s.guess_attempts), "set:noop"),
+            btn("➕", "set:guess:+"),
+        ],
+        vec![btn(&format!("🌐 Game: {}", s.language.label()), "set:lang")],
+        vec![btn(notes, "set:notes")],
+        vec![btn("✅ Done", "set:close")],
+    ]
+}
@@ -165,8 +165,8 @@ pub async fn cmd_repeat(bot: &Bot, msg: &Message, args: &str) {
     let parts: Vec<&str> = args.splitn(2, ' ').collect();
     match parts.as_slice() {
         [n_str, text] => {
-            let n: usize = n_str.parse().unwrap_or(1).min(10).max(1);
-            let repeated = std::iter::repeat(*text).take(n).collect::<Vec<_>>().join("\n");
+            let n: usize = n_str.parse().unwrap_or(1).clamp(1, 10);
+            let repeated = std::iter::repeat_n(*text, n).collect::<Vec<_>>().join("\n");
             let _ = bot.send_message(msg.chat.id, repeated, None).await;
         }
         _ => {
@@ -253,7 +253,7 @@ fn tokenize(s: &str) -> Result<Vec<Tok>, String> {
             ' ' | '\t' => { chars.next(); }
             '0'..='9' | '.' => {
                 let mut n = String::new();
-                while chars.peek().map_or(false, |&x| x.is_ascii_digit() || x == '.') {
+                while chars.peek().is_some_and(|&x| x.is_ascii_digit() || x == '.') {
                     n.push(chars.next().unwrap());
                 }
                 out.push(Tok::Num(n.parse().map_err(|_| "Invalid number".to_string())?));
@@ -0,0 +1,199 @@
+//! Welcome and goodbye messages, sent when `new_chat_members` / `left_chat_member`
+//! service messages arrive.
+//!
+//! Greetings are HTML templates with placeholders (`{first}`, `{mention}`, `{chatname}`,
+//! `{count}`, …) and optional URL buttons written as `[label](buttonurl://example.com)`;
+//! end the URL with `:same` to put a button on the same row as the one before it.
+
+use tgbotrs::{Bot, Chat, InlineKeyboardButton, Message, User};
+use tgbotrs::gen_methods::SendMessageParams;
+use crate::cmd::settings;
+use crate::i18n::fill;
+use crate::kb::{kb, url_btn};
+use crate::registry::escape;
+use crate::state::SharedState;
+
+pub const DEFAULT_WELCOME: &str = "Hey {mention}, welcome to <b>{chatname}</b>! 👋";
+
+const BUTTON_MARK: &str = "](buttonurl://";
+
+// ─── Service messages ─────────────────────────────────────────────────────────
+
+/// Sends the chat's welcome to one new member — on join, or once they pass the captcha.
+pub async fn greet(bot: &Bot, chat: &Chat, user: &User, state: &SharedState) {
+    let settings = state.lock().await.store.chat_settings(chat.id);
+    if !settings.welcome_enabled { return; }
+    let template = settings.welcome_text.as_deref().unwrap_or(DEFAULT_WELCOME);
+    let Some(sent) = send(bot, chat, user, template).await else { return };
+
+    let mut previous = None;
+    settings::update(state, chat.id, |s| previous = s.last_welcome_id.replace(sent.message_id)).await;
+    if let Some(id) = previous.filter(|_| settings.clean_welcome) {
+        let _ = bot.delete_message(chat.id, id).await;
+    }
+}
+
+pub async fn on_leave(bot: &Bot, msg: &Message, state: &SharedState) {
+    let Some(user) = msg.left_chat_member.as_deref().filter(|u| !u.is_bot) else { return };
+    let settings = state.lock().await.store.chat_settings(msg.chat.id);
+    if let (true, Some(template)) = (settings.welcome_enabled, settings.goodbye_text.as_deref()) {
+        send(bot, &msg.chat, user, template).await;
+    }
+}
+
+/// Renders `template` for `user` and sends it; failures (usually broken HTML) are logged.
+async fn send(bot: &Bot, chat: &Chat, user: &User, template: &str) -> Option<Message> {
+    let (text, rows) = render(bot, chat, user, template).await;
+    let mut params = SendMessageParams::new().parse_mode("HTML");
+    if !rows.is_empty() {
+        params = params.reply_markup(kb(rows));
+    }
+    bot.send_message(chat.id, text, Some(params)).await
+        .map_err(|e| eprintln!("⚠️   Failed to send greeting in {}: {e}", chat.id))
+        .ok()
+}
+
+async fn render(bot: &Bot, chat: &Chat, user: &User, template: &str) -> (String, Vec<Vec<InlineKeyboardButton>>) {
+    let (text, rows) = parse_buttons(template);
+    let count = if text.contains("{count}") {
+        bot.get_chat_member_count(chat.id).await.map(|n| n.to_string()).unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let first    = escape(&user.first_name);
+    let fullname = match &user.last_name {
+        Some(last) => format!("{first} {}", escape(last)),
+        None => first.clone(),
+    };
+    let mention = format!("<a href=\"tg://user?id={}\">{first}</a>", user.id);
+    let values = [
+        ("first",    first.clone()),
+        ("last",     user.last_name.as_deref().map(escape).unwrap_or_default()),
+        ("fullname", fullname),
+        ("username", user.username.as_ref().map_or_else(|| mention.clone(), |u| format!("@{u}"))),
+        ("mention",  mention),
+        ("id",       user.id.to_string()),
+        ("chatname", escape(chat.title.as_deref().unwrap_or("the chat"))),
+        ("count",    count),
+    ];
+    (fill(&text, &values), rows)
+}
+
+/// Pulls `[label](buttonurl://url)` buttons out of a template, returning the remaining
+/// text and the button rows.
+fn parse_buttons(template: &str) -> (String, Vec<Vec<InlineKeyboardButton>>) {
+    let mut text = String::new();
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+    let mut rest = template;
+    while let Some(mark) = rest.find(BUTTON_MARK) {
+        let after = &rest[mark + BUTTON_MARK.len()..];
+        let (Some(open), Some(close)) = (rest[..mark].rfind('['), after.find(')')) else {
+            text.push_str(&rest[..mark + BUTTON_MARK.len()]);
+            rest = after;
+            continue;
+        };
+        let label = &rest[open + 1..mark];
+        let (url, same_row) = match after[..close].strip_suffix(":same") {
+            Some(url) => (url, true),
+            None => (&after[..close], false),
+        };
+        let url = if url.contains("://") { url.to_string() } else { format!("https://{url}") };
+        let button = url_btn(label, &url);
+        match rows.last_mut() {
+            Some(row) if same_row => row.push(button),
+            _ => rows.push(vec![button]),
+        }
+        text.push_str(&rest[..open]);
+        rest = &after[close + 1..];
+    }
+    text.push_str(rest);
+    (text.trim().to_string(), rows)
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// The text to save: the arguments, or the replied-to message's text.
+fn template_arg(msg: &Message, args: &str) -> Option<String> {
+    let args = args.trim();
+    if !args.is_empty() {
+        return Some(args.to_string());
+    }
+    msg.reply_to_message.as_ref()?.text.clone()
+}
+
+pub async fn cmd_setwelcome(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let reply = match template_arg(msg, args) {
+        Some(text) => {
+            settings::update(state, msg.chat.id, |s| {
+                s.welcome_text = Some(text);
+                s.welcome_enabled = true;
+            }).await;
+            "✅ Welcome message saved and switched on. Send /welcome to preview it."
+        }
+        None => "Usage: /setwelcome <text>, or reply to a message with /setwelcome\n\
+                 Placeholders: {first} {last} {fullname} {username} {mention} {id} {chatname} {count}\n\
+                 Buttons: [Rules](buttonurl://t.me/yourchannel)",
+    };
+    let _ = bot.send_message(msg.chat.id, reply, None).await;
+}
+
+pub async fn cmd_setgoodbye(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let reply = match template_arg(msg, args) {
+        Some(text) if text.eq_ignore_ascii_case("off") => {
+            settings::update(state, msg.chat.id, |s| s.goodbye_text = None).await;
+            "✅ No more goodbye messages."
+        }
+        Some(text) => {
+            let s = settings::update(state, msg.chat.id, |s| s.goodbye_text = Some(text)).await;
+            if s.welcome_enabled { "✅ Goodbye message saved." } else { "✅ Goodbye message saved. Turn greetings on with /welcome on." }
+        }
+        None => "Usage: /setgoodbye <text|off> — same placeholders and buttons as /setwelcome",
+    };
+    let _ = bot.send_message(msg.chat.id, reply, None).await;
+}
+
+pub async fn cmd_resetwelcome(bot: &Bot, msg: &Message, state: &SharedState) {
+    settings::update(state, msg.chat.id, |s| {
+        s.welcome_text = None;
+        s.goodbye_text = None;
+    }).await;
+    let _ = bot.send_message(msg.chat.id, "✅ Welcome message reset to the default; goodbye message removed.", None).await;
+}
+
+/// `/welcome on|off` switches greetings; without arguments it shows the settings and a
+/// preview of the welcome, rendered for the caller.
+pub async fn cmd_welcome(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    match args.trim().to_lowercase().as_str() {
+        "on" | "off" => {
+            let on = args.trim().eq_ignore_ascii_case("on");
+            settings::update(state, chat_id, |s| s.welcome_enabled = on).await;
+            let reply = if on { "✅ New members will be greeted." } else { "✅ Welcome and goodbye messages are off." };
+            let _ = bot.send_message(chat_id, reply, None).await;
+        }
+        "" => {
+            let settings = state.lock().await.store.chat_settings(chat_id);
+            let status = format!(
+                "👋 Welcome messages: <b>{}</b>\n🧹 Delete the previous welcome: <b>{}</b>\n👋 Goodbye message: <b>{}</b>\n\nPreview:",
+                if settings.welcome_enabled { "on" } else { "off" },
+                if settings.clean_welcome { "on" } else { "off" },
+                if settings.goodbye_text.is_some() { "set" } else { "none" },
+            );
+            let _ = bot.send_message(chat_id, status, Some(SendMessageParams::new().parse_mode("HTML"))).await;
+            if let Some(user) = msg.from.as_deref() {
+                send(bot, &msg.chat, user, settings.welcome_text.as_deref().unwrap_or(DEFAULT_WELCOME)).await;
+            }
+        }
+        _ => { let _ = bot.send_message(chat_id, "Usage: /welcome [on|off]", None).await; }
+    }
+}
+
+pub async fn cmd_cleanwelcome(bot: &Bot, msg: &Message, args: &str, state: &SharedState) {
+    let chat_id = msg.chat.id;
+    let reply = match args.trim().to_lowercase().as_str() {
+        "on"  => { settings::update(state, chat_id, |s| s.clean_welcome = true).await;  "✅ I'll delete the previous welcome when sending a new one." }
+        "off" => { settings::update(state, chat_id, |s| s.clean_welcome = false).await; "✅ Old welcome messages will be kept." }
+        _ => "Usage: /cleanwelcome on|off",
+    };
+    let _ = bot.send_message(chat_id, reply, None).await;
+}
@@ -0,0 +1,46 @@
+//! Human durations: `30s`, `10m`, `2h`, `7d`, `2w` and combinations like `1d12h`.
+
+use std::time::Duration;
+
+/// Parses a duration such as `90s`, `1h30m` or `1d12h`. Every number needs a unit.
+pub fn parse(text: &str) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return None,
+        };
+        let n: u64 = std::mem::take(&mut number).parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+    }
+    if !number.is_empty() || total == 0 {
+        return None;
+    }
+    Some(Duration::from_secs(total))
+}
+
+/// `93784s` → `1d 2h 3m 4s`, largest units first, zero parts left out.
+pub fn format(duration: Duration) -> String {
+    let mut secs = duration.as_secs();
+    if secs == 0 {
+        return "0s".into();
+    }
+    let mut parts = Vec::new();
+    for (unit, size) in [("w", 604_800), ("d", 86_400), ("h", 3600), ("m", 60), ("s", 1)] {
+        if secs >= size {
+            parts.push(format!("{}{unit}", secs / size));
+            secs %= size;
+        }
+    }
+    parts.join(" ")
+}
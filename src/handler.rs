@@ -1,8 +1,16 @@
-use tgbotrs::{Bot, MaybeInaccessibleMessage, Update};
+use std::sync::Arc;
+use tgbotrs::{Bot, MaybeInaccessibleMessage, Update, User};
 use tgbotrs::gen_methods::{AnswerCallbackQueryParams, EditMessageTextParams};
-use crate::cmd::{admin, fun, games, info, notes, polls, util};
+use crate::admin_cache;
+use crate::cmd::{antiflood, blocklist, captcha, federation, fun, info, locks, promote, reports, restrict, settings, welcome};
+use crate::middleware::Call;
+use crate::registry::{self, Ctx};
 use crate::state::SharedState;
 
+/// Update types asked for when polling. Telegram leaves `chat_member` out unless it's named,
+/// and the admin cache needs it to notice promotions and demotions.
+pub const ALLOWED_UPDATES: [&str; 4] = ["message", "callback_query", "chat_member", "my_chat_member"];
+
 /// Strip /command@botname → ("/command", "args")
 fn parse_cmd(text: &str) -> (&str, &str) {
     let text = text.trim();
@@ -15,84 +23,64 @@ fn parse_cmd(text: &str) -> (&str, &str) {
     (cmd, args)
 }
 
+/// Feeds the @username → id cache from everyone visible in the update, so admin
+/// commands can target people by name.
+async fn remember_users(update: &Update, state: &SharedState) {
+    let mut users: Vec<&User> = Vec::new();
+    if let Some(msg) = &update.message {
+        users.extend(msg.from.as_deref());
+        users.extend(msg.reply_to_message.as_ref().and_then(|r| r.from.as_deref()));
+        users.extend(msg.new_chat_members.iter().flatten());
+    }
+    if let Some(cbq) = &update.callback_query {
+        users.push(&cbq.from);
+    }
+    let named: Vec<(&str, i64)> = users.into_iter()
+        .filter_map(|u| Some((u.username.as_deref()?, u.id)))
+        .collect();
+    if named.is_empty() { return; }
+
+    let mut st = state.lock().await;
+    for (username, id) in named {
+        st.store.remember_username(username, id);
+    }
+}
+
 pub async fn handle_update(bot: Bot, update: Update, state: SharedState) {
-    // ── 1. Text messages ──────────────────────────────────────────────────────
+    remember_users(&update, &state).await;
+
+    // ── 0. Admin changes, which make the chat's cached admin list stale ───────
+    for member in update.chat_member.iter().chain(&update.my_chat_member) {
+        admin_cache::on_member_update(member, &state).await;
+    }
+
+    // ── 1. Joins / leaves, antiflood, locks, blocklist, commands, @admin ──────
     if let Some(msg) = update.message {
+        for user in msg.new_chat_members.iter().flatten().filter(|u| !u.is_bot) {
+            if federation::check_join(&bot, &msg.chat, user, &state).await {
+                continue;
+            }
+            if !captcha::challenge(&bot, &msg.chat, user, &state).await {
+                welcome::greet(&bot, &msg.chat, user, &state).await;
+            }
+        }
+        if msg.left_chat_member.is_some() {
+            welcome::on_leave(&bot, &msg, &state).await;
+        }
+        let caught = antiflood::check(&bot, &msg, &state).await
+            || locks::check(&bot, &msg, &state).await
+            || blocklist::check(&bot, &msg, &state).await;
+        if caught {
+            return;
+        }
         if let Some(text) = msg.text.clone() {
             let (cmd, args) = parse_cmd(&text);
-            match cmd {
-                // ── Info / general
-                "/start"    => info::cmd_start(&bot, &msg).await,
-                "/help"     => info::cmd_help(&bot, &msg, "main").await,
-                "/about"    => info::cmd_about(&bot, &msg).await,
-                "/ping"     => info::cmd_ping(&bot, &msg).await,
-                "/source"   => info::cmd_source(&bot, &msg).await,
-                "/id"       => util::cmd_id(&bot, &msg).await,
-                "/userinfo" => info::cmd_userinfo(&bot, &msg).await,
-                "/chatinfo" => info::cmd_chatinfo(&bot, &msg).await,
-                "/members"  => info::cmd_members(&bot, &msg).await,
-
-                // ── Fun
-                "/dice"       => fun::cmd_dice(&bot, &msg).await,
-                "/roll"       => fun::cmd_roll(&bot, &msg, args).await,
-                "/flip"       => fun::cmd_flip(&bot, &msg).await,
-                "/joke"       => fun::cmd_joke(&bot, &msg).await,
-                "/quote"      => fun::cmd_quote(&bot, &msg).await,
-                "/fact"       => fun::cmd_fact(&bot, &msg).await,
-                "/8ball"      => fun::cmd_8ball(&bot, &msg, args).await,
-                "/rps"        => fun::cmd_rps(&bot, &msg).await,
-                "/choose"     => fun::cmd_choose(&bot, &msg, args).await,
-                "/rate"       => fun::cmd_rate(&bot, &msg, args).await,
-                "/password"   => fun::cmd_password(&bot, &msg, args).await,
-                "/mock"       => fun::cmd_mock(&bot, &msg, args).await,
-                "/clap"       => fun::cmd_clap(&bot, &msg, args).await,
-                "/shrug"      => fun::cmd_shrug(&bot, &msg).await,
-                "/tableflip"  => fun::cmd_tableflip(&bot, &msg).await,
-                "/unflip"     => fun::cmd_unflip(&bot, &msg).await,
-
-                // ── Utility
-                "/echo"    => util::cmd_echo(&bot, &msg, args).await,
-                "/reverse" => util::cmd_reverse(&bot, &msg, args).await,
-                "/upper"   => util::cmd_upper(&bot, &msg, args).await,
-                "/lower"   => util::cmd_lower(&bot, &msg, args).await,
-                "/count"   => util::cmd_count(&bot, &msg, args).await,
-                "/calc"    => util::cmd_calc(&bot, &msg, args).await,
-                "/b64"     => util::cmd_b64(&bot, &msg, args).await,
-                "/repeat"  => util::cmd_repeat(&bot, &msg, args).await,
-                "/ascii"   => util::cmd_ascii(&bot, &msg, args).await,
-                "/binary"  => util::cmd_binary(&bot, &msg, args).await,
-                "/time"    => util::cmd_time(&bot, &msg).await,
-
-                // ── Admin
-                "/ban"        => admin::cmd_ban(&bot, &msg).await,
-                "/kick"       => admin::cmd_kick(&bot, &msg).await,
-                "/mute"       => admin::cmd_mute(&bot, &msg).await,
-                "/unmute"     => admin::cmd_unmute(&bot, &msg).await,
-                "/warn"       => admin::cmd_warn(&bot, &msg, args, &state).await,
-                "/warns"      => admin::cmd_warns(&bot, &msg, &state).await,
-                "/clearwarns" => admin::cmd_clearwarns(&bot, &msg, &state).await,
-                "/pin"        => admin::cmd_pin(&bot, &msg).await,
-                "/unpin"      => admin::cmd_unpin(&bot, &msg).await,
-                "/del"        => admin::cmd_del(&bot, &msg).await,
-                "/promote"    => admin::cmd_promote(&bot, &msg).await,
-                "/demote"     => admin::cmd_demote(&bot, &msg).await,
-                "/invite"     => admin::cmd_invite(&bot, &msg).await,
-
-                // ── Games
-                "/guess"  => games::cmd_guess(&bot, &msg, args, &state).await,
-                "/giveup" => games::cmd_giveup(&bot, &msg, &state).await,
-
-                // ── Notes
-                "/save"    => notes::cmd_save(&bot, &msg, args, &state).await,
-                "/get"     => notes::cmd_get(&bot, &msg, args, &state).await,
-                "/notes"   => notes::cmd_notes(&bot, &msg, &state).await,
-                "/delnote" => notes::cmd_delnote(&bot, &msg, args, &state).await,
-
-                // ── Polls
-                "/poll" => polls::cmd_poll(&bot, &msg, args).await,
-                "/quiz" => polls::cmd_quiz(&bot, &msg, args).await,
-
-                _ => {}
+            if let Some(command) = cmd.strip_prefix('/').and_then(registry::find) {
+                let ctx = Ctx { bot: bot.clone(), msg: *msg, args: args.to_string(), state: Arc::clone(&state) };
+                let pipeline = Arc::clone(&state.lock().await.middleware);
+                pipeline.run(Call { cmd: cmd[1..].to_string(), command, ctx }).await;
+            } else if cmd.is_empty() && msg.reply_to_message.is_some() && reports::mentions_admins(&text) {
+                reports::report(&bot, &msg, "", &state, false).await;
             }
         }
     }
@@ -102,6 +90,36 @@ pub async fn handle_update(bot: Bot, update: Update, state: SharedState) {
         let query_id = cbq.id.clone();
         let data     = cbq.data.as_deref().unwrap_or("").to_string();
 
+        // Settings menu answers the query itself (non-admins get an alert)
+        if let Some(action) = data.strip_prefix("set:") {
+            settings::on_callback(&bot, &cbq, action, &state).await;
+            return;
+        }
+
+        // So does the join captcha (others pressing someone's captcha get an alert)
+        if let Some(answer) = data.strip_prefix("cap:") {
+            captcha::on_callback(&bot, &cbq, answer, &state).await;
+            return;
+        }
+
+        // And report buttons, which only admins may press
+        if let Some(report) = data.strip_prefix("rep:") {
+            reports::on_callback(&bot, &cbq, report, &state).await;
+            return;
+        }
+
+        // And the /promote checklist, which only its admin may press
+        if let Some(action) = data.strip_prefix("pro:") {
+            promote::on_callback(&bot, &cbq, action, &state).await;
+            return;
+        }
+
+        // And the /restrict toggles, likewise
+        if let Some(action) = data.strip_prefix("rst:") {
+            restrict::on_callback(&bot, &cbq, action, &state).await;
+            return;
+        }
+
         let _ = bot.answer_callback_query(
             &query_id,
             Some(AnswerCallbackQueryParams::new()),
@@ -129,7 +147,6 @@ pub async fn handle_update(bot: Bot, update: Update, state: SharedState) {
                         format!("✊✌️🖐 *Rock Paper Scissors*\n\n{result}"),
                         Some(params),
                     ).await;
-                    return;
                 }
             }
         }
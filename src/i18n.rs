@@ -0,0 +1,149 @@
+//! Translations of the `/guess` game's replies, which follow `ChatSettings::language`.
+//!
+//! Templates use `{name}` placeholders, filled in with [`fill`]. Texts are Markdown
+//! unless noted. Every other reply is English.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+    De,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::En, Language::Es, Language::De];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::En => "🇬🇧 English",
+            Language::Es => "🇪🇸 Español",
+            Language::De => "🇩🇪 Deutsch",
+        }
+    }
+
+    /// The next language in [`Language::ALL`], wrapping around — for the settings toggle
+    pub fn next(self) -> Language {
+        let i = Language::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        Language::ALL[(i + 1) % Language::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    GameRunning,
+    /// `{name}`, `{max}`
+    GameStarted,
+    NotANumber,
+    OutOfRange,
+    /// `{secret}`, `{name}`, `{attempts}`, `{max}`
+    GameWon,
+    /// `{secret}`, `{max}`
+    GameLost,
+    TooLow,
+    TooHigh,
+    /// `{attempts}`, `{max}` — after the too low / too high hint
+    GuessCount,
+    /// `{remaining}`
+    AttemptsLeft,
+    /// `{secret}`
+    GaveUp,
+    NoGame,
+}
+
+pub fn tr(lang: Language, text: Text) -> &'static str {
+    use Language::*;
+    use Text::*;
+    match (text, lang) {
+        (GameRunning, En) => "🎮 A game is already running! Use `/guess <number>` to guess, or /giveup to quit.",
+        (GameRunning, Es) => "🎮 ¡Ya hay una partida en curso! Usa `/guess <número>` para adivinar o /giveup para rendirte.",
+        (GameRunning, De) => "🎮 Es läuft schon ein Spiel! Rate mit `/guess <Zahl>` oder gib mit /giveup auf.",
+
+        (GameStarted, En) => "🎮 *Number Guessing Game started!*\n\n\
+                              {name} is playing!\n\
+                              I'm thinking of a number between *1* and *100*.\n\
+                              You have *{max} attempts*.\n\n\
+                              Use `/guess <number>` to guess!",
+        (GameStarted, Es) => "🎮 *¡Empieza el juego de adivinar el número!*\n\n\
+                              ¡Juega {name}!\n\
+                              Estoy pensando en un número entre *1* y *100*.\n\
+                              Tienes *{max} intentos*.\n\n\
+                              ¡Usa `/guess <número>` para adivinar!",
+        (GameStarted, De) => "🎮 *Zahlenraten gestartet!*\n\n\
+                              {name} spielt!\n\
+                              Ich denke an eine Zahl zwischen *1* und *100*.\n\
+                              Du hast *{max} Versuche*.\n\n\
+                              Rate mit `/guess <Zahl>`!",
+
+        (NotANumber, En) => "❌ Please enter a valid number between 1 and 100.",
+        (NotANumber, Es) => "❌ Escribe un número válido entre 1 y 100.",
+        (NotANumber, De) => "❌ Bitte gib eine gültige Zahl zwischen 1 und 100 ein.",
+
+        (OutOfRange, En) => "❌ Number must be between 1 and 100!",
+        (OutOfRange, Es) => "❌ ¡El número debe estar entre 1 y 100!",
+        (OutOfRange, De) => "❌ Die Zahl muss zwischen 1 und 100 liegen!",
+
+        (GameWon, En) => "🎉 *Correct!* The number was *{secret}*!\n{name} got it in *{attempts}/{max}* attempts! 🏆",
+        (GameWon, Es) => "🎉 *¡Correcto!* El número era *{secret}*.\n¡{name} lo adivinó en *{attempts}/{max}* intentos! 🏆",
+        (GameWon, De) => "🎉 *Richtig!* Die Zahl war *{secret}*!\n{name} hat es in *{attempts}/{max}* Versuchen geschafft! 🏆",
+
+        (GameLost, En) => "💀 *Game over!* You used all {max} attempts.\nThe number was *{secret}*. Better luck next time!",
+        (GameLost, Es) => "💀 *¡Fin del juego!* Usaste los {max} intentos.\nEl número era *{secret}*. ¡Más suerte la próxima vez!",
+        (GameLost, De) => "💀 *Spiel vorbei!* Alle {max} Versuche aufgebraucht.\nDie Zahl war *{secret}*. Viel Glück beim nächsten Mal!",
+
+        (TooLow, En) => "📈 Too low!",
+        (TooLow, Es) => "📈 ¡Muy bajo!",
+        (TooLow, De) => "📈 Zu niedrig!",
+
+        (TooHigh, En) => "📉 Too high!",
+        (TooHigh, Es) => "📉 ¡Muy alto!",
+        (TooHigh, De) => "📉 Zu hoch!",
+
+        (GuessCount, En) => "Guess {attempts}/{max}",
+        (GuessCount, Es) => "Intento {attempts}/{max}",
+        (GuessCount, De) => "Versuch {attempts}/{max}",
+
+        (AttemptsLeft, En) => "{remaining} attempt(s) left. `/guess <number>`",
+        (AttemptsLeft, Es) => "Te quedan {remaining} intento(s). `/guess <número>`",
+        (AttemptsLeft, De) => "Noch {remaining} Versuch(e). `/guess <Zahl>`",
+
+        (GaveUp, En) => "🏳️ Game over! The number was *{secret}*.",
+        (GaveUp, Es) => "🏳️ ¡Fin del juego! El número era *{secret}*.",
+        (GaveUp, De) => "🏳️ Spiel vorbei! Die Zahl war *{secret}*.",
+
+        (NoGame, En) => "❌ No game is currently running.",
+        (NoGame, Es) => "❌ No hay ninguna partida en curso.",
+        (NoGame, De) => "❌ Gerade läuft kein Spiel.",
+    }
+}
+
+/// Replaces each `{key}` in `template` with its value, in one pass: values are never
+/// scanned for placeholders themselves, so a user called `{mention}` stays as typed.
+/// Unknown keys are left alone.
+pub fn fill(template: &str, values: &[(&str, String)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let found = after.find('}').and_then(|close| {
+            let key = &after[..close];
+            values.iter().find(|(k, _)| *k == key).map(|(_, value)| (value, close))
+        });
+        match found {
+            Some((value, close)) => {
+                text.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
@@ -20,7 +20,12 @@ pub fn url_btn(text: &str, url: &str) -> InlineKeyboardButton {
 
 /// Multi-row inline keyboard
 pub fn kb(rows: Vec<Vec<InlineKeyboardButton>>) -> ReplyMarkup {
-    ReplyMarkup::InlineKeyboard(InlineKeyboardMarkup { inline_keyboard: rows })
+    ReplyMarkup::InlineKeyboard(markup(rows))
+}
+
+/// Bare markup, for edit calls that take `InlineKeyboardMarkup` rather than `ReplyMarkup`
+pub fn markup(rows: Vec<Vec<InlineKeyboardButton>>) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup { inline_keyboard: rows }
 }
 
 /// Single-row inline keyboard
@@ -0,0 +1,18 @@
+//! Everything except startup lives here so `tests/` (and other binaries) can drive the
+//! bot without Telegram: build a `Bot` from [`mock_api::MockApi`], a state from
+//! [`storage::MemoryStorage`], and call [`handler::handle_update`].
+
+pub mod admin_cache;
+pub mod cmd;
+pub mod duration;
+pub mod handler;
+pub mod i18n;
+pub mod kb;
+pub mod middleware;
+pub mod mock_api;
+pub mod modlog;
+pub mod punish;
+pub mod registry;
+pub mod scheduler;
+pub mod state;
+pub mod storage;
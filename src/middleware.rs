@@ -0,0 +1,344 @@
+//! Hooks that run around every registered command.
+//!
+//! A [`Pipeline`] is an ordered list of [`Middleware`] layers. For each command, every
+//! layer's `before` runs first, outermost to innermost. Any layer can return
+//! [`Flow::Stop`] to drop the update, or edit the [`Call`] (args, message) before it
+//! reaches the handler. Then the command is dispatched through the registry, and the
+//! `after` hooks run in reverse order with the [`Outcome`].
+//!
+//! [`from_env`] builds the stack `main` uses. Tests and `bot-sim` can assemble their own:
+//!
+//! ```ignore
+//! state.lock().await.middleware = Arc::new(
+//!     Pipeline::default().with(DisabledCommands::new(["joke"])),
+//! );
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::registry::{self, Command, Ctx};
+
+pub type Hook<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// One command on its way through the pipeline.
+pub struct Call {
+    /// The command as typed, without `/` or `@botname` — may be an alias
+    pub cmd: String,
+    /// The registry entry `cmd` resolved to
+    pub command: &'static Command,
+    /// Message, args, bot and state the handler will receive
+    pub ctx: Ctx,
+}
+
+impl Call {
+    pub fn sender_id(&self) -> Option<i64> {
+        self.ctx.msg.from.as_ref().map(|u| u.id)
+    }
+
+    pub fn chat_id(&self) -> i64 {
+        self.ctx.msg.chat.id
+    }
+
+    async fn reply(&self, text: &str) {
+        let _ = self.ctx.bot.send_message(self.chat_id(), text, None).await;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    /// Drop the update: later layers and the handler don't run
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The handler ran (including the registry's permission check) and took this long
+    Handled(Duration),
+    /// A middleware stopped the update before it reached the handler
+    Stopped,
+}
+
+pub trait Middleware: Send + Sync {
+    /// Shown in logs and `Debug` output
+    fn name(&self) -> &'static str;
+
+    fn before<'a>(&'a self, _call: &'a mut Call) -> Hook<'a, Flow> {
+        Box::pin(async { Flow::Continue })
+    }
+
+    /// Runs for every layer whose `before` ran, even if a later layer stopped the update.
+    fn after<'a>(&'a self, _call: &'a Call, _outcome: Outcome) -> Hook<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+#[derive(Default)]
+pub struct Pipeline {
+    layers: Vec<Box<dyn Middleware>>,
+}
+
+impl Pipeline {
+    /// Adds a layer inside the ones already added.
+    pub fn with(mut self, layer: impl Middleware + 'static) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    pub async fn run(&self, mut call: Call) {
+        let mut entered = 0;
+        let mut flow = Flow::Continue;
+        for layer in &self.layers {
+            entered += 1;
+            flow = layer.before(&mut call).await;
+            if flow == Flow::Stop { break; }
+        }
+
+        let outcome = match flow {
+            Flow::Stop => Outcome::Stopped,
+            Flow::Continue => {
+                let started = Instant::now();
+                registry::dispatch(call.command, call.ctx.clone()).await;
+                Outcome::Handled(started.elapsed())
+            }
+        };
+
+        for layer in self.layers[..entered].iter().rev() {
+            layer.after(&call, outcome).await;
+        }
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.layers.iter().map(|l| l.name())).finish()
+    }
+}
+
+/// The stack `main` runs with, configured from `.env`. Each layer is skipped when its
+/// variable is unset.
+///
+/// - `LOG_COMMANDS=true`: log every command with its timing.
+/// - `BLOCKED_USERS=1,2`: ignore these user ids completely.
+/// - `MAINTENANCE=true`: answer everyone except `BOT_OWNERS` with a maintenance notice.
+/// - `DISABLED_COMMANDS=joke,8ball`: turn these commands off.
+/// - `RATE_LIMIT=5/10`: at most 5 commands per user every 10 seconds.
+pub fn from_env() -> Pipeline {
+    let mut pipeline = Pipeline::default();
+    if env_flag("LOG_COMMANDS") {
+        pipeline = pipeline.with(Logger);
+    }
+    let blocked = env_ids("BLOCKED_USERS");
+    if !blocked.is_empty() {
+        pipeline = pipeline.with(Blocklist::new(blocked));
+    }
+    if env_flag("MAINTENANCE") {
+        pipeline = pipeline.with(Maintenance::new(env_ids("BOT_OWNERS")));
+    }
+    let disabled = env_list("DISABLED_COMMANDS");
+    if !disabled.is_empty() {
+        pipeline = pipeline.with(DisabledCommands::new(disabled));
+    }
+    if let Ok(spec) = std::env::var("RATE_LIMIT") {
+        match RateLimit::parse(&spec) {
+            Some(limit) => pipeline = pipeline.with(limit),
+            None => eprintln!("⚠️   Ignoring RATE_LIMIT={spec:?} — expected e.g. 5/10 (commands/seconds)"),
+        }
+    }
+    pipeline
+}
+
+fn env_list(key: &str) -> Vec<String> {
+    std::env::var(key).unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().trim_start_matches('/').to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn env_ids(key: &str) -> Vec<i64> {
+    env_list(key).iter().filter_map(|id| id.parse().ok()).collect()
+}
+
+fn env_flag(key: &str) -> bool {
+    matches!(std::env::var(key).as_deref(), Ok("1" | "true" | "on" | "yes"))
+}
+
+// ─── Logger ───────────────────────────────────────────────────────────────────
+
+/// Prints one line per command with who sent it, where, and how long it took.
+pub struct Logger;
+
+impl Middleware for Logger {
+    fn name(&self) -> &'static str { "logger" }
+
+    fn after<'a>(&'a self, call: &'a Call, outcome: Outcome) -> Hook<'a, ()> {
+        Box::pin(async move {
+            let who = call.sender_id().map(|id| id.to_string()).unwrap_or_else(|| "?".into());
+            match outcome {
+                Outcome::Handled(took) => println!("💬  /{} from {who} in {} — {took:.1?}", call.cmd, call.chat_id()),
+                Outcome::Stopped       => println!("💬  /{} from {who} in {} — stopped", call.cmd, call.chat_id()),
+            }
+        })
+    }
+}
+
+// ─── Blocklist ────────────────────────────────────────────────────────────────
+
+/// Silently ignores commands from these users.
+pub struct Blocklist {
+    users: HashSet<i64>,
+}
+
+impl Blocklist {
+    pub fn new(users: impl IntoIterator<Item = i64>) -> Self {
+        Blocklist { users: users.into_iter().collect() }
+    }
+}
+
+impl Middleware for Blocklist {
+    fn name(&self) -> &'static str { "blocklist" }
+
+    fn before<'a>(&'a self, call: &'a mut Call) -> Hook<'a, Flow> {
+        let blocked = call.sender_id().is_some_and(|id| self.users.contains(&id));
+        Box::pin(async move { if blocked { Flow::Stop } else { Flow::Continue } })
+    }
+}
+
+// ─── Maintenance ──────────────────────────────────────────────────────────────
+
+/// Answers every command with a notice, except for the bot owners.
+pub struct Maintenance {
+    owners: HashSet<i64>,
+}
+
+impl Maintenance {
+    pub fn new(owners: impl IntoIterator<Item = i64>) -> Self {
+        Maintenance { owners: owners.into_iter().collect() }
+    }
+}
+
+impl Middleware for Maintenance {
+    fn name(&self) -> &'static str { "maintenance" }
+
+    fn before<'a>(&'a self, call: &'a mut Call) -> Hook<'a, Flow> {
+        Box::pin(async move {
+            if call.sender_id().is_some_and(|id| self.owners.contains(&id)) {
+                return Flow::Continue;
+            }
+            call.reply("🛠️ The bot is down for maintenance — please try again later.").await;
+            Flow::Stop
+        })
+    }
+}
+
+// ─── Disabled commands ────────────────────────────────────────────────────────
+
+/// Turns commands off by name. Aliases are resolved, so disabling `flip` also blocks `/coin`.
+pub struct DisabledCommands {
+    names: HashSet<&'static str>,
+}
+
+impl DisabledCommands {
+    pub fn new(names: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let names = names.into_iter()
+            .filter_map(|n| match registry::find(n.as_ref()) {
+                Some(cmd) => Some(cmd.name),
+                None => {
+                    eprintln!("⚠️   Can't disable unknown command /{}", n.as_ref());
+                    None
+                }
+            })
+            .collect();
+        DisabledCommands { names }
+    }
+}
+
+impl Middleware for DisabledCommands {
+    fn name(&self) -> &'static str { "disabled-commands" }
+
+    fn before<'a>(&'a self, call: &'a mut Call) -> Hook<'a, Flow> {
+        Box::pin(async move {
+            if !self.names.contains(call.command.name) {
+                return Flow::Continue;
+            }
+            call.reply(&format!("🚫 /{} is disabled.", call.cmd)).await;
+            Flow::Stop
+        })
+    }
+}
+
+// ─── Rate limit ───────────────────────────────────────────────────────────────
+
+/// Past this many tracked users, those with no hits left in the window are dropped.
+const PRUNE_AT: usize = 10_000;
+
+/// Sliding-window limit per user. The first rejected command gets a "slow down" reply;
+/// the rest of the burst is dropped silently.
+pub struct RateLimit {
+    max: usize,
+    window: Duration,
+    users: Mutex<HashMap<i64, Window>>,
+}
+
+#[derive(Default)]
+struct Window {
+    hits: VecDeque<Instant>,
+    warned: bool,
+}
+
+impl RateLimit {
+    pub fn new(max: usize, window: Duration) -> Self {
+        RateLimit { max, window, users: Mutex::new(HashMap::new()) }
+    }
+
+    /// `"5/10"` → 5 commands per 10 seconds
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (max, secs) = spec.split_once('/')?;
+        let max: usize = max.trim().parse().ok().filter(|&n| n > 0)?;
+        let secs: u64 = secs.trim().trim_end_matches('s').parse().ok().filter(|&n| n > 0)?;
+        Some(RateLimit::new(max, Duration::from_secs(secs)))
+    }
+
+    /// Records a hit; `Some(warn)` means the command is over the limit.
+    fn hit(&self, user_id: i64) -> Option<bool> {
+        let now = Instant::now();
+        let mut users = self.users.lock().unwrap_or_else(|e| e.into_inner());
+        if users.len() >= PRUNE_AT {
+            users.retain(|_, win| win.hits.back().is_some_and(|&t| now.duration_since(t) < self.window));
+        }
+        let win = users.entry(user_id).or_default();
+        while win.hits.front().is_some_and(|&t| now.duration_since(t) >= self.window) {
+            win.hits.pop_front();
+        }
+        if win.hits.len() < self.max {
+            win.hits.push_back(now);
+            win.warned = false;
+            return None;
+        }
+        Some(!std::mem::replace(&mut win.warned, true))
+    }
+}
+
+impl Middleware for RateLimit {
+    fn name(&self) -> &'static str { "rate-limit" }
+
+    fn before<'a>(&'a self, call: &'a mut Call) -> Hook<'a, Flow> {
+        let over = call.sender_id().and_then(|id| self.hit(id));
+        Box::pin(async move {
+            match over {
+                None => Flow::Continue,
+                Some(warn) => {
+                    if warn {
+                        call.reply("⏳ Slow down — you're sending commands too fast.").await;
+                    }
+                    Flow::Stop
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,410 @@
+//! Offline stand-in for the Telegram Bot API.
+//!
+//! [`MockApi::start`] binds a tiny HTTP server on localhost and [`MockApi::bot`] returns a
+//! real `tgbotrs::Bot` pointed at it, so command code runs unmodified. Every call the bot
+//! makes is recorded and answered with a plausible result; fake updates are built with
+//! [`FakeMessage`] / [`callback`] and fed straight into `handler::handle_update`.
+//!
+//! ```ignore
+//! let api   = MockApi::start().await;
+//! let bot   = api.bot().await;
+//! let alice = FakeUser::new(1, "Alice");
+//! handle_update(bot, FakeMessage::new(-100, &alice, "/ping").into_update(), state).await;
+//! assert_eq!(api.calls_to("sendMessage").len(), 1);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use serde_json::{json, Value};
+use tgbotrs::{Bot, Update};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// User id the mock reports for the bot itself (`getMe`)
+pub const BOT_ID: i64 = 4242;
+
+/// Shared between message and update ids so every fake object is unique
+static NEXT_ID: AtomicI64 = AtomicI64::new(1);
+
+fn next_id() -> i64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// One recorded Bot API request.
+#[derive(Debug, Clone)]
+pub struct ApiCall {
+    /// Method name as sent, e.g. `sendMessage`
+    pub method: String,
+    /// JSON parameters (multipart bodies are kept as a raw string)
+    pub params: Value,
+    /// What the mock answered with — `null` for calls set up to [`fail`](MockApi::fail)
+    pub result: Value,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    calls: Vec<ApiCall>,
+    /// (chat_id, user_id) pairs that `getChatMember` reports as administrators
+    admins: HashSet<(i64, i64)>,
+    /// (chat_id, user_id) pairs reported as the chat's owner (`creator`)
+    owners: HashSet<(i64, i64)>,
+    /// (chat_id, user_id) → custom admin title
+    titles: HashMap<(i64, i64), String>,
+    /// (chat_id, user_id) → the only `can_*` rights that admin has; unlisted admins have all
+    rights: HashMap<(i64, i64), Vec<String>>,
+    /// (chat_id, user_id) → the last `restrictChatMember` permissions and `until_date`
+    restrictions: HashMap<(i64, i64), (Value, i64)>,
+    /// method → error description to answer with instead of success
+    failures: HashMap<String, String>,
+}
+
+/// Handle to a running mock server. Cheap to clone; the server lives as long as the runtime.
+#[derive(Debug, Clone)]
+pub struct MockApi {
+    /// Base URL to hand to `Bot::with_api_url`
+    pub url: String,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MockApi {
+    /// Binds an ephemeral port on 127.0.0.1 and starts answering requests.
+    pub async fn start() -> MockApi {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("mock API: bind failed");
+        let url      = format!("http://{}", listener.local_addr().expect("mock API: no local addr"));
+        let inner    = Arc::new(Mutex::new(Inner::default()));
+        let shared   = Arc::clone(&inner);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, Arc::clone(&shared)));
+            }
+        });
+        MockApi { url, inner }
+    }
+
+    /// A `Bot` that talks to this server. Runs `getMe` like the real constructor.
+    pub async fn bot(&self) -> Bot {
+        Bot::with_api_url("123456:MOCK", &self.url).await.expect("mock API: getMe failed")
+    }
+
+    /// Make `getChatMember` / `getChatAdministrators` report `user_id` as an admin of `chat_id`.
+    pub fn set_admin(&self, chat_id: i64, user_id: i64) {
+        self.lock().admins.insert((chat_id, user_id));
+    }
+
+    /// Make `user_id` the owner of `chat_id`, listed among its admins.
+    pub fn set_owner(&self, chat_id: i64, user_id: i64) {
+        let mut st = self.lock();
+        st.admins.insert((chat_id, user_id));
+        st.owners.insert((chat_id, user_id));
+    }
+
+    /// Give an admin a custom title, as shown in `getChatAdministrators`.
+    pub fn set_title(&self, chat_id: i64, user_id: i64, title: &str) {
+        self.lock().titles.insert((chat_id, user_id), title.to_string());
+    }
+
+    /// Limit an admin to these rights, named as in the Bot API (`can_pin_messages`, …).
+    pub fn set_rights(&self, chat_id: i64, user_id: i64, rights: &[&str]) {
+        self.lock().rights.insert((chat_id, user_id), rights.iter().map(|r| r.to_string()).collect());
+    }
+
+    /// Answer every future `method` call with an API error.
+    pub fn fail(&self, method: &str, description: &str) {
+        self.lock().failures.insert(method.to_string(), description.to_string());
+    }
+
+    /// Everything recorded so far, oldest first.
+    pub fn calls(&self) -> Vec<ApiCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Like [`calls`](Self::calls) but clears the log.
+    pub fn take_calls(&self) -> Vec<ApiCall> {
+        std::mem::take(&mut self.lock().calls)
+    }
+
+    /// Parameters of every recorded call to `method`.
+    pub fn calls_to(&self, method: &str) -> Vec<Value> {
+        self.lock().calls.iter().filter(|c| c.method == method).map(|c| c.params.clone()).collect()
+    }
+
+    /// `text` of every `sendMessage`, in order — the usual thing to assert on.
+    pub fn sent_texts(&self) -> Vec<String> {
+        self.calls_to("sendMessage").iter()
+            .filter_map(|p| p["text"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ─── HTTP plumbing ────────────────────────────────────────────────────────────
+
+/// Minimal HTTP/1.1: keep-alive, `Content-Length` bodies only — which is all reqwest sends here.
+async fn serve(stream: TcpStream, inner: Arc<Mutex<Inner>>) {
+    let (read, mut write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await.unwrap_or(0) == 0 { return; }
+        let path = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+
+        let mut content_length = 0usize;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap_or(0) == 0 { return; }
+            let line = line.trim_end();
+            if line.is_empty() { break; }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0u8; content_length];
+        if reader.read_exact(&mut body).await.is_err() { return; }
+
+        let method = path.rsplit('/').next().unwrap_or("").to_string();
+        let params = serde_json::from_slice(&body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned()));
+        let payload = {
+            let mut st = inner.lock().unwrap_or_else(|e| e.into_inner());
+            let (payload, result) = match st.failures.get(&method) {
+                Some(description) => (json!({ "ok": false, "error_code": 400, "description": description }), Value::Null),
+                None => {
+                    let result = respond(&mut st, &method, &params);
+                    (json!({ "ok": true, "result": result }), result)
+                }
+            };
+            st.calls.push(ApiCall { method, params, result });
+            payload
+        }.to_string();
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{payload}",
+            payload.len()
+        );
+        if write.write_all(response.as_bytes()).await.is_err() { return; }
+    }
+}
+
+/// Canned `result` for each method; anything unlisted just succeeds with `true`.
+fn respond(st: &mut Inner, method: &str, params: &Value) -> Value {
+    let chat_id = params["chat_id"].as_i64().unwrap_or(0);
+    match method {
+        "restrictChatMember" => {
+            let user_id = params["user_id"].as_i64().unwrap_or(0);
+            let until = params["until_date"].as_i64().unwrap_or(0);
+            st.restrictions.insert((chat_id, user_id), (params["permissions"].clone(), until));
+            json!(true)
+        }
+        "getMe" => json!({
+            "id": BOT_ID, "is_bot": true, "first_name": "MockBot", "username": "mock_bot"
+        }),
+        "getChatMember" => {
+            let user_id = params["user_id"].as_i64().unwrap_or(0);
+            member_json(st, chat_id, user_id)
+        }
+        "getChatAdministrators" => Value::Array(
+            st.admins.iter()
+                .filter(|(cid, _)| *cid == chat_id)
+                .map(|(_, uid)| member_json(st, chat_id, *uid))
+                .collect(),
+        ),
+        "getChatMemberCount"   => json!(42),
+        "exportChatInviteLink" => json!("https://t.me/+mock"),
+        m if m.starts_with("send") || m == "copyMessage" || m == "forwardMessage" => json!({
+            "message_id": next_id(),
+            "date":       chrono::Utc::now().timestamp(),
+            "chat":       chat_json(chat_id),
+            "from":       user_json(BOT_ID, "MockBot", Some("mock_bot")),
+            "text":       params["text"].as_str().unwrap_or(""),
+        }),
+        _ => json!(true),
+    }
+}
+
+fn member_json(st: &Inner, chat_id: i64, user_id: i64) -> Value {
+    let user = user_json(user_id, "User", None);
+    if !st.admins.contains(&(chat_id, user_id)) {
+        // Restricted until every permission is given back, like Telegram does
+        let restricted = st.restrictions.get(&(chat_id, user_id))
+            .filter(|(perms, _)| perms.as_object().is_some_and(|p| p.values().any(|v| v == false)));
+        let Some((perms, until)) = restricted else {
+            return json!({ "status": "member", "user": user });
+        };
+        let mut member = json!({ "status": "restricted", "user": user, "is_member": true, "until_date": until });
+        for (key, value) in perms.as_object().into_iter().flatten() {
+            member[key] = value.clone();
+        }
+        return member;
+    }
+    if st.owners.contains(&(chat_id, user_id)) {
+        return json!({
+            "status": "creator", "user": user, "is_anonymous": false,
+            "custom_title": st.titles.get(&(chat_id, user_id)),
+        });
+    }
+    let mut admin = json!({
+        "status": "administrator", "user": user, "custom_title": st.titles.get(&(chat_id, user_id)),
+        "can_be_edited": false, "is_anonymous": false,
+        "can_manage_chat": true, "can_delete_messages": true, "can_manage_video_chats": true,
+        "can_restrict_members": true, "can_promote_members": true, "can_change_info": true,
+        "can_invite_users": true, "can_post_stories": true, "can_edit_stories": true,
+        "can_delete_stories": true, "can_pin_messages": true, "can_manage_topics": true,
+    });
+    if let Some(rights) = st.rights.get(&(chat_id, user_id)) {
+        for (key, value) in admin.as_object_mut().into_iter().flatten() {
+            if key.starts_with("can_") && key != "can_be_edited" {
+                *value = json!(rights.contains(key));
+            }
+        }
+    }
+    admin
+}
+
+fn chat_json(chat_id: i64) -> Value {
+    if chat_id < 0 {
+        json!({ "id": chat_id, "type": "supergroup", "title": "Mock Group" })
+    } else {
+        json!({ "id": chat_id, "type": "private", "first_name": "User" })
+    }
+}
+
+fn user_json(id: i64, first_name: &str, username: Option<&str>) -> Value {
+    json!({ "id": id, "is_bot": false, "first_name": first_name, "username": username })
+}
+
+// ─── Fake updates ─────────────────────────────────────────────────────────────
+
+/// Someone sending messages in fake updates.
+#[derive(Debug, Clone)]
+pub struct FakeUser {
+    pub id: i64,
+    pub first_name: String,
+    pub username: Option<String>,
+}
+
+impl FakeUser {
+    pub fn new(id: i64, first_name: &str) -> Self {
+        FakeUser { id, first_name: first_name.to_string(), username: None }
+    }
+
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = Some(username.to_string());
+        self
+    }
+
+    pub fn json(&self) -> Value {
+        user_json(self.id, &self.first_name, self.username.as_deref())
+    }
+}
+
+/// A message to inject, built up as raw Bot API JSON.
+#[derive(Debug, Clone)]
+pub struct FakeMessage {
+    json: Value,
+}
+
+impl FakeMessage {
+    pub fn new(chat_id: i64, from: &FakeUser, text: &str) -> Self {
+        FakeMessage {
+            json: json!({
+                "message_id": next_id(),
+                "date":       chrono::Utc::now().timestamp(),
+                "chat":       chat_json(chat_id),
+                "from":       from.json(),
+                "text":       text,
+            }),
+        }
+    }
+
+    /// `user` joining the chat: a `new_chat_members` service message with no text.
+    pub fn join(chat_id: i64, user: &FakeUser) -> Self {
+        FakeMessage::new(chat_id, user, "")
+            .with("text", Value::Null)
+            .with("new_chat_members", json!([user.json()]))
+    }
+
+    /// `user` leaving the chat: a `left_chat_member` service message with no text.
+    pub fn leave(chat_id: i64, user: &FakeUser) -> Self {
+        FakeMessage::new(chat_id, user, "")
+            .with("text", Value::Null)
+            .with("left_chat_member", user.json())
+    }
+
+    /// Wrap a message the bot sent (an [`ApiCall::result`]) so it can be replied to.
+    pub fn from_json(json: Value) -> Self {
+        FakeMessage { json }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.json["message_id"].as_i64().unwrap_or(0)
+    }
+
+    pub fn reply_to(self, other: &FakeMessage) -> Self {
+        self.with("reply_to_message", other.json.clone())
+    }
+
+    /// Set any other `Message` field, e.g. `new_chat_members` or `sticker`.
+    pub fn with(mut self, field: &str, value: Value) -> Self {
+        self.json[field] = value;
+        self
+    }
+
+    pub fn into_update(self) -> Update {
+        update_from(json!({ "update_id": next_id(), "message": self.json }))
+    }
+}
+
+/// An inline-button press by `from` on bot message `message_id`.
+pub fn callback(chat_id: i64, from: &FakeUser, message_id: i64, data: &str) -> Update {
+    update_from(json!({
+        "update_id": next_id(),
+        "callback_query": {
+            "id":            next_id().to_string(),
+            "from":          from.json(),
+            "chat_instance": "mock",
+            "data":          data,
+            "message": {
+                "message_id": message_id,
+                "date":       chrono::Utc::now().timestamp(),
+                "chat":       chat_json(chat_id),
+                "from":       user_json(BOT_ID, "MockBot", Some("mock_bot")),
+                "text":       "",
+            },
+        },
+    }))
+}
+
+/// `user`'s status in the chat changing, as a `chat_member` update. Statuses are Bot API
+/// names: `member`, `administrator`, `left`, …
+pub fn member_update(chat_id: i64, user: &FakeUser, old: &str, new: &str) -> Update {
+    let member = |status: &str| match status {
+        "administrator" => json!({
+            "status": status, "user": user.json(), "can_be_edited": true, "is_anonymous": false,
+            "can_manage_chat": true, "can_delete_messages": false, "can_manage_video_chats": false,
+            "can_restrict_members": false, "can_promote_members": false, "can_change_info": false,
+            "can_invite_users": false, "can_post_stories": false, "can_edit_stories": false,
+            "can_delete_stories": false,
+        }),
+        _ => json!({ "status": status, "user": user.json() }),
+    };
+    update_from(json!({
+        "update_id": next_id(),
+        "chat_member": {
+            "chat":            chat_json(chat_id),
+            "from":            user.json(),
+            "date":            chrono::Utc::now().timestamp(),
+            "old_chat_member": member(old),
+            "new_chat_member": member(new),
+        },
+    }))
+}
+
+fn update_from(json: Value) -> Update {
+    serde_json::from_value(json).expect("mock API: fake update doesn't match the Update type")
+}
@@ -0,0 +1,196 @@
+//! Moderation log: every admin action is appended to the chat's audit log in storage
+//! (queried with `/modlog`) and posted to its log channel, if one is set with `/setlog`,
+//! so moderators can review each other's work.
+
+use std::time::Duration;
+use tgbotrs::{Bot, LinkPreviewOptions, Message, User};
+use tgbotrs::gen_methods::SendMessageParams;
+use crate::duration;
+use crate::punish::Punishment;
+use crate::registry::escape;
+use crate::state::{Action, AuditEntry, SharedState};
+
+impl From<Punishment> for Action {
+    fn from(p: Punishment) -> Action {
+        match p {
+            Punishment::Ban | Punishment::TempBan(_)   => Action::Ban,
+            Punishment::Kick                           => Action::Kick,
+            Punishment::Mute | Punishment::TempMute(_) => Action::Mute,
+        }
+    }
+}
+
+/// One moderation action, built up and then [`record`](Entry::record)ed.
+#[derive(Debug, Clone)]
+pub struct Entry<'a> {
+    action: Action,
+    /// Who acted, when it isn't the sender of `msg` — the bot, for automatic actions
+    actor: Option<&'a User>,
+    target: Option<&'a User>,
+    reason: &'a str,
+    /// Seconds, for timed bans and mutes
+    duration: Option<u64>,
+}
+
+impl<'a> Entry<'a> {
+    pub fn new(action: Action) -> Self {
+        Entry { action, actor: None, target: None, reason: "", duration: None }
+    }
+
+    pub fn by(mut self, user: &'a User) -> Self {
+        self.actor = Some(user);
+        self
+    }
+
+    pub fn target(mut self, user: &'a User) -> Self {
+        self.target = Some(user);
+        self
+    }
+
+    pub fn reason(mut self, reason: &'a str) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn duration(mut self, secs: Option<u64>) -> Self {
+        self.duration = secs;
+        self
+    }
+
+    /// Appends the entry to the chat's audit log and posts it to the log channel, if any.
+    /// `msg` is the admin's command, or the offending message for automatic actions; the
+    /// entry points at the message it replied to, or at `msg` itself.
+    pub async fn record(self, bot: &Bot, state: &SharedState, msg: &Message) {
+        let channel = {
+            let mut st = state.lock().await;
+            st.store.append_audit(msg.chat.id, &self.audit_entry(msg));
+            st.store.chat_settings(msg.chat.id).log_channel
+        };
+        let Some(channel) = channel else { return };
+        let params = SendMessageParams::new()
+            .parse_mode("HTML")
+            .link_preview_options(LinkPreviewOptions { is_disabled: Some(true), ..Default::default() });
+        if let Err(e) = bot.send_message(channel, self.render(msg), Some(params)).await {
+            eprintln!("⚠️   Failed to post to log channel {channel}: {e}");
+        }
+    }
+
+    fn actor<'m>(&'m self, msg: &'m Message) -> Option<&'m User> {
+        self.actor.or(msg.from.as_deref())
+    }
+
+    /// The message acted on: what an admin's command replied to, or the offending message itself
+    fn about<'m>(&self, msg: &'m Message) -> &'m Message {
+        let is_command = self.actor.is_none_or(|a| msg.from.as_ref().is_some_and(|f| f.id == a.id));
+        if is_command { msg.reply_to_message.as_deref().unwrap_or(msg) } else { msg }
+    }
+
+    fn audit_entry(&self, msg: &Message) -> AuditEntry {
+        let about = self.about(msg);
+        let actor = self.actor(msg);
+        AuditEntry {
+            action:      self.action,
+            actor_id:    actor.map_or(0, |u| u.id),
+            actor_name:  actor.map(|u| u.first_name.clone()).unwrap_or_default(),
+            target_id:   self.target.map(|u| u.id),
+            target_name: self.target.map(|u| u.first_name.clone()).unwrap_or_default(),
+            reason:      self.reason.to_string(),
+            duration:    self.duration,
+            message_id:  about.message_id,
+            at:          chrono::Utc::now().timestamp(),
+        }
+    }
+
+    fn render(&self, msg: &Message) -> String {
+        let chat = msg.chat.title.as_deref().unwrap_or("this chat");
+        let mut lines = vec![
+            format!("#{}", self.action.name().to_uppercase()),
+            format!("<b>Chat:</b> {} [<code>{}</code>]", escape(chat), msg.chat.id),
+        ];
+        if let Some(actor) = self.actor(msg) {
+            lines.push(format!("<b>Admin:</b> {}", user_link(actor)));
+        }
+        if let Some(target) = self.target {
+            lines.push(format!("<b>User:</b> {}", user_link(target)));
+        }
+        if let Some(secs) = self.duration {
+            lines.push(format!("<b>Duration:</b> {}", duration::format(Duration::from_secs(secs))));
+        }
+        if !self.reason.is_empty() {
+            lines.push(format!("<b>Reason:</b> {}", escape(self.reason)));
+        }
+        if let Some(link) = message_link(self.about(msg)) {
+            lines.push(format!("<a href=\"{link}\">Go to message</a>"));
+        }
+        lines.join("\n")
+    }
+}
+
+fn user_link(user: &User) -> String {
+    format!("<a href=\"tg://user?id={0}\">{1}</a> [<code>{0}</code>]", user.id, escape(&user.first_name))
+}
+
+/// `t.me` link to a message in a public or private supergroup; basic groups have none.
+pub(crate) fn message_link(msg: &Message) -> Option<String> {
+    if let Some(username) = &msg.chat.username {
+        return Some(format!("https://t.me/{username}/{}", msg.message_id));
+    }
+    let internal = msg.chat.id.to_string().strip_prefix("-100").filter(|id| !id.is_empty())?.to_string();
+    Some(format!("https://t.me/c/{internal}/{}", msg.message_id))
+}
+
+// ─── Reading the audit log ────────────────────────────────────────────────────
+
+pub(crate) fn timestamp(at: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp(at, 0).map(|t| t.format(format).to_string()).unwrap_or_default()
+}
+
+/// One line of `/modlog` output (HTML)
+pub fn summary(e: &AuditEntry) -> String {
+    let mut line = format!("<code>{}</code> #{}", timestamp(e.at, "%Y-%m-%d %H:%M"), e.action.name());
+    if e.target_id.is_some() {
+        line.push_str(&format!(" <b>{}</b>", escape(&e.target_name)));
+    }
+    line.push_str(&format!(" by {}", escape(&e.actor_name)));
+    if let Some(secs) = e.duration {
+        line.push_str(&format!(" for {}", duration::format(Duration::from_secs(secs))));
+    }
+    if !e.reason.is_empty() {
+        line.push_str(&format!(" — <i>{}</i>", escape(&e.reason)));
+    }
+    line
+}
+
+/// One CSV field of user-supplied text, quoted as RFC 4180 asks when it needs to be.
+/// Text a spreadsheet would run as a formula (`=`, `+`, `-`, `@`, tab or CR first) gets a
+/// leading `'` so it's shown as text instead.
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("\"'{}\"", value.replace('"', "\"\""))
+    } else if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The whole log as CSV with a header row.
+pub fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut csv = String::from("time,action,admin_id,admin,user_id,user,duration_secs,reason,message_id\n");
+    for e in entries {
+        let row = [
+            timestamp(e.at, "%Y-%m-%dT%H:%M:%SZ"),
+            e.action.name().to_string(),
+            e.actor_id.to_string(),
+            csv_field(&e.actor_name),
+            e.target_id.map(|id| id.to_string()).unwrap_or_default(),
+            csv_field(&e.target_name),
+            e.duration.map(|d| d.to_string()).unwrap_or_default(),
+            csv_field(&e.reason),
+            e.message_id.to_string(),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
@@ -0,0 +1,104 @@
+//! Ban, kick, mute and their timed variants — used by `/ban`, `/mute` and friends and by
+//! automatic rules like the warn limit. Stored inside `ChatSettings`, so it's serde-friendly.
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tgbotrs::gen_methods::{BanChatMemberParams, RestrictChatMemberParams, UnbanChatMemberParams};
+use tgbotrs::{Bot, BotError};
+use crate::cmd::admin::all_perms;
+use crate::duration;
+
+/// Telegram treats restrictions shorter than 30 seconds or longer than 366 days as forever.
+pub const MIN_TEMP: Duration = Duration::from_secs(30);
+pub const MAX_TEMP: Duration = Duration::from_secs(366 * 86_400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Punishment {
+    Ban,
+    Kick,
+    Mute,
+    /// Mute for this many seconds
+    TempMute(u64),
+    /// Ban for this many seconds
+    TempBan(u64),
+}
+
+impl Punishment {
+    /// `ban`, `kick`, `mute`, `tban <duration>` or `tmute <duration>`
+    pub fn parse(mode: &str, duration: Option<&str>) -> Option<Punishment> {
+        match mode.to_lowercase().as_str() {
+            "ban"   => Some(Punishment::Ban),
+            "kick"  => Some(Punishment::Kick),
+            "mute"  => Some(Punishment::Mute),
+            "tban"  => Some(Punishment::TempBan(temp_secs(duration?)?)),
+            "tmute" => Some(Punishment::TempMute(temp_secs(duration?)?)),
+            _ => None,
+        }
+    }
+
+    /// Past tense for announcements: "has been {label}"
+    pub fn label(self) -> String {
+        match self {
+            Punishment::Ban         => "banned".into(),
+            Punishment::Kick        => "kicked".into(),
+            Punishment::Mute        => "muted".into(),
+            Punishment::TempMute(s) => format!("muted for {}", duration::format(Duration::from_secs(s))),
+            Punishment::TempBan(s)  => format!("banned for {}", duration::format(Duration::from_secs(s))),
+        }
+    }
+
+    /// Length of a timed punishment in seconds
+    pub fn secs(self) -> Option<u64> {
+        match self {
+            Punishment::TempMute(s) | Punishment::TempBan(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// When a timed punishment applied now would be lifted, as Unix time
+    pub fn until_date(self) -> Option<i64> {
+        self.secs().map(|s| chrono::Utc::now().timestamp() + s as i64)
+    }
+
+    /// The next option, for cycling through them on a settings button
+    pub fn next(self) -> Punishment {
+        match self {
+            Punishment::Ban         => Punishment::Kick,
+            Punishment::Kick        => Punishment::Mute,
+            Punishment::Mute        => Punishment::TempMute(3600),
+            Punishment::TempMute(_) => Punishment::TempBan(86_400),
+            Punishment::TempBan(_)  => Punishment::Ban,
+        }
+    }
+
+    pub async fn apply(self, bot: &Bot, chat_id: i64, user_id: i64) -> Result<(), BotError> {
+        match self {
+            Punishment::Ban => {
+                bot.ban_chat_member(chat_id, user_id, None).await?;
+            }
+            Punishment::Kick => {
+                bot.ban_chat_member(chat_id, user_id, None).await?;
+                bot.unban_chat_member(chat_id, user_id, Some(UnbanChatMemberParams::new().only_if_banned(true))).await?;
+            }
+            Punishment::Mute => {
+                bot.restrict_chat_member(chat_id, user_id, all_perms(false), None).await?;
+            }
+            Punishment::TempMute(_) => {
+                let params = RestrictChatMemberParams::new().until_date(self.until_date().unwrap_or(0));
+                bot.restrict_chat_member(chat_id, user_id, all_perms(false), Some(params)).await?;
+            }
+            Punishment::TempBan(_) => {
+                let params = BanChatMemberParams::new().until_date(self.until_date().unwrap_or(0));
+                bot.ban_chat_member(chat_id, user_id, Some(params)).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A duration Telegram will honour as temporary, in seconds
+pub fn temp_secs(text: &str) -> Option<u64> {
+    let d = duration::parse(text)?;
+    (MIN_TEMP..=MAX_TEMP).contains(&d).then_some(d.as_secs())
+}
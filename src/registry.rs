@@ -0,0 +1,382 @@
+//! Single source of truth for every command.
+//!
+//! Each [`Command`] declares its name, aliases, help text, category, required permission
+//! and handler once. `handler::handle_update` dispatches through [`find`], `main` builds
+//! the Telegram command menu from [`commands`], and `/help` renders each [`Category`]
+//! from the same list — adding a command means adding one entry below.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use tgbotrs::{Bot, Message};
+use crate::cmd::{admin, antiflood, approvals, blocklist, captcha, federation, fun, games, info, locks, nightmode, notes, polls, promote, reports, restrict, settings, util, welcome};
+use crate::state::SharedState;
+
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+pub type Handler   = fn(Ctx) -> BoxFuture;
+
+/// Everything a command handler gets to work with.
+#[derive(Clone)]
+pub struct Ctx {
+    pub bot: Bot,
+    pub msg: Message,
+    /// Text after the command, trimmed
+    pub args: String,
+    pub state: SharedState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Fun,
+    Util,
+    Admin,
+    Greetings,
+    Protection,
+    Federations,
+    Games,
+    Notes,
+    Polls,
+    Info,
+}
+
+impl Category {
+    /// Order of the `/help` menu buttons
+    pub const ALL: [Category; 10] = [
+        Category::Fun, Category::Util, Category::Admin, Category::Greetings, Category::Protection,
+        Category::Federations, Category::Games, Category::Notes, Category::Polls, Category::Info,
+    ];
+
+    /// Callback key, as in `help:<key>`
+    pub fn key(self) -> &'static str {
+        match self {
+            Category::Fun         => "fun",
+            Category::Util        => "util",
+            Category::Admin       => "admin",
+            Category::Greetings   => "greetings",
+            Category::Protection  => "protection",
+            Category::Federations => "feds",
+            Category::Games       => "games",
+            Category::Notes       => "notes",
+            Category::Polls       => "polls",
+            Category::Info        => "info",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Category> {
+        Category::ALL.into_iter().find(|c| c.key() == key)
+    }
+
+    pub fn button(self) -> &'static str {
+        match self {
+            Category::Fun         => "🎉 Fun",
+            Category::Util        => "🔧 Utility",
+            Category::Admin       => "👮 Admin",
+            Category::Greetings   => "👋 Greetings",
+            Category::Protection  => "🛡️ Protection",
+            Category::Federations => "🌐 Federations",
+            Category::Games       => "🎮 Games",
+            Category::Notes       => "📝 Notes",
+            Category::Polls       => "📊 Polls",
+            Category::Info        => "ℹ️ Info",
+        }
+    }
+
+    /// HTML heading of the help section
+    pub fn title(self) -> &'static str {
+        match self {
+            Category::Fun         => "🎉 <b>Fun Commands</b>",
+            Category::Util        => "🔧 <b>Utility Commands</b>",
+            Category::Admin       => "👮 <b>Admin Commands</b> <i>(groups only, reply to a user)</i>",
+            Category::Greetings   => "👋 <b>Greeting Commands</b> <i>(groups only, admins)</i>",
+            Category::Protection  => "🛡️ <b>Protection Commands</b> <i>(groups only, admins)</i>",
+            Category::Federations => "🌐 <b>Federation Commands</b> <i>(bans shared across groups)</i>",
+            Category::Games       => "🎮 <b>Game Commands</b>",
+            Category::Notes       => "📝 <b>Notes Commands</b>",
+            Category::Polls       => "📊 <b>Poll Commands</b>",
+            Category::Info        => "ℹ️ <b>Info Commands</b>",
+        }
+    }
+
+    /// Extra HTML line under the command list
+    pub fn footer(self) -> Option<&'static str> {
+        match self {
+            Category::Polls       => Some("<i>Separate everything with</i> <code>|</code>"),
+            Category::Admin       => Some("<i>[user]: reply to them, or give their @username, user ID or a mention</i>"),
+            Category::Greetings   => Some("<i>Placeholders:</i> <code>{first} {mention} {chatname} {count}</code> …"),
+            Category::Federations => Some("<i>Create one with /newfed, then run /joinfed &lt;ID&gt; in each group</i>"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Anyone,
+    /// Sender must be a chat owner or administrator — checked before the handler runs
+    Admin,
+}
+
+pub struct Command {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Argument hint shown in `/help`, e.g. `<text>` or `[N]`
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub category: Category,
+    pub permission: Permission,
+    /// Listed in Telegram's command menu (`set_my_commands`)
+    pub menu: bool,
+    pub handler: Handler,
+}
+
+impl Command {
+    pub fn new(name: &'static str, category: Category, description: &'static str, handler: Handler) -> Self {
+        Command {
+            name,
+            aliases: &[],
+            usage: "",
+            description,
+            category,
+            permission: Permission::Anyone,
+            menu: true,
+            handler,
+        }
+    }
+
+    pub fn usage(mut self, usage: &'static str) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    pub fn admin(mut self) -> Self {
+        self.permission = Permission::Admin;
+        self
+    }
+
+    /// Keep out of Telegram's command menu (it only shows ~100); still in `/help`.
+    pub fn hidden(mut self) -> Self {
+        self.menu = false;
+        self
+    }
+
+    /// One `/help` line, HTML-escaped
+    pub fn help_line(&self) -> String {
+        let mut line = format!("/{}", self.name);
+        if !self.usage.is_empty() {
+            line.push(' ');
+            line.push_str(&escape(self.usage));
+        }
+        line.push_str(" — ");
+        line.push_str(&escape(self.description));
+        if !self.aliases.is_empty() {
+            let aliases: Vec<String> = self.aliases.iter().map(|a| format!("/{a}")).collect();
+            line.push_str(&format!(" <i>(also {})</i>", aliases.join(", ")));
+        }
+        line
+    }
+}
+
+/// Escapes user text for `parse_mode("HTML")` messages.
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// `run!(|c| module::cmd_x(&c.bot, &c.msg))` — adapts a `cmd_*` call to a [`Handler`].
+macro_rules! run {
+    (|$c:ident| $call:expr) => {
+        |$c: Ctx| -> BoxFuture { Box::pin(async move { $call.await; }) }
+    };
+}
+
+fn build() -> Vec<Command> {
+    use Category::*;
+    vec![
+        // ── Info / general
+        Command::new("start",    Info, "Welcome screen with library links", run!(|c| info::cmd_start(&c.bot, &c.msg))),
+        Command::new("help",     Info, "Interactive help menu",              run!(|c| info::cmd_help(&c.bot, &c.msg, "main")))
+            .aliases(&["commands"]),
+        Command::new("about",    Info, "About this bot & the tgbotrs library", run!(|c| info::cmd_about(&c.bot, &c.msg))),
+        Command::new("ping",     Info, "Check bot response time",            run!(|c| info::cmd_ping(&c.bot, &c.msg))),
+        Command::new("source",   Info, "tgbotrs library links",              run!(|c| info::cmd_source(&c.bot, &c.msg))),
+        Command::new("userinfo", Info, "User info (reply to see another's)", run!(|c| info::cmd_userinfo(&c.bot, &c.msg))),
+        Command::new("chatinfo", Info, "Current chat info",                  run!(|c| info::cmd_chatinfo(&c.bot, &c.msg))),
+        Command::new("members",  Info, "Member count",                       run!(|c| info::cmd_members(&c.bot, &c.msg))),
+
+        // ── Fun
+        Command::new("dice",      Fun, "Animated Telegram dice 🎲",          run!(|c| fun::cmd_dice(&c.bot, &c.msg))),
+        Command::new("roll",      Fun, "Roll an N-sided die (default d6)",   run!(|c| fun::cmd_roll(&c.bot, &c.msg, &c.args))).usage("[N]"),
+        Command::new("flip",      Fun, "Flip a coin 🪙",                     run!(|c| fun::cmd_flip(&c.bot, &c.msg))).aliases(&["coin"]),
+        Command::new("joke",      Fun, "Random programming joke 😂",         run!(|c| fun::cmd_joke(&c.bot, &c.msg))),
+        Command::new("quote",     Fun, "Inspiring developer quote 💭",       run!(|c| fun::cmd_quote(&c.bot, &c.msg))),
+        Command::new("fact",      Fun, "Random tech/programming fact 🤓",    run!(|c| fun::cmd_fact(&c.bot, &c.msg))),
+        Command::new("8ball",     Fun, "Magic 8-ball 🎱",                    run!(|c| fun::cmd_8ball(&c.bot, &c.msg, &c.args))).usage("<question>"),
+        Command::new("rps",       Fun, "Rock Paper Scissors ✊✌️🖐",          run!(|c| fun::cmd_rps(&c.bot, &c.msg))),
+        Command::new("choose",    Fun, "Pick randomly from options",         run!(|c| fun::cmd_choose(&c.bot, &c.msg, &c.args))).usage("<a> | <b> | <c>"),
+        Command::new("rate",      Fun, "Rate anything out of 10 ⭐",          run!(|c| fun::cmd_rate(&c.bot, &c.msg, &c.args))).usage("<anything>"),
+        Command::new("password",  Fun, "Generate a secure random password 🔐", run!(|c| fun::cmd_password(&c.bot, &c.msg, &c.args))).usage("[length]"),
+        Command::new("mock",      Fun, "mOcK tExT aLtErNaTiNg CaSe",         run!(|c| fun::cmd_mock(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("clap",      Fun, "Add 👏 between words",               run!(|c| fun::cmd_clap(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("shrug",     Fun, "¯\\_(ツ)_/¯",                        run!(|c| fun::cmd_shrug(&c.bot, &c.msg))),
+        Command::new("tableflip", Fun, "(╯°□°）╯︵ ┻━┻",                     run!(|c| fun::cmd_tableflip(&c.bot, &c.msg))),
+        Command::new("unflip",    Fun, "┬─┬ノ( º _ ºノ)",                    run!(|c| fun::cmd_unflip(&c.bot, &c.msg))),
+
+        // ── Utility
+        Command::new("echo",    Util, "Echo text back",                      run!(|c| util::cmd_echo(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("reverse", Util, "Reverse text",                        run!(|c| util::cmd_reverse(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("upper",   Util, "UPPERCASE text",                      run!(|c| util::cmd_upper(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("lower",   Util, "lowercase text",                      run!(|c| util::cmd_lower(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("count",   Util, "Count chars / words / lines",         run!(|c| util::cmd_count(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("calc",    Util, "Calculator with sqrt/abs/floor/ceil/round", run!(|c| util::cmd_calc(&c.bot, &c.msg, &c.args))).usage("<expr>"),
+        Command::new("b64",     Util, "Base64 encode or decode",             run!(|c| util::cmd_b64(&c.bot, &c.msg, &c.args))).usage("encode|decode <text>"),
+        Command::new("repeat",  Util, "Repeat text N times (max 10)",        run!(|c| util::cmd_repeat(&c.bot, &c.msg, &c.args))).usage("<N> <text>"),
+        Command::new("ascii",   Util, "Text → ASCII codes",                  run!(|c| util::cmd_ascii(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("binary",  Util, "Text → binary",                       run!(|c| util::cmd_binary(&c.bot, &c.msg, &c.args))).usage("<text>"),
+        Command::new("time",    Util, "Current UTC time + unix timestamp",   run!(|c| util::cmd_time(&c.bot, &c.msg))),
+        Command::new("id",      Util, "Your Telegram ID (reply to see another's)", run!(|c| util::cmd_id(&c.bot, &c.msg))),
+
+        // ── Greetings (group)
+        Command::new("welcome",      Greetings, "Greeting settings and a preview, or switch on/off 👋", run!(|c| welcome::cmd_welcome(&c.bot, &c.msg, &c.args, &c.state))).usage("[on|off]").admin(),
+        Command::new("setwelcome",   Greetings, "Set the welcome message (HTML, placeholders, buttons)", run!(|c| welcome::cmd_setwelcome(&c.bot, &c.msg, &c.args, &c.state))).usage("<text>").admin(),
+        Command::new("setgoodbye",   Greetings, "Set the goodbye message",                run!(|c| welcome::cmd_setgoodbye(&c.bot, &c.msg, &c.args, &c.state))).usage("<text|off>").admin(),
+        Command::new("resetwelcome", Greetings, "Back to the default welcome, no goodbye", run!(|c| welcome::cmd_resetwelcome(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("cleanwelcome", Greetings, "Delete the previous welcome on each join 🧹", run!(|c| welcome::cmd_cleanwelcome(&c.bot, &c.msg, &c.args, &c.state))).usage("on|off").admin(),
+        Command::new("captcha",      Greetings, "Mute new members until they solve a captcha 🤖", run!(|c| captcha::cmd_captcha(&c.bot, &c.msg, &c.args, &c.state))).usage("off|button|math|emoji").admin(),
+        Command::new("captchatime",  Greetings, "Time to solve it before being kicked ⏳", run!(|c| captcha::cmd_captchatime(&c.bot, &c.msg, &c.args, &c.state))).usage("<duration>").admin(),
+
+        // ── Protection (group)
+        Command::new("flood",         Protection, "Show the antiflood settings 🌊",        run!(|c| antiflood::cmd_flood(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("setflood",      Protection, "Act on members who send too many messages", run!(|c| antiflood::cmd_setflood(&c.bot, &c.msg, &c.args, &c.state))).usage("<messages|off> [window]").admin(),
+        Command::new("setfloodmode",  Protection, "What happens to flooders",              run!(|c| antiflood::cmd_setfloodmode(&c.bot, &c.msg, &c.args, &c.state))).usage("<action> [duration]").admin(),
+        Command::new("blacklist",     Protection, "List the blocklisted words and patterns 🚫", run!(|c| blocklist::cmd_blacklist(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("addblacklist",  Protection, "Delete messages containing a word, phrase or /regex/", run!(|c| blocklist::cmd_addblacklist(&c.bot, &c.msg, &c.args, &c.state))).usage("<trigger> [action]").admin(),
+        Command::new("unblacklist",   Protection, "Remove a blocklist trigger",            run!(|c| blocklist::cmd_unblacklist(&c.bot, &c.msg, &c.args, &c.state))).usage("<trigger>").admin(),
+        Command::new("blacklistmode", Protection, "What happens on a blocklist hit",      run!(|c| blocklist::cmd_blacklistmode(&c.bot, &c.msg, &c.args, &c.state))).usage("delete|warn|mute|ban").admin(),
+        Command::new("lock",          Protection, "Delete links, stickers, forwards, media… 🔒", run!(|c| locks::cmd_lock(&c.bot, &c.msg, &c.args, &c.state))).usage("<type…|all>").admin(),
+        Command::new("unlock",        Protection, "Allow a locked type again 🔓",          run!(|c| locks::cmd_unlock(&c.bot, &c.msg, &c.args, &c.state))).usage("<type…|all>").admin(),
+        Command::new("locks",         Protection, "Show what's locked",                   run!(|c| locks::cmd_locks(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("setperms",      Protection, "What members may send by default",     run!(|c| nightmode::cmd_setperms(&c.bot, &c.msg, &c.args, &c.state))).usage("<perm…|all|none>").admin(),
+        Command::new("nightmode",     Protection, "Lock the chat or media every night 🌙", run!(|c| nightmode::cmd_nightmode(&c.bot, &c.msg, &c.args, &c.state))).usage("<HH:MM-HH:MM> [UTC±H] [all|media] | off").admin(),
+        Command::new("approve",       Protection, "Exempt someone from antiflood, locks and the blocklist ✅", run!(|c| approvals::cmd_approve(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").admin(),
+        Command::new("unapprove",     Protection, "Take an approval back",                run!(|c| approvals::cmd_unapprove(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").admin(),
+        Command::new("approved",      Protection, "List the approved users",             run!(|c| approvals::cmd_approved(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("unapproveall",  Protection, "Remove every approval (owner only)",  run!(|c| approvals::cmd_unapproveall(&c.bot, &c.msg, &c.state))).admin().hidden(),
+
+        // ── Federations
+        Command::new("newfed",     Federations, "Create a federation 🌐",              run!(|c| federation::cmd_newfed(&c.bot, &c.msg, &c.args, &c.state))).usage("<name>"),
+        Command::new("joinfed",    Federations, "Add this chat to a federation",        run!(|c| federation::cmd_joinfed(&c.bot, &c.msg, &c.args, &c.state))).usage("<federation ID>"),
+        Command::new("leavefed",   Federations, "Take this chat out of its federation", run!(|c| federation::cmd_leavefed(&c.bot, &c.msg, &c.state))),
+        Command::new("fedadmins",  Federations, "The federation's owner and admins",    run!(|c| federation::cmd_fedadmins(&c.bot, &c.msg, &c.state))),
+        Command::new("fpromote",   Federations, "Let someone federation-ban",           run!(|c| federation::cmd_fpromote(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").hidden(),
+        Command::new("fdemote",    Federations, "Stop someone federation-banning",      run!(|c| federation::cmd_fdemote(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").hidden(),
+        Command::new("fban",       Federations, "Ban in every chat of the federation 🔨", run!(|c| federation::cmd_fban(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [reason]"),
+        Command::new("unfban",     Federations, "Lift a federation ban",                run!(|c| federation::cmd_unfban(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]"),
+        Command::new("fedbanlist", Federations, "Export the federation's bans as CSV 📄", run!(|c| federation::cmd_fedbanlist(&c.bot, &c.msg, &c.state))),
+
+        // ── Games
+        Command::new("guess",  Games, "Start a number guessing game (1–100), or make a guess", run!(|c| games::cmd_guess(&c.bot, &c.msg, &c.args, &c.state))).usage("[number]"),
+        Command::new("giveup", Games, "Reveal the number and end the game", run!(|c| games::cmd_giveup(&c.bot, &c.msg, &c.state))),
+
+        // ── Notes
+        Command::new("save",    Notes, "Save a note",                  run!(|c| notes::cmd_save(&c.bot, &c.msg, &c.args, &c.state))).usage("<name> <content>"),
+        Command::new("get",     Notes, "Get a saved note",             run!(|c| notes::cmd_get(&c.bot, &c.msg, &c.args, &c.state))).usage("<name>"),
+        Command::new("notes",   Notes, "List all notes in this chat",  run!(|c| notes::cmd_notes(&c.bot, &c.msg, &c.state))),
+        Command::new("delnote", Notes, "Delete a note",                run!(|c| notes::cmd_delnote(&c.bot, &c.msg, &c.args, &c.state))).usage("<name>"),
+
+        // ── Polls
+        Command::new("poll", Polls, "Create a poll",                        run!(|c| polls::cmd_poll(&c.bot, &c.msg, &c.args))).usage("<question> | <opt1> | <opt2> ..."),
+        Command::new("quiz", Polls, "Create a quiz (first option = correct)", run!(|c| polls::cmd_quiz(&c.bot, &c.msg, &c.args))).usage("<question> | <correct> | <wrong1> ..."),
+
+        // ── Admin (group)
+        Command::new("ban",        Admin, "Ban user, optionally for a while 🔨", run!(|c| admin::cmd_ban(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [duration] [reason]").admin(),
+        Command::new("tban",       Admin, "Ban user temporarily ⏳",            run!(|c| admin::cmd_tban(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] <duration> [reason]").admin(),
+        Command::new("kick",       Admin, "Kick user (ban + immediate unban) 👢", run!(|c| admin::cmd_kick(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [reason]").admin(),
+        Command::new("mute",       Admin, "Remove all send permissions 🔇",     run!(|c| admin::cmd_mute(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [duration] [reason]").admin(),
+        Command::new("tmute",      Admin, "Mute user temporarily ⏳",           run!(|c| admin::cmd_tmute(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] <duration> [reason]").admin(),
+        Command::new("unmute",     Admin, "Restore all send permissions 🔊",    run!(|c| admin::cmd_unmute(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").admin(),
+        Command::new("restrict",   Admin, "Take away only some send permissions 🔐", run!(|c| restrict::cmd_restrict(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [perm…] [duration]").admin(),
+        Command::new("unrestrict", Admin, "Give back some or all send permissions 🔓", run!(|c| restrict::cmd_unrestrict(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [perm…]").admin(),
+        Command::new("perms",      Admin, "What a member may and may not send",  run!(|c| restrict::cmd_perms(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]"),
+        Command::new("warn",       Admin, "Warn user — acts at the chat's warn limit ⚠️", run!(|c| admin::cmd_warn(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [reason]").admin(),
+        Command::new("warns",      Admin, "User's warnings: who gave them, when",run!(|c| admin::cmd_warns(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]"),
+        Command::new("warnlimit",  Admin, "Warnings before the warn action",    run!(|c| admin::cmd_warnlimit(&c.bot, &c.msg, &c.args, &c.state))).usage("<n>").admin().hidden(),
+        Command::new("warnmode",   Admin, "Ban, kick, mute or a timed ban/mute at the warn limit", run!(|c| admin::cmd_warnmode(&c.bot, &c.msg, &c.args, &c.state))).usage("ban|kick|mute|tban <duration>|tmute <duration>").admin().hidden(),
+        Command::new("warnexpiry", Admin, "Forget warnings after N days",       run!(|c| admin::cmd_warnexpiry(&c.bot, &c.msg, &c.args, &c.state))).usage("<days|off>").admin().hidden(),
+        Command::new("clearwarns", Admin, "Clear user's warnings",              run!(|c| admin::cmd_clearwarns(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").admin(),
+        Command::new("pin",        Admin, "Pin replied message 📌",             run!(|c| admin::cmd_pin(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("unpin",      Admin, "Unpin latest pinned message",        run!(|c| admin::cmd_unpin(&c.bot, &c.msg))).admin(),
+        Command::new("del",        Admin, "Delete replied message 🗑️",          run!(|c| admin::cmd_del(&c.bot, &c.msg, &c.args, &c.state))).usage("[reason]").admin(),
+        Command::new("purge",      Admin, "Delete from the replied message up to here 🧹", run!(|c| admin::cmd_purge(&c.bot, &c.msg, &c.args, &c.state))).usage("[N]").admin(),
+        Command::new("spurge",     Admin, "Purge without the summary",          run!(|c| admin::cmd_spurge(&c.bot, &c.msg, &c.args, &c.state))).usage("[N]").admin().hidden(),
+        Command::new("promote",    Admin, "Make admin with chosen rights and a title, or pick from a checklist ⬆️", run!(|c| promote::cmd_promote(&c.bot, &c.msg, &c.args, &c.state))).usage("[user] [title] [rights…|all]").admin(),
+        Command::new("demote",     Admin, "Remove all admin rights ⬇️",         run!(|c| promote::cmd_demote(&c.bot, &c.msg, &c.args, &c.state))).usage("[user]").admin(),
+        Command::new("adminlist",  Admin, "The chat's admins and their titles 👮", run!(|c| admin::cmd_adminlist(&c.bot, &c.msg, &c.state))).aliases(&["admins"]),
+        Command::new("admincache", Admin, "Refresh the bot's list of admins",   run!(|c| admin::cmd_admincache(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("invite",     Admin, "Generate new invite link 🔗",        run!(|c| admin::cmd_invite(&c.bot, &c.msg))).admin(),
+        Command::new("setlog",     Admin, "Log every moderation action to a channel 📋", run!(|c| admin::cmd_setlog(&c.bot, &c.msg, &c.args, &c.state))).usage("<channel>").admin(),
+        Command::new("unsetlog",   Admin, "Stop logging moderation actions",    run!(|c| admin::cmd_unsetlog(&c.bot, &c.msg, &c.state))).admin(),
+        Command::new("modlog",     Admin, "Past moderation actions, or a CSV export 📜", run!(|c| admin::cmd_modlog(&c.bot, &c.msg, &c.args, &c.state))).usage("[@user] [action] [since] [csv]").admin(),
+        Command::new("report",     Admin, "Report the replied message to the admins 🚨 (also: @admin)", run!(|c| reports::cmd_report(&c.bot, &c.msg, &c.args, &c.state))).usage("[reason]"),
+        Command::new("reports",    Admin, "Turn reports on/off, or get them by DM", run!(|c| reports::cmd_reports(&c.bot, &c.msg, &c.args, &c.state))).usage("on|off|dm on|dm off").admin(),
+        Command::new("settings",   Admin, "Chat settings menu ⚙️",              run!(|c| settings::cmd_settings(&c.bot, &c.msg, &c.state))).admin(),
+    ]
+}
+
+struct Registry {
+    commands: Vec<Command>,
+    /// name or alias → index into `commands`
+    lookup: HashMap<&'static str, usize>,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let commands = build();
+        let mut lookup = HashMap::new();
+        for (i, cmd) in commands.iter().enumerate() {
+            for name in std::iter::once(&cmd.name).chain(cmd.aliases) {
+                if lookup.insert(*name, i).is_some() {
+                    panic!("command `/{name}` is registered twice");
+                }
+            }
+        }
+        Registry { commands, lookup }
+    })
+}
+
+/// Every registered command, in menu order.
+pub fn commands() -> &'static [Command] {
+    &registry().commands
+}
+
+/// Looks a command up by name or alias (without the leading `/`).
+pub fn find(name: &str) -> Option<&'static Command> {
+    let reg = registry();
+    reg.lookup.get(name).map(|&i| &reg.commands[i])
+}
+
+/// Commands shown in one help section.
+pub fn in_category(category: Category) -> impl Iterator<Item = &'static Command> {
+    commands().iter().filter(move |c| c.category == category)
+}
+
+/// Checks the command's permission, then runs it.
+pub async fn dispatch(command: &'static Command, ctx: Ctx) {
+    if command.permission == Permission::Admin {
+        let Some(sender_id) = ctx.msg.from.as_ref().map(|u| u.id) else { return };
+        if !admin::is_admin(&ctx.bot, ctx.msg.chat.id, sender_id, &ctx.state).await {
+            let _ = ctx.bot.send_message(
+                ctx.msg.chat.id,
+                "❌ You need to be an admin to use this command.",
+                None,
+            ).await;
+            return;
+        }
+    }
+    (command.handler)(ctx).await;
+}
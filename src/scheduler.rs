@@ -0,0 +1,22 @@
+//! Jobs that run on a clock rather than on updates, started beside the `Poller` in `main`.
+
+use std::time::Duration;
+use tgbotrs::Bot;
+use crate::cmd::{captcha, nightmode};
+use crate::state::SharedState;
+
+/// How often the jobs run; night mode starts and ends within this of the set time, and
+/// unsolved captchas are kicked within this of their deadline
+pub const TICK: Duration = Duration::from_secs(60);
+
+/// Runs forever; spawn it.
+pub async fn run(bot: Bot, state: SharedState) {
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let now = chrono::Utc::now();
+        nightmode::tick(&bot, &state, now).await;
+        captcha::tick(&bot, &state, now).await;
+    }
+}
@@ -1,18 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::RangeInclusive;
 use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
+use crate::admin_cache::AdminCache;
+use crate::cmd::antiflood::FloodCounter;
+use crate::cmd::blocklist::Blocklist;
+use crate::cmd::captcha::CaptchaMode;
+use crate::cmd::locks::Lock;
+use crate::cmd::nightmode::NightMode;
+use crate::cmd::promote::PendingPromotion;
+use crate::cmd::reports::Report;
+use crate::cmd::restrict::{PendingRestriction, Perm};
+use crate::i18n::Language;
+use crate::middleware::Pipeline;
+use crate::punish::Punishment;
+use crate::storage::Storage;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct BotState {
-    /// (chat_id, user_id) → warning reasons
-    pub warnings: HashMap<(i64, i64), Vec<String>>,
-    /// (chat_id, note_name) → content
-    pub notes: HashMap<(i64, String), String>,
-    /// chat_id → active number game
-    pub games: HashMap<i64, NumberGame>,
+    /// Notes, warnings, games, chat settings and the audit log — see `storage` for the backends
+    pub store: Box<dyn Storage>,
+    /// Hooks run around every command — empty unless replaced, see `middleware::from_env`
+    pub middleware: Arc<Pipeline>,
+    /// chat_id → its owner and admins, so permission checks rarely need an API call
+    pub admins: AdminCache,
+    /// Recent messages per member, for antiflood
+    pub flood: FloodCounter,
+    /// chat_id → compiled blocklist, built on the chat's first message and dropped when it changes
+    pub blocklists: HashMap<i64, Blocklist>,
+    /// (chat_id, message_id) → report the admins haven't acted on yet
+    pub reports: HashMap<(i64, i64), Report>,
+    /// (chat_id, message_id) → `/promote` checklist waiting for its admin
+    pub promotions: HashMap<(i64, i64), PendingPromotion>,
+    /// (chat_id, message_id) → `/restrict` keyboard waiting for its admin
+    pub restrictions: HashMap<(i64, i64), PendingRestriction>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NumberGame {
     pub secret: u32,
     pub attempts: u32,
@@ -21,8 +46,316 @@ pub struct NumberGame {
     pub player_name: String,
 }
 
+/// One `/warn`, with who gave it and when.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredWarning")]
+pub struct Warning {
+    pub reason: String,
+    pub issued_by: i64,
+    pub issued_by_name: String,
+    /// Unix time
+    pub issued_at: i64,
+}
+
+/// JSON snapshots from before warnings had an issuer were plain reason strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredWarning {
+    Full { reason: String, issued_by: i64, issued_by_name: String, issued_at: i64 },
+    Reason(String),
+}
+
+impl From<StoredWarning> for Warning {
+    fn from(stored: StoredWarning) -> Self {
+        match stored {
+            StoredWarning::Full { reason, issued_by, issued_by_name, issued_at } =>
+                Warning { reason, issued_by, issued_by_name, issued_at },
+            StoredWarning::Reason(reason) =>
+                Warning { reason, issued_by: 0, issued_by_name: String::new(), issued_at: chrono::Utc::now().timestamp() },
+        }
+    }
+}
+
+/// What a moderation log entry records, see `modlog`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Ban,
+    Kick,
+    Mute,
+    Unmute,
+    Restrict,
+    Unrestrict,
+    Warn,
+    ClearWarns,
+    Promote,
+    Demote,
+    Approve,
+    Unapprove,
+    Delete,
+    Pin,
+}
+
+impl Action {
+    /// Lowercase name, as used in hashtags and `/modlog` filters
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Ban        => "ban",
+            Action::Kick       => "kick",
+            Action::Mute       => "mute",
+            Action::Unmute     => "unmute",
+            Action::Restrict   => "restrict",
+            Action::Unrestrict => "unrestrict",
+            Action::Warn       => "warn",
+            Action::ClearWarns => "clearwarns",
+            Action::Promote    => "promote",
+            Action::Demote     => "demote",
+            Action::Approve    => "approve",
+            Action::Unapprove  => "unapprove",
+            Action::Delete     => "delete",
+            Action::Pin        => "pin",
+        }
+    }
+
+    /// A [`name`](Action::name), or the command that takes the action (`tban`, `del`, …)
+    pub fn parse(text: &str) -> Option<Action> {
+        match text.to_lowercase().as_str() {
+            "ban" | "tban"       => Some(Action::Ban),
+            "kick"               => Some(Action::Kick),
+            "mute" | "tmute"     => Some(Action::Mute),
+            "unmute"             => Some(Action::Unmute),
+            "restrict"           => Some(Action::Restrict),
+            "unrestrict"         => Some(Action::Unrestrict),
+            "warn"               => Some(Action::Warn),
+            "clearwarns"         => Some(Action::ClearWarns),
+            "promote"            => Some(Action::Promote),
+            "demote"             => Some(Action::Demote),
+            "approve"            => Some(Action::Approve),
+            "unapprove"          => Some(Action::Unapprove),
+            "delete" | "del"     => Some(Action::Delete),
+            "pin"                => Some(Action::Pin),
+            _ => None,
+        }
+    }
+}
+
+/// One row of a chat's append-only moderation audit log, see `modlog`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub action: Action,
+    pub actor_id: i64,
+    pub actor_name: String,
+    /// `None` for actions that aren't aimed at a user, like `/pin`
+    pub target_id: Option<i64>,
+    pub target_name: String,
+    pub reason: String,
+    /// Seconds, for timed bans and mutes
+    pub duration: Option<u64>,
+    /// The message acted on — the one the admin replied to, or the command itself
+    pub message_id: i64,
+    /// Unix time
+    pub at: i64,
+}
+
+/// What happens to the sender of a blocklisted message, besides the deletion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlocklistAction {
+    /// Only delete the message
+    #[default]
+    Delete,
+    /// Delete it and warn the sender, counting towards the warn limit
+    Warn,
+    Mute,
+    Ban,
+}
+
+impl BlocklistAction {
+    pub fn parse(text: &str) -> Option<BlocklistAction> {
+        match text.to_lowercase().as_str() {
+            "delete" | "del" => Some(BlocklistAction::Delete),
+            "warn"           => Some(BlocklistAction::Warn),
+            "mute"           => Some(BlocklistAction::Mute),
+            "ban"            => Some(BlocklistAction::Ban),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BlocklistAction::Delete => "delete",
+            BlocklistAction::Warn   => "warn",
+            BlocklistAction::Mute   => "mute",
+            BlocklistAction::Ban    => "ban",
+        }
+    }
+}
+
+/// One `/addblacklist` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockedTrigger {
+    /// A lowercase word or phrase, or a pattern written as `/regex/`
+    pub trigger: String,
+    /// `None` uses the chat's `blocklist_action`
+    pub action: Option<BlocklistAction>,
+}
+
+/// Chats sharing one ban list, see `cmd::federation`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Federation {
+    pub id: String,
+    pub name: String,
+    pub owner_id: i64,
+    /// Who may `/fban` besides the owner
+    pub admins: BTreeSet<i64>,
+}
+
+impl Federation {
+    pub fn is_admin(&self, user_id: i64) -> bool {
+        user_id == self.owner_id || self.admins.contains(&user_id)
+    }
+}
+
+/// One `/fban`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FedBan {
+    pub user_id: i64,
+    pub user_name: String,
+    pub reason: String,
+    pub banned_by: i64,
+    /// Unix time
+    pub at: i64,
+}
+
+/// One `/approve`: the user is left alone by antiflood, locks and the blocklist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Approval {
+    pub user_id: i64,
+    pub user_name: String,
+    pub approved_by: i64,
+    /// Unix time
+    pub at: i64,
+}
+
+/// A join captcha waiting for its answer. Kept in storage so a restart doesn't leave
+/// the member muted forever.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCaptcha {
+    pub user_id: i64,
+    /// The `<answer>` part of the right button's callback data
+    pub answer: String,
+    /// The challenge message — also tells a re-join's challenge apart from the old one
+    pub message_id: i64,
+    /// Wrong answers allowed before the member is kicked
+    pub tries_left: u32,
+    /// Unix time after which the member is kicked
+    pub deadline: i64,
+}
+
+/// Per-chat configuration. Missing fields fall back to the defaults below,
+/// so records saved by older versions keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatSettings {
+    /// Warnings before `warn_action` is taken
+    pub warn_limit: u32,
+    /// What happens at `warn_limit`
+    pub warn_action: Punishment,
+    /// Warnings older than this many days stop counting; 0 keeps them forever
+    pub warn_expiry_days: u32,
+    /// Guesses allowed per `/guess` game
+    pub guess_attempts: u32,
+    /// Language of the `/guess` game's replies, see `i18n`; everything else is English
+    pub language: Language,
+    /// Only admins may `/save` and `/delnote`
+    pub notes_admin_only: bool,
+    /// Channel that receives a log entry for every moderation action, see `modlog`
+    pub log_channel: Option<i64>,
+    /// Greet new members, and say goodbye if `goodbye_text` is set. Off until `/welcome on`
+    /// or `/setwelcome`, so chats the bot was already in don't start greeting by surprise
+    pub welcome_enabled: bool,
+    /// `None` uses `welcome::DEFAULT_WELCOME`
+    pub welcome_text: Option<String>,
+    /// `None` says nothing when someone leaves
+    pub goodbye_text: Option<String>,
+    /// Delete the previous welcome when sending a new one
+    pub clean_welcome: bool,
+    /// Last welcome sent, so `clean_welcome` knows what to delete
+    pub last_welcome_id: Option<i64>,
+    /// Challenge new members must pass before they can talk
+    pub captcha_mode: CaptchaMode,
+    /// Members who haven't solved the captcha by then are kicked
+    pub captcha_timeout_secs: u64,
+    /// More messages than this within `flood_window_secs` is flooding; 0 turns antiflood off
+    pub flood_limit: u32,
+    pub flood_window_secs: u64,
+    /// What happens to members who flood
+    pub flood_action: Punishment,
+    /// What happens to messages with a blocklisted word, unless the trigger has its own action
+    pub blocklist_action: BlocklistAction,
+    /// Kinds of message deleted when non-admins send them
+    pub locks: BTreeSet<Lock>,
+    /// Members may `/report` messages or call `@admin`
+    pub reports_enabled: bool,
+    /// Admins who also get reports by DM
+    pub report_dm_admins: BTreeSet<i64>,
+    /// What members may not send by default, as last set with `/setperms`
+    pub default_denied: BTreeSet<Perm>,
+    /// Hours the chat is locked every night, if any
+    pub night_mode: Option<NightMode>,
+    /// Whether night mode has locked the chat and has yet to unlock it
+    pub night_active: bool,
+}
+
+impl ChatSettings {
+    pub const WARN_LIMIT_RANGE: RangeInclusive<u32>     = 1..=10;
+    pub const GUESS_ATTEMPTS_RANGE: RangeInclusive<u32> = 3..=15;
+    /// Choices offered by the settings menu; `/warnexpiry` takes any number
+    pub const WARN_EXPIRY_PRESETS: [u32; 6] = [0, 1, 7, 14, 30, 90];
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        ChatSettings {
+            warn_limit: 3,
+            warn_action: Punishment::Ban,
+            warn_expiry_days: 0,
+            guess_attempts: 7,
+            language: Language::En,
+            notes_admin_only: false,
+            log_channel: None,
+            welcome_enabled: false,
+            welcome_text: None,
+            goodbye_text: None,
+            clean_welcome: false,
+            last_welcome_id: None,
+            captcha_mode: CaptchaMode::Off,
+            captcha_timeout_secs: 300,
+            flood_limit: 0,
+            flood_window_secs: 10,
+            flood_action: Punishment::Mute,
+            blocklist_action: BlocklistAction::Delete,
+            locks: BTreeSet::new(),
+            reports_enabled: true,
+            report_dm_admins: BTreeSet::new(),
+            default_denied: BTreeSet::new(),
+            night_mode: None,
+            night_active: false,
+        }
+    }
+}
+
 pub type SharedState = Arc<Mutex<BotState>>;
 
-pub fn new_state() -> SharedState {
-    Arc::new(Mutex::new(BotState::default()))
+pub fn new_state(store: Box<dyn Storage>) -> SharedState {
+    Arc::new(Mutex::new(BotState {
+        store,
+        middleware: Arc::new(Pipeline::default()),
+        admins:     AdminCache::default(),
+        flood:      FloodCounter::default(),
+        blocklists: HashMap::new(),
+        reports:    HashMap::new(),
+        promotions: HashMap::new(),
+        restrictions: HashMap::new(),
+    }))
 }
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+use crate::state::{Approval, AuditEntry, BlockedTrigger, ChatSettings, FedBan, Federation, NumberGame, PendingCaptcha, Warning};
+use super::{MemoryStorage, Storage};
+
+/// [`MemoryStorage`] that rewrites a JSON snapshot file after every change.
+/// Fine for small deployments and easy to inspect by hand; use SQLite for busy bots.
+#[derive(Debug)]
+pub struct JsonStorage {
+    path: PathBuf,
+    mem: MemoryStorage,
+}
+
+impl JsonStorage {
+    /// Loads the snapshot at `path`, or starts empty if the file doesn't exist yet.
+    pub fn open(path: &str) -> std::io::Result<Self> {
+        let path = PathBuf::from(path);
+        let mem = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => MemoryStorage::default(),
+            Err(e) => return Err(e),
+        };
+        Ok(JsonStorage { path, mem })
+    }
+
+    /// Write to a temp file and rename over the old one, so a crash mid-write
+    /// never leaves a truncated snapshot behind.
+    fn flush(&self) {
+        let tmp = self.path.with_extension("json.tmp");
+        let result = serde_json::to_vec_pretty(&self.mem)
+            .map_err(std::io::Error::from)
+            .and_then(|bytes| std::fs::write(&tmp, bytes))
+            .and_then(|_| std::fs::rename(&tmp, &self.path));
+        if let Err(e) = result {
+            eprintln!("⚠️   Failed to write {}: {e}", self.path.display());
+        }
+    }
+}
+
+impl Storage for JsonStorage {
+    fn note(&self, chat_id: i64, name: &str) -> Option<String> {
+        self.mem.note(chat_id, name)
+    }
+
+    fn note_names(&self, chat_id: i64) -> Vec<String> {
+        self.mem.note_names(chat_id)
+    }
+
+    fn save_note(&mut self, chat_id: i64, name: &str, content: &str) {
+        self.mem.save_note(chat_id, name, content);
+        self.flush();
+    }
+
+    fn delete_note(&mut self, chat_id: i64, name: &str) -> bool {
+        let removed = self.mem.delete_note(chat_id, name);
+        if removed { self.flush(); }
+        removed
+    }
+
+    fn warnings(&self, chat_id: i64, user_id: i64) -> Vec<Warning> {
+        self.mem.warnings(chat_id, user_id)
+    }
+
+    fn add_warning(&mut self, chat_id: i64, user_id: i64, warning: &Warning) -> usize {
+        let count = self.mem.add_warning(chat_id, user_id, warning);
+        self.flush();
+        count
+    }
+
+    fn clear_warnings(&mut self, chat_id: i64, user_id: i64) {
+        self.mem.clear_warnings(chat_id, user_id);
+        self.flush();
+    }
+
+    fn expire_warnings(&mut self, chat_id: i64, before: i64) -> usize {
+        let removed = self.mem.expire_warnings(chat_id, before);
+        if removed > 0 { self.flush(); }
+        removed
+    }
+
+    fn game(&self, chat_id: i64) -> Option<NumberGame> {
+        self.mem.game(chat_id)
+    }
+
+    fn save_game(&mut self, chat_id: i64, game: &NumberGame) {
+        self.mem.save_game(chat_id, game);
+        self.flush();
+    }
+
+    fn delete_game(&mut self, chat_id: i64) -> Option<NumberGame> {
+        let game = self.mem.delete_game(chat_id);
+        if game.is_some() { self.flush(); }
+        game
+    }
+
+    fn chat_settings(&self, chat_id: i64) -> ChatSettings {
+        self.mem.chat_settings(chat_id)
+    }
+
+    fn save_chat_settings(&mut self, chat_id: i64, settings: &ChatSettings) {
+        self.mem.save_chat_settings(chat_id, settings);
+        self.flush();
+    }
+
+    fn configured_chats(&self) -> Vec<i64> {
+        self.mem.configured_chats()
+    }
+
+    fn remember_username(&mut self, username: &str, user_id: i64) -> bool {
+        let changed = self.mem.remember_username(username, user_id);
+        if changed { self.flush(); }
+        changed
+    }
+
+    fn user_id_by_username(&self, username: &str) -> Option<i64> {
+        self.mem.user_id_by_username(username)
+    }
+
+    fn append_audit(&mut self, chat_id: i64, entry: &AuditEntry) {
+        self.mem.append_audit(chat_id, entry);
+        self.flush();
+    }
+
+    fn audit_log(&self, chat_id: i64, since: i64) -> Vec<AuditEntry> {
+        self.mem.audit_log(chat_id, since)
+    }
+
+    fn blocklist(&self, chat_id: i64) -> Vec<BlockedTrigger> {
+        self.mem.blocklist(chat_id)
+    }
+
+    fn add_blocked(&mut self, chat_id: i64, entry: &BlockedTrigger) {
+        self.mem.add_blocked(chat_id, entry);
+        self.flush();
+    }
+
+    fn remove_blocked(&mut self, chat_id: i64, trigger: &str) -> bool {
+        let removed = self.mem.remove_blocked(chat_id, trigger);
+        if removed { self.flush(); }
+        removed
+    }
+
+    fn federation(&self, fed_id: &str) -> Option<Federation> {
+        self.mem.federation(fed_id)
+    }
+
+    fn owned_federation(&self, owner_id: i64) -> Option<Federation> {
+        self.mem.owned_federation(owner_id)
+    }
+
+    fn save_federation(&mut self, fed: &Federation) {
+        self.mem.save_federation(fed);
+        self.flush();
+    }
+
+    fn chat_federation(&self, chat_id: i64) -> Option<String> {
+        self.mem.chat_federation(chat_id)
+    }
+
+    fn set_chat_federation(&mut self, chat_id: i64, fed_id: Option<&str>) {
+        self.mem.set_chat_federation(chat_id, fed_id);
+        self.flush();
+    }
+
+    fn federation_chats(&self, fed_id: &str) -> Vec<i64> {
+        self.mem.federation_chats(fed_id)
+    }
+
+    fn fed_bans(&self, fed_id: &str) -> Vec<FedBan> {
+        self.mem.fed_bans(fed_id)
+    }
+
+    fn fed_ban(&self, fed_id: &str, user_id: i64) -> Option<FedBan> {
+        self.mem.fed_ban(fed_id, user_id)
+    }
+
+    fn add_fed_ban(&mut self, fed_id: &str, ban: &FedBan) {
+        self.mem.add_fed_ban(fed_id, ban);
+        self.flush();
+    }
+
+    fn remove_fed_ban(&mut self, fed_id: &str, user_id: i64) -> bool {
+        let removed = self.mem.remove_fed_ban(fed_id, user_id);
+        if removed { self.flush(); }
+        removed
+    }
+
+    fn approvals(&self, chat_id: i64) -> Vec<Approval> {
+        self.mem.approvals(chat_id)
+    }
+
+    fn is_approved(&self, chat_id: i64, user_id: i64) -> bool {
+        self.mem.is_approved(chat_id, user_id)
+    }
+
+    fn approve(&mut self, chat_id: i64, approval: &Approval) {
+        self.mem.approve(chat_id, approval);
+        self.flush();
+    }
+
+    fn unapprove(&mut self, chat_id: i64, user_id: i64) -> bool {
+        let removed = self.mem.unapprove(chat_id, user_id);
+        if removed { self.flush(); }
+        removed
+    }
+
+    fn unapprove_all(&mut self, chat_id: i64) -> usize {
+        let removed = self.mem.unapprove_all(chat_id);
+        if removed > 0 { self.flush(); }
+        removed
+    }
+
+    fn captcha(&self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha> {
+        self.mem.captcha(chat_id, user_id)
+    }
+
+    fn save_captcha(&mut self, chat_id: i64, captcha: &PendingCaptcha) {
+        self.mem.save_captcha(chat_id, captcha);
+        self.flush();
+    }
+
+    fn delete_captcha(&mut self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha> {
+        let removed = self.mem.delete_captcha(chat_id, user_id);
+        if removed.is_some() { self.flush(); }
+        removed
+    }
+
+    fn expired_captchas(&self, now: i64) -> Vec<(i64, PendingCaptcha)> {
+        self.mem.expired_captchas(now)
+    }
+}
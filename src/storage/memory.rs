@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use crate::state::{Approval, AuditEntry, BlockedTrigger, BlocklistAction, ChatSettings, FedBan, Federation, NumberGame, PendingCaptcha, Warning};
+use super::Storage;
+
+/// Plain maps, lost on restart. Also the in-memory half of [`super::JsonStorage`],
+/// which is why everything is keyed by chat first — JSON object keys can't be tuples.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryStorage {
+    /// chat_id → note_name → content
+    notes: HashMap<i64, BTreeMap<String, String>>,
+    /// chat_id → user_id → warnings, oldest first
+    warnings: HashMap<i64, HashMap<i64, Vec<Warning>>>,
+    /// chat_id → active number game
+    games: HashMap<i64, NumberGame>,
+    /// chat_id → settings (only chats that changed something)
+    settings: HashMap<i64, ChatSettings>,
+    /// lowercase username → user_id, from every update the bot has seen
+    usernames: HashMap<String, i64>,
+    /// chat_id → moderation actions, oldest first
+    audit: HashMap<i64, Vec<AuditEntry>>,
+    /// chat_id → trigger → its own action, if any
+    blocklist: HashMap<i64, BTreeMap<String, Option<BlocklistAction>>>,
+    /// fed_id → federation
+    federations: HashMap<String, Federation>,
+    /// chat_id → fed_id
+    fed_chats: HashMap<i64, String>,
+    /// fed_id → bans, oldest first
+    fed_bans: HashMap<String, Vec<FedBan>>,
+    /// chat_id → approved users, oldest first
+    approvals: HashMap<i64, Vec<Approval>>,
+    /// chat_id → user_id → join captcha waiting to be solved
+    captchas: HashMap<i64, HashMap<i64, PendingCaptcha>>,
+}
+
+impl Storage for MemoryStorage {
+    fn note(&self, chat_id: i64, name: &str) -> Option<String> {
+        self.notes.get(&chat_id)?.get(name).cloned()
+    }
+
+    fn note_names(&self, chat_id: i64) -> Vec<String> {
+        self.notes.get(&chat_id).map(|n| n.keys().cloned().collect()).unwrap_or_default()
+    }
+
+    fn save_note(&mut self, chat_id: i64, name: &str, content: &str) {
+        self.notes.entry(chat_id).or_default().insert(name.to_string(), content.to_string());
+    }
+
+    fn delete_note(&mut self, chat_id: i64, name: &str) -> bool {
+        self.notes.get_mut(&chat_id).and_then(|n| n.remove(name)).is_some()
+    }
+
+    fn warnings(&self, chat_id: i64, user_id: i64) -> Vec<Warning> {
+        self.warnings.get(&chat_id).and_then(|w| w.get(&user_id)).cloned().unwrap_or_default()
+    }
+
+    fn add_warning(&mut self, chat_id: i64, user_id: i64, warning: &Warning) -> usize {
+        let warns = self.warnings.entry(chat_id).or_default().entry(user_id).or_default();
+        warns.push(warning.clone());
+        warns.len()
+    }
+
+    fn clear_warnings(&mut self, chat_id: i64, user_id: i64) {
+        if let Some(w) = self.warnings.get_mut(&chat_id) {
+            w.remove(&user_id);
+        }
+    }
+
+    fn expire_warnings(&mut self, chat_id: i64, before: i64) -> usize {
+        let Some(users) = self.warnings.get_mut(&chat_id) else { return 0 };
+        let mut removed = 0;
+        users.retain(|_, warns| {
+            let kept = warns.len();
+            warns.retain(|w| w.issued_at >= before);
+            removed += kept - warns.len();
+            !warns.is_empty()
+        });
+        removed
+    }
+
+    fn game(&self, chat_id: i64) -> Option<NumberGame> {
+        self.games.get(&chat_id).cloned()
+    }
+
+    fn save_game(&mut self, chat_id: i64, game: &NumberGame) {
+        self.games.insert(chat_id, game.clone());
+    }
+
+    fn delete_game(&mut self, chat_id: i64) -> Option<NumberGame> {
+        self.games.remove(&chat_id)
+    }
+
+    fn chat_settings(&self, chat_id: i64) -> ChatSettings {
+        self.settings.get(&chat_id).cloned().unwrap_or_default()
+    }
+
+    fn save_chat_settings(&mut self, chat_id: i64, settings: &ChatSettings) {
+        self.settings.insert(chat_id, settings.clone());
+    }
+
+    fn configured_chats(&self) -> Vec<i64> {
+        let mut chats: Vec<i64> = self.settings.keys().copied().collect();
+        chats.sort_unstable();
+        chats
+    }
+
+    fn remember_username(&mut self, username: &str, user_id: i64) -> bool {
+        self.usernames.insert(username.to_lowercase(), user_id) != Some(user_id)
+    }
+
+    fn user_id_by_username(&self, username: &str) -> Option<i64> {
+        self.usernames.get(&username.to_lowercase()).copied()
+    }
+
+    fn append_audit(&mut self, chat_id: i64, entry: &AuditEntry) {
+        self.audit.entry(chat_id).or_default().push(entry.clone());
+    }
+
+    fn audit_log(&self, chat_id: i64, since: i64) -> Vec<AuditEntry> {
+        self.audit.get(&chat_id)
+            .map(|log| log.iter().filter(|e| e.at >= since).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn blocklist(&self, chat_id: i64) -> Vec<BlockedTrigger> {
+        self.blocklist.get(&chat_id)
+            .map(|list| list.iter().map(|(trigger, &action)| BlockedTrigger { trigger: trigger.clone(), action }).collect())
+            .unwrap_or_default()
+    }
+
+    fn add_blocked(&mut self, chat_id: i64, entry: &BlockedTrigger) {
+        self.blocklist.entry(chat_id).or_default().insert(entry.trigger.clone(), entry.action);
+    }
+
+    fn remove_blocked(&mut self, chat_id: i64, trigger: &str) -> bool {
+        self.blocklist.get_mut(&chat_id).and_then(|list| list.remove(trigger)).is_some()
+    }
+
+    fn federation(&self, fed_id: &str) -> Option<Federation> {
+        self.federations.get(fed_id).cloned()
+    }
+
+    fn owned_federation(&self, owner_id: i64) -> Option<Federation> {
+        self.federations.values().find(|f| f.owner_id == owner_id).cloned()
+    }
+
+    fn save_federation(&mut self, fed: &Federation) {
+        self.federations.insert(fed.id.clone(), fed.clone());
+    }
+
+    fn chat_federation(&self, chat_id: i64) -> Option<String> {
+        self.fed_chats.get(&chat_id).cloned()
+    }
+
+    fn set_chat_federation(&mut self, chat_id: i64, fed_id: Option<&str>) {
+        match fed_id {
+            Some(id) => { self.fed_chats.insert(chat_id, id.to_string()); }
+            None     => { self.fed_chats.remove(&chat_id); }
+        }
+    }
+
+    fn federation_chats(&self, fed_id: &str) -> Vec<i64> {
+        let mut chats: Vec<i64> = self.fed_chats.iter().filter(|(_, f)| *f == fed_id).map(|(c, _)| *c).collect();
+        chats.sort_unstable();
+        chats
+    }
+
+    fn fed_bans(&self, fed_id: &str) -> Vec<FedBan> {
+        self.fed_bans.get(fed_id).cloned().unwrap_or_default()
+    }
+
+    fn fed_ban(&self, fed_id: &str, user_id: i64) -> Option<FedBan> {
+        self.fed_bans.get(fed_id)?.iter().find(|b| b.user_id == user_id).cloned()
+    }
+
+    fn add_fed_ban(&mut self, fed_id: &str, ban: &FedBan) {
+        let bans = self.fed_bans.entry(fed_id.to_string()).or_default();
+        bans.retain(|b| b.user_id != ban.user_id);
+        bans.push(ban.clone());
+    }
+
+    fn remove_fed_ban(&mut self, fed_id: &str, user_id: i64) -> bool {
+        let Some(bans) = self.fed_bans.get_mut(fed_id) else { return false };
+        let before = bans.len();
+        bans.retain(|b| b.user_id != user_id);
+        bans.len() < before
+    }
+
+    fn approvals(&self, chat_id: i64) -> Vec<Approval> {
+        self.approvals.get(&chat_id).cloned().unwrap_or_default()
+    }
+
+    fn is_approved(&self, chat_id: i64, user_id: i64) -> bool {
+        self.approvals.get(&chat_id).is_some_and(|a| a.iter().any(|a| a.user_id == user_id))
+    }
+
+    fn approve(&mut self, chat_id: i64, approval: &Approval) {
+        let approvals = self.approvals.entry(chat_id).or_default();
+        approvals.retain(|a| a.user_id != approval.user_id);
+        approvals.push(approval.clone());
+    }
+
+    fn unapprove(&mut self, chat_id: i64, user_id: i64) -> bool {
+        let Some(approvals) = self.approvals.get_mut(&chat_id) else { return false };
+        let before = approvals.len();
+        approvals.retain(|a| a.user_id != user_id);
+        approvals.len() < before
+    }
+
+    fn unapprove_all(&mut self, chat_id: i64) -> usize {
+        self.approvals.remove(&chat_id).map_or(0, |a| a.len())
+    }
+
+    fn captcha(&self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha> {
+        self.captchas.get(&chat_id)?.get(&user_id).cloned()
+    }
+
+    fn save_captcha(&mut self, chat_id: i64, captcha: &PendingCaptcha) {
+        self.captchas.entry(chat_id).or_default().insert(captcha.user_id, captcha.clone());
+    }
+
+    fn delete_captcha(&mut self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha> {
+        let chat = self.captchas.get_mut(&chat_id)?;
+        let removed = chat.remove(&user_id);
+        if chat.is_empty() {
+            self.captchas.remove(&chat_id);
+        }
+        removed
+    }
+
+    fn expired_captchas(&self, now: i64) -> Vec<(i64, PendingCaptcha)> {
+        let mut expired: Vec<_> = self.captchas.iter()
+            .flat_map(|(&chat_id, users)| users.values().map(move |c| (chat_id, c.clone())))
+            .filter(|(_, c)| c.deadline <= now)
+            .collect();
+        expired.sort_by_key(|(chat_id, c)| (c.deadline, *chat_id, c.user_id));
+        expired
+    }
+}
@@ -0,0 +1,138 @@
+//! Storage backends for everything the bot needs to remember.
+//!
+//! Command modules only talk to the [`Storage`] trait; which backend sits behind it is
+//! picked at startup from `STORAGE` in `.env`:
+//!
+//! | `STORAGE`  | Backend            | `STORAGE_PATH` default |
+//! |------------|--------------------|------------------------|
+//! | `sqlite`   | [`SqliteStorage`]  | `bot.db`               |
+//! | `json`     | [`JsonStorage`]    | `bot.json`             |
+//! | `memory`   | [`MemoryStorage`]  | —                      |
+
+mod json;
+mod memory;
+mod sqlite;
+
+pub use json::JsonStorage;
+pub use memory::MemoryStorage;
+pub use sqlite::SqliteStorage;
+
+use crate::state::{Approval, AuditEntry, BlockedTrigger, ChatSettings, FedBan, Federation, NumberGame, PendingCaptcha, Warning};
+
+/// Everything here is synchronous and called with the `BotState` lock held,
+/// so never `.await` between a read and the write that depends on it.
+/// Write failures are logged by the backend rather than returned.
+pub trait Storage: Send + std::fmt::Debug {
+    // ─── Notes ────────────────────────────────────────────────────────────────
+    fn note(&self, chat_id: i64, name: &str) -> Option<String>;
+    /// Sorted note names for a chat
+    fn note_names(&self, chat_id: i64) -> Vec<String>;
+    fn save_note(&mut self, chat_id: i64, name: &str, content: &str);
+    /// Returns whether a note was removed
+    fn delete_note(&mut self, chat_id: i64, name: &str) -> bool;
+
+    // ─── Warnings ─────────────────────────────────────────────────────────────
+    /// Oldest first
+    fn warnings(&self, chat_id: i64, user_id: i64) -> Vec<Warning>;
+    /// Returns the user's new warning count
+    fn add_warning(&mut self, chat_id: i64, user_id: i64, warning: &Warning) -> usize;
+    fn clear_warnings(&mut self, chat_id: i64, user_id: i64);
+    /// Drops every warning in the chat issued before `before` (unix time); returns how many
+    fn expire_warnings(&mut self, chat_id: i64, before: i64) -> usize;
+
+    // ─── Games ────────────────────────────────────────────────────────────────
+    fn game(&self, chat_id: i64) -> Option<NumberGame>;
+    fn save_game(&mut self, chat_id: i64, game: &NumberGame);
+    /// Returns the game that was running, if any
+    fn delete_game(&mut self, chat_id: i64) -> Option<NumberGame>;
+
+    // ─── Chat settings ────────────────────────────────────────────────────────
+    /// Defaults when the chat has never been configured
+    fn chat_settings(&self, chat_id: i64) -> ChatSettings;
+    fn save_chat_settings(&mut self, chat_id: i64, settings: &ChatSettings);
+    /// Every chat that has saved settings, sorted — for jobs that visit all of them
+    fn configured_chats(&self) -> Vec<i64>;
+
+    // ─── Users ────────────────────────────────────────────────────────────────
+    /// Records who currently owns `username` (no `@`, any case); returns whether it changed
+    fn remember_username(&mut self, username: &str, user_id: i64) -> bool;
+    fn user_id_by_username(&self, username: &str) -> Option<i64>;
+
+    // ─── Audit log ────────────────────────────────────────────────────────────
+    /// Entries are only ever appended — nothing edits or removes them
+    fn append_audit(&mut self, chat_id: i64, entry: &AuditEntry);
+    /// Entries at or after `since` (unix time), oldest first
+    fn audit_log(&self, chat_id: i64, since: i64) -> Vec<AuditEntry>;
+
+    // ─── Blocklist ────────────────────────────────────────────────────────────
+    /// Sorted by trigger
+    fn blocklist(&self, chat_id: i64) -> Vec<BlockedTrigger>;
+    /// Adds the trigger, or replaces its action if it's already listed
+    fn add_blocked(&mut self, chat_id: i64, entry: &BlockedTrigger);
+    /// Returns whether the trigger was listed
+    fn remove_blocked(&mut self, chat_id: i64, trigger: &str) -> bool;
+
+    // ─── Federations ──────────────────────────────────────────────────────────
+    fn federation(&self, fed_id: &str) -> Option<Federation>;
+    /// The federation `owner_id` created — nobody owns more than one
+    fn owned_federation(&self, owner_id: i64) -> Option<Federation>;
+    /// Creates the federation, or replaces its name and admins
+    fn save_federation(&mut self, fed: &Federation);
+    /// ID of the federation the chat belongs to
+    fn chat_federation(&self, chat_id: i64) -> Option<String>;
+    /// Moves the chat into `fed_id`, or out of its federation with `None`
+    fn set_chat_federation(&mut self, chat_id: i64, fed_id: Option<&str>);
+    /// Sorted
+    fn federation_chats(&self, fed_id: &str) -> Vec<i64>;
+    /// Oldest first
+    fn fed_bans(&self, fed_id: &str) -> Vec<FedBan>;
+    fn fed_ban(&self, fed_id: &str, user_id: i64) -> Option<FedBan>;
+    /// Adds the ban, or replaces the user's earlier one
+    fn add_fed_ban(&mut self, fed_id: &str, ban: &FedBan);
+    /// Returns whether the user was banned
+    fn remove_fed_ban(&mut self, fed_id: &str, user_id: i64) -> bool;
+
+    // ─── Approvals ────────────────────────────────────────────────────────────
+    /// Oldest first
+    fn approvals(&self, chat_id: i64) -> Vec<Approval>;
+    fn is_approved(&self, chat_id: i64, user_id: i64) -> bool;
+    /// Adds the approval, or replaces the user's earlier one
+    fn approve(&mut self, chat_id: i64, approval: &Approval);
+    /// Returns whether the user was approved
+    fn unapprove(&mut self, chat_id: i64, user_id: i64) -> bool;
+    /// Removes every approval in the chat; returns how many
+    fn unapprove_all(&mut self, chat_id: i64) -> usize;
+
+    // ─── Captchas ─────────────────────────────────────────────────────────────
+    fn captcha(&self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha>;
+    /// Adds the captcha, or replaces the user's earlier one
+    fn save_captcha(&mut self, chat_id: i64, captcha: &PendingCaptcha);
+    /// Returns the captcha that was pending, if any
+    fn delete_captcha(&mut self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha>;
+    /// (chat_id, captcha) for every captcha whose deadline is at or before `now` (unix time),
+    /// earliest deadline first
+    fn expired_captchas(&self, now: i64) -> Vec<(i64, PendingCaptcha)>;
+}
+
+/// Builds the backend selected by `STORAGE` / `STORAGE_PATH`.
+pub fn from_env() -> Box<dyn Storage> {
+    let kind = std::env::var("STORAGE").unwrap_or_else(|_| "sqlite".into());
+    let path = std::env::var("STORAGE_PATH").ok();
+    match kind.as_str() {
+        "memory" => {
+            println!("🗄️   Storage: in-memory (nothing survives a restart)");
+            Box::new(MemoryStorage::default())
+        }
+        "json" => {
+            let path = path.unwrap_or_else(|| "bot.json".into());
+            println!("🗄️   Storage: JSON snapshot at {path}");
+            Box::new(JsonStorage::open(&path).expect("Failed to load JSON storage — check STORAGE_PATH"))
+        }
+        "sqlite" => {
+            let path = path.unwrap_or_else(|| "bot.db".into());
+            println!("🗄️   Storage: SQLite at {path}");
+            Box::new(SqliteStorage::open(&path).expect("Failed to open database — check STORAGE_PATH"))
+        }
+        other => panic!("Unknown STORAGE backend `{other}` — use sqlite, json or memory"),
+    }
+}
@@ -0,0 +1,558 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::state::{Action, Approval, AuditEntry, BlockedTrigger, BlocklistAction, ChatSettings, FedBan, Federation, NumberGame, PendingCaptcha, Warning};
+use super::Storage;
+
+/// Schema migrations, applied in order. `PRAGMA user_version` records how many have run,
+/// so only append to this list — never edit an entry that has shipped.
+const MIGRATIONS: &[&str] = &[
+    // v1 — warnings, notes, games
+    "CREATE TABLE warnings (
+         chat_id INTEGER NOT NULL,
+         user_id INTEGER NOT NULL,
+         reason  TEXT    NOT NULL
+     );
+     CREATE INDEX warnings_chat_user ON warnings (chat_id, user_id);
+
+     CREATE TABLE notes (
+         chat_id INTEGER NOT NULL,
+         name    TEXT    NOT NULL,
+         content TEXT    NOT NULL,
+         PRIMARY KEY (chat_id, name)
+     );
+
+     CREATE TABLE games (
+         chat_id      INTEGER PRIMARY KEY,
+         secret       INTEGER NOT NULL,
+         attempts     INTEGER NOT NULL,
+         max_attempts INTEGER NOT NULL,
+         player_id    INTEGER NOT NULL,
+         player_name  TEXT    NOT NULL
+     );",
+    // v2 — per-chat settings, stored as a JSON blob so new fields need no migration
+    "CREATE TABLE chat_settings (
+         chat_id INTEGER PRIMARY KEY,
+         data    TEXT    NOT NULL
+     );",
+    // v3 — who issued each warning and when; existing ones count from the upgrade
+    "ALTER TABLE warnings ADD COLUMN issued_by      INTEGER NOT NULL DEFAULT 0;
+     ALTER TABLE warnings ADD COLUMN issued_by_name TEXT    NOT NULL DEFAULT '';
+     ALTER TABLE warnings ADD COLUMN issued_at      INTEGER NOT NULL DEFAULT 0;
+     UPDATE warnings SET issued_at = CAST(strftime('%s', 'now') AS INTEGER);",
+    // v4 — @username → user id, so commands can target people by name
+    "CREATE TABLE usernames (
+         username TEXT    PRIMARY KEY,
+         user_id  INTEGER NOT NULL
+     );",
+    // v5 — append-only moderation audit log
+    "CREATE TABLE audit_log (
+         chat_id     INTEGER NOT NULL,
+         action      TEXT    NOT NULL,
+         actor_id    INTEGER NOT NULL,
+         actor_name  TEXT    NOT NULL,
+         target_id   INTEGER,
+         target_name TEXT    NOT NULL,
+         reason      TEXT    NOT NULL,
+         duration    INTEGER,
+         message_id  INTEGER NOT NULL,
+         at          INTEGER NOT NULL
+     );
+     CREATE INDEX audit_log_chat_at ON audit_log (chat_id, at);",
+    // v6 — blocklisted words and patterns; a NULL action follows the chat's setting
+    "CREATE TABLE blocklist (
+         chat_id INTEGER NOT NULL,
+         trigger TEXT    NOT NULL,
+         action  TEXT,
+         PRIMARY KEY (chat_id, trigger)
+     );",
+    // v7 — federations: chats sharing a ban list; admins are a JSON array of user ids
+    "CREATE TABLE federations (
+         id       TEXT    PRIMARY KEY,
+         name     TEXT    NOT NULL,
+         owner_id INTEGER NOT NULL UNIQUE,
+         admins   TEXT    NOT NULL
+     );
+     CREATE TABLE federation_chats (
+         chat_id INTEGER PRIMARY KEY,
+         fed_id  TEXT    NOT NULL
+     );
+     CREATE TABLE fed_bans (
+         fed_id    TEXT    NOT NULL,
+         user_id   INTEGER NOT NULL,
+         user_name TEXT    NOT NULL,
+         reason    TEXT    NOT NULL,
+         banned_by INTEGER NOT NULL,
+         at        INTEGER NOT NULL,
+         PRIMARY KEY (fed_id, user_id)
+     );",
+    // v8 — users exempt from automatic moderation in a chat
+    "CREATE TABLE approvals (
+         chat_id     INTEGER NOT NULL,
+         user_id     INTEGER NOT NULL,
+         user_name   TEXT    NOT NULL,
+         approved_by INTEGER NOT NULL,
+         at          INTEGER NOT NULL,
+         PRIMARY KEY (chat_id, user_id)
+     );",
+    // v9 — join captchas, so their deadlines survive a restart
+    "CREATE TABLE captchas (
+         chat_id    INTEGER NOT NULL,
+         user_id    INTEGER NOT NULL,
+         answer     TEXT    NOT NULL,
+         message_id INTEGER NOT NULL,
+         tries_left INTEGER NOT NULL,
+         deadline   INTEGER NOT NULL,
+         PRIMARY KEY (chat_id, user_id)
+     );
+     CREATE INDEX captchas_deadline ON captchas (deadline);",
+];
+
+/// Everything lives in one SQLite file and is queried on demand.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database file and brings the schema up to date.
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        let db = SqliteStorage { conn: Connection::open(path)? };
+        db.migrate()?;
+        Ok(db)
+    }
+
+    fn migrate(&self) -> rusqlite::Result<()> {
+        let version: i64 = self.conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute_batch(sql)?;
+            tx.pragma_update(None, "user_version", (i + 1) as i64)?;
+            tx.commit()?;
+            println!("🗄️   Database migrated to v{}", i + 1);
+        }
+        Ok(())
+    }
+
+    /// Writes are fire-and-forget like every other side effect in the bot: log and move on.
+    fn exec(&self, sql: &str, params: impl rusqlite::Params) -> usize {
+        self.conn.execute(sql, params).unwrap_or_else(|e| {
+            eprintln!("⚠️   Database write failed: {e}");
+            0
+        })
+    }
+
+    /// Reads that fail are logged and treated as "nothing found".
+    fn read<T>(&self, result: rusqlite::Result<T>) -> Option<T> {
+        result.map_err(|e| eprintln!("⚠️   Database read failed: {e}")).ok()
+    }
+
+    /// The chat's settings record, if it has one, parsed.
+    fn stored_settings(&self, chat_id: i64) -> Option<serde_json::Result<ChatSettings>> {
+        let data: String = self.read(self.conn.query_row(
+            "SELECT data FROM chat_settings WHERE chat_id = ?1",
+            params![chat_id],
+            |r| r.get(0),
+        ).optional()).flatten()?;
+        Some(serde_json::from_str(&data))
+    }
+
+    /// Multi-row query, mapped row by row; a failure reads as "no rows".
+    fn rows<T>(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+        map: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+    ) -> Vec<T> {
+        let result = self.conn.prepare(sql).and_then(|mut stmt| {
+            let rows = stmt.query_map(params, map)?;
+            rows.collect::<rusqlite::Result<Vec<T>>>()
+        });
+        self.read(result).unwrap_or_default()
+    }
+}
+
+impl Storage for SqliteStorage {
+    // ─── Notes ────────────────────────────────────────────────────────────────
+
+    fn note(&self, chat_id: i64, name: &str) -> Option<String> {
+        self.read(self.conn.query_row(
+            "SELECT content FROM notes WHERE chat_id = ?1 AND name = ?2",
+            params![chat_id, name],
+            |r| r.get(0),
+        ).optional())?
+    }
+
+    fn note_names(&self, chat_id: i64) -> Vec<String> {
+        self.rows("SELECT name FROM notes WHERE chat_id = ?1 ORDER BY name", params![chat_id], |r| r.get(0))
+    }
+
+    fn save_note(&mut self, chat_id: i64, name: &str, content: &str) {
+        self.exec(
+            "INSERT OR REPLACE INTO notes (chat_id, name, content) VALUES (?1, ?2, ?3)",
+            params![chat_id, name, content],
+        );
+    }
+
+    fn delete_note(&mut self, chat_id: i64, name: &str) -> bool {
+        self.exec("DELETE FROM notes WHERE chat_id = ?1 AND name = ?2", params![chat_id, name]) > 0
+    }
+
+    // ─── Warnings ─────────────────────────────────────────────────────────────
+
+    fn warnings(&self, chat_id: i64, user_id: i64) -> Vec<Warning> {
+        self.rows(
+            "SELECT reason, issued_by, issued_by_name, issued_at FROM warnings
+             WHERE chat_id = ?1 AND user_id = ?2 ORDER BY rowid",
+            params![chat_id, user_id],
+            |r| Ok(Warning {
+                reason:         r.get(0)?,
+                issued_by:      r.get(1)?,
+                issued_by_name: r.get(2)?,
+                issued_at:      r.get(3)?,
+            }),
+        )
+    }
+
+    fn add_warning(&mut self, chat_id: i64, user_id: i64, warning: &Warning) -> usize {
+        self.exec(
+            "INSERT INTO warnings (chat_id, user_id, reason, issued_by, issued_by_name, issued_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![chat_id, user_id, warning.reason, warning.issued_by, warning.issued_by_name, warning.issued_at],
+        );
+        self.read(self.conn.query_row(
+            "SELECT COUNT(*) FROM warnings WHERE chat_id = ?1 AND user_id = ?2",
+            params![chat_id, user_id],
+            |r| r.get::<_, i64>(0),
+        )).unwrap_or(0) as usize
+    }
+
+    fn clear_warnings(&mut self, chat_id: i64, user_id: i64) {
+        self.exec(
+            "DELETE FROM warnings WHERE chat_id = ?1 AND user_id = ?2",
+            params![chat_id, user_id],
+        );
+    }
+
+    fn expire_warnings(&mut self, chat_id: i64, before: i64) -> usize {
+        self.exec("DELETE FROM warnings WHERE chat_id = ?1 AND issued_at < ?2", params![chat_id, before])
+    }
+
+    // ─── Games ────────────────────────────────────────────────────────────────
+
+    fn game(&self, chat_id: i64) -> Option<NumberGame> {
+        self.read(self.conn.query_row(
+            "SELECT secret, attempts, max_attempts, player_id, player_name FROM games WHERE chat_id = ?1",
+            params![chat_id],
+            |r| Ok(NumberGame {
+                secret:       r.get(0)?,
+                attempts:     r.get(1)?,
+                max_attempts: r.get(2)?,
+                _player_id:   r.get(3)?,
+                player_name:  r.get(4)?,
+            }),
+        ).optional())?
+    }
+
+    fn save_game(&mut self, chat_id: i64, game: &NumberGame) {
+        self.exec(
+            "INSERT OR REPLACE INTO games (chat_id, secret, attempts, max_attempts, player_id, player_name)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![chat_id, game.secret, game.attempts, game.max_attempts, game._player_id, game.player_name],
+        );
+    }
+
+    fn delete_game(&mut self, chat_id: i64) -> Option<NumberGame> {
+        let game = self.game(chat_id)?;
+        self.exec("DELETE FROM games WHERE chat_id = ?1", params![chat_id]);
+        Some(game)
+    }
+
+    // ─── Chat settings ────────────────────────────────────────────────────────
+
+    fn chat_settings(&self, chat_id: i64) -> ChatSettings {
+        match self.stored_settings(chat_id) {
+            Some(Ok(settings)) => settings,
+            Some(Err(e)) => {
+                eprintln!("⚠️   Unreadable settings for chat {chat_id}, using the defaults: {e}");
+                ChatSettings::default()
+            }
+            None => ChatSettings::default(),
+        }
+    }
+
+    fn save_chat_settings(&mut self, chat_id: i64, settings: &ChatSettings) {
+        // The defaults handed out for an unreadable record would wipe it; leave it to be fixed
+        if let Some(Err(e)) = self.stored_settings(chat_id) {
+            eprintln!("⚠️   Not saving over the unreadable settings of chat {chat_id}: {e}");
+            return;
+        }
+        let Ok(data) = serde_json::to_string(settings) else { return };
+        self.exec(
+            "INSERT OR REPLACE INTO chat_settings (chat_id, data) VALUES (?1, ?2)",
+            params![chat_id, data],
+        );
+    }
+
+    fn configured_chats(&self) -> Vec<i64> {
+        self.rows("SELECT chat_id FROM chat_settings ORDER BY chat_id", [], |r| r.get(0))
+    }
+
+    // ─── Users ────────────────────────────────────────────────────────────────
+
+    fn remember_username(&mut self, username: &str, user_id: i64) -> bool {
+        self.exec(
+            "INSERT INTO usernames (username, user_id) VALUES (?1, ?2)
+             ON CONFLICT (username) DO UPDATE SET user_id = excluded.user_id
+             WHERE user_id != excluded.user_id",
+            params![username.to_lowercase(), user_id],
+        ) > 0
+    }
+
+    fn user_id_by_username(&self, username: &str) -> Option<i64> {
+        self.read(self.conn.query_row(
+            "SELECT user_id FROM usernames WHERE username = ?1",
+            params![username.to_lowercase()],
+            |r| r.get(0),
+        ).optional())?
+    }
+
+    // ─── Audit log ────────────────────────────────────────────────────────────
+
+    fn append_audit(&mut self, chat_id: i64, e: &AuditEntry) {
+        self.exec(
+            "INSERT INTO audit_log (chat_id, action, actor_id, actor_name, target_id, target_name, reason, duration, message_id, at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                chat_id, e.action.name(), e.actor_id, e.actor_name, e.target_id, e.target_name,
+                e.reason, e.duration.map(|d| d as i64), e.message_id, e.at,
+            ],
+        );
+    }
+
+    fn audit_log(&self, chat_id: i64, since: i64) -> Vec<AuditEntry> {
+        // Action names this version doesn't know (written by a newer one) are skipped
+        self.rows(
+            "SELECT action, actor_id, actor_name, target_id, target_name, reason, duration, message_id, at
+             FROM audit_log WHERE chat_id = ?1 AND at >= ?2 ORDER BY rowid",
+            params![chat_id, since],
+            |r| {
+                let Some(action) = Action::parse(&r.get::<_, String>(0)?) else { return Ok(None) };
+                Ok(Some(AuditEntry {
+                    action,
+                    actor_id:    r.get(1)?,
+                    actor_name:  r.get(2)?,
+                    target_id:   r.get(3)?,
+                    target_name: r.get(4)?,
+                    reason:      r.get(5)?,
+                    duration:    r.get::<_, Option<i64>>(6)?.map(|d| d as u64),
+                    message_id:  r.get(7)?,
+                    at:          r.get(8)?,
+                }))
+            },
+        ).into_iter().flatten().collect()
+    }
+
+    // ─── Blocklist ────────────────────────────────────────────────────────────
+
+    fn blocklist(&self, chat_id: i64) -> Vec<BlockedTrigger> {
+        self.rows(
+            "SELECT trigger, action FROM blocklist WHERE chat_id = ?1 ORDER BY trigger",
+            params![chat_id],
+            |r| Ok(BlockedTrigger {
+                trigger: r.get(0)?,
+                action:  r.get::<_, Option<String>>(1)?.as_deref().and_then(BlocklistAction::parse),
+            }),
+        )
+    }
+
+    fn add_blocked(&mut self, chat_id: i64, entry: &BlockedTrigger) {
+        self.exec(
+            "INSERT OR REPLACE INTO blocklist (chat_id, trigger, action) VALUES (?1, ?2, ?3)",
+            params![chat_id, entry.trigger, entry.action.map(BlocklistAction::label)],
+        );
+    }
+
+    fn remove_blocked(&mut self, chat_id: i64, trigger: &str) -> bool {
+        self.exec("DELETE FROM blocklist WHERE chat_id = ?1 AND trigger = ?2", params![chat_id, trigger]) > 0
+    }
+
+    // ─── Federations ──────────────────────────────────────────────────────────
+
+    fn federation(&self, fed_id: &str) -> Option<Federation> {
+        self.rows(
+            "SELECT id, name, owner_id, admins FROM federations WHERE id = ?1",
+            params![fed_id],
+            federation_row,
+        ).into_iter().next()
+    }
+
+    fn owned_federation(&self, owner_id: i64) -> Option<Federation> {
+        self.rows(
+            "SELECT id, name, owner_id, admins FROM federations WHERE owner_id = ?1",
+            params![owner_id],
+            federation_row,
+        ).into_iter().next()
+    }
+
+    fn save_federation(&mut self, fed: &Federation) {
+        let Ok(admins) = serde_json::to_string(&fed.admins) else { return };
+        self.exec(
+            "INSERT OR REPLACE INTO federations (id, name, owner_id, admins) VALUES (?1, ?2, ?3, ?4)",
+            params![fed.id, fed.name, fed.owner_id, admins],
+        );
+    }
+
+    fn chat_federation(&self, chat_id: i64) -> Option<String> {
+        self.read(self.conn.query_row(
+            "SELECT fed_id FROM federation_chats WHERE chat_id = ?1",
+            params![chat_id],
+            |r| r.get(0),
+        ).optional())?
+    }
+
+    fn set_chat_federation(&mut self, chat_id: i64, fed_id: Option<&str>) {
+        match fed_id {
+            Some(id) => self.exec(
+                "INSERT OR REPLACE INTO federation_chats (chat_id, fed_id) VALUES (?1, ?2)",
+                params![chat_id, id],
+            ),
+            None => self.exec("DELETE FROM federation_chats WHERE chat_id = ?1", params![chat_id]),
+        };
+    }
+
+    fn federation_chats(&self, fed_id: &str) -> Vec<i64> {
+        self.rows(
+            "SELECT chat_id FROM federation_chats WHERE fed_id = ?1 ORDER BY chat_id",
+            params![fed_id],
+            |r| r.get(0),
+        )
+    }
+
+    fn fed_bans(&self, fed_id: &str) -> Vec<FedBan> {
+        self.rows(
+            "SELECT user_id, user_name, reason, banned_by, at FROM fed_bans WHERE fed_id = ?1 ORDER BY at, rowid",
+            params![fed_id],
+            fed_ban_row,
+        )
+    }
+
+    fn fed_ban(&self, fed_id: &str, user_id: i64) -> Option<FedBan> {
+        self.rows(
+            "SELECT user_id, user_name, reason, banned_by, at FROM fed_bans WHERE fed_id = ?1 AND user_id = ?2",
+            params![fed_id, user_id],
+            fed_ban_row,
+        ).into_iter().next()
+    }
+
+    fn add_fed_ban(&mut self, fed_id: &str, ban: &FedBan) {
+        self.exec(
+            "INSERT OR REPLACE INTO fed_bans (fed_id, user_id, user_name, reason, banned_by, at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![fed_id, ban.user_id, ban.user_name, ban.reason, ban.banned_by, ban.at],
+        );
+    }
+
+    fn remove_fed_ban(&mut self, fed_id: &str, user_id: i64) -> bool {
+        self.exec("DELETE FROM fed_bans WHERE fed_id = ?1 AND user_id = ?2", params![fed_id, user_id]) > 0
+    }
+
+    // ─── Approvals ────────────────────────────────────────────────────────────
+
+    fn approvals(&self, chat_id: i64) -> Vec<Approval> {
+        self.rows(
+            "SELECT user_id, user_name, approved_by, at FROM approvals WHERE chat_id = ?1 ORDER BY at, rowid",
+            params![chat_id],
+            |r| Ok(Approval {
+                user_id:     r.get(0)?,
+                user_name:   r.get(1)?,
+                approved_by: r.get(2)?,
+                at:          r.get(3)?,
+            }),
+        )
+    }
+
+    fn is_approved(&self, chat_id: i64, user_id: i64) -> bool {
+        self.read(self.conn.query_row(
+            "SELECT 1 FROM approvals WHERE chat_id = ?1 AND user_id = ?2",
+            params![chat_id, user_id],
+            |_| Ok(()),
+        ).optional()).flatten().is_some()
+    }
+
+    fn approve(&mut self, chat_id: i64, approval: &Approval) {
+        self.exec(
+            "INSERT OR REPLACE INTO approvals (chat_id, user_id, user_name, approved_by, at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![chat_id, approval.user_id, approval.user_name, approval.approved_by, approval.at],
+        );
+    }
+
+    fn unapprove(&mut self, chat_id: i64, user_id: i64) -> bool {
+        self.exec("DELETE FROM approvals WHERE chat_id = ?1 AND user_id = ?2", params![chat_id, user_id]) > 0
+    }
+
+    fn unapprove_all(&mut self, chat_id: i64) -> usize {
+        self.exec("DELETE FROM approvals WHERE chat_id = ?1", params![chat_id])
+    }
+
+    // ─── Captchas ─────────────────────────────────────────────────────────────
+
+    fn captcha(&self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha> {
+        self.read(self.conn.query_row(
+            "SELECT user_id, answer, message_id, tries_left, deadline FROM captchas WHERE chat_id = ?1 AND user_id = ?2",
+            params![chat_id, user_id],
+            captcha_row,
+        ).optional()).flatten()
+    }
+
+    fn save_captcha(&mut self, chat_id: i64, captcha: &PendingCaptcha) {
+        self.exec(
+            "INSERT OR REPLACE INTO captchas (chat_id, user_id, answer, message_id, tries_left, deadline)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![chat_id, captcha.user_id, captcha.answer, captcha.message_id, captcha.tries_left, captcha.deadline],
+        );
+    }
+
+    fn delete_captcha(&mut self, chat_id: i64, user_id: i64) -> Option<PendingCaptcha> {
+        let captcha = self.captcha(chat_id, user_id)?;
+        self.exec("DELETE FROM captchas WHERE chat_id = ?1 AND user_id = ?2", params![chat_id, user_id]);
+        Some(captcha)
+    }
+
+    fn expired_captchas(&self, now: i64) -> Vec<(i64, PendingCaptcha)> {
+        self.rows(
+            "SELECT user_id, answer, message_id, tries_left, deadline, chat_id FROM captchas
+             WHERE deadline <= ?1 ORDER BY deadline, chat_id, user_id",
+            params![now],
+            |r| Ok((r.get(5)?, captcha_row(r)?)),
+        )
+    }
+}
+
+fn captcha_row(r: &rusqlite::Row) -> rusqlite::Result<PendingCaptcha> {
+    Ok(PendingCaptcha {
+        user_id:    r.get(0)?,
+        answer:     r.get(1)?,
+        message_id: r.get(2)?,
+        tries_left: r.get(3)?,
+        deadline:   r.get(4)?,
+    })
+}
+
+fn federation_row(r: &rusqlite::Row) -> rusqlite::Result<Federation> {
+    let admins: String = r.get(3)?;
+    Ok(Federation {
+        id:       r.get(0)?,
+        name:     r.get(1)?,
+        owner_id: r.get(2)?,
+        admins:   serde_json::from_str(&admins).unwrap_or_default(),
+    })
+}
+
+fn fed_ban_row(r: &rusqlite::Row) -> rusqlite::Result<FedBan> {
+    Ok(FedBan {
+        user_id:   r.get(0)?,
+        user_name: r.get(1)?,
+        reason:    r.get(2)?,
+        banned_by: r.get(3)?,
+        at:        r.get(4)?,
+    })
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::time::{Duration, Instant};
+use common::{send, setup, GROUP};
+use multipurpose_bot::admin_cache::{AdminCache, TTL};
+use multipurpose_bot::mock_api::{member_update, FakeMessage, FakeUser};
+
+#[test]
+fn lists_expire_after_the_ttl() {
+    let mut cache = AdminCache::default();
+    let now = Instant::now();
+    cache.insert(GROUP, Vec::new(), now);
+
+    assert!(cache.get(GROUP, now + TTL - Duration::from_secs(1)).is_some());
+    assert!(cache.get(GROUP, now + TTL).is_none());
+    assert!(cache.get(-200, now).is_none());
+    cache.invalidate(GROUP);
+    assert!(cache.get(GROUP, now).is_none());
+}
+
+#[tokio::test]
+async fn admin_commands_share_one_fetch_until_an_admin_changes() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    let other  = FakeUser::new(3, "Other");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warn 2 spam").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warn 2 more spam").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/warn 1").into_update()).await;
+    assert_eq!(api.calls_to("getChatAdministrators").len(), 1);
+    // Only to look up the targets given by ID, never to check anyone's rights
+    assert_eq!(api.calls_to("getChatMember").len(), 2);
+
+    // Someone joining doesn't touch the list; a promotion does
+    send(&bot, &state, member_update(GROUP, &other, "left", "member")).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/flood").into_update()).await;
+    assert_eq!(api.calls_to("getChatAdministrators").len(), 1);
+
+    api.set_admin(GROUP, member.id);
+    send(&bot, &state, member_update(GROUP, &member, "member", "administrator")).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/flood").into_update()).await;
+    assert_eq!(api.calls_to("getChatAdministrators").len(), 2);
+    assert!(api.sent_texts().last().unwrap().contains("Antiflood is off"), "{:?}", api.sent_texts());
+}
+
+#[tokio::test]
+async fn adminlist_shows_titles_and_admincache_refreshes() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let helper = FakeUser::new(2, "Helper");
+    api.set_admin(GROUP, admin.id);
+    api.set_title(GROUP, admin.id, "Head <Mod>");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &helper, "/adminlist").into_update()).await;
+    api.set_admin(GROUP, helper.id);
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/admincache").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &helper, "/admins").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("• User — <i>Head &lt;Mod&gt;</i>"), "{texts:?}");
+    assert_eq!(texts[0].lines().count(), 2, "{texts:?}");
+    assert_eq!(texts[1], "✅ Admin list refreshed — 2 admin(s).");
+    assert_eq!(texts[2].lines().count(), 3, "{texts:?}");
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use std::time::{Duration, Instant};
+use common::{send, setup, GROUP};
+use multipurpose_bot::cmd::antiflood::FloodCounter;
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use multipurpose_bot::state::Action;
+
+#[test]
+fn window_slides_and_restarts_after_tripping() {
+    let mut counter = FloodCounter::default();
+    let start  = Instant::now();
+    let window = Duration::from_secs(10);
+    let at = |secs| start + Duration::from_secs(secs);
+
+    assert!(!counter.hit((GROUP, 1), at(0), 2, window));
+    assert!(!counter.hit((GROUP, 1), at(5), 2, window));
+    // The first message has left the window by now
+    assert!(!counter.hit((GROUP, 1), at(11), 2, window));
+    assert!(!counter.hit((GROUP, 2), at(12), 2, window));
+    assert!(counter.hit((GROUP, 1), at(12), 2, window));
+    assert!(!counter.hit((GROUP, 1), at(13), 2, window));
+}
+
+#[tokio::test]
+async fn flooder_is_muted_and_announced() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let flooder = FakeUser::new(2, "Flooder");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setflood 3").into_update()).await;
+    api.take_calls();
+    for i in 0..4 {
+        send(&bot, &state, FakeMessage::new(GROUP, &flooder, &format!("spam {i}")).into_update()).await;
+    }
+
+    let mutes = api.calls_to("restrictChatMember");
+    assert_eq!(mutes.len(), 1);
+    assert_eq!(mutes[0]["user_id"], flooder.id);
+    assert_eq!(mutes[0]["permissions"]["can_send_messages"], false);
+    let texts = api.sent_texts();
+    assert!(texts[0].contains(">Flooder</a> has been muted for flooding"), "{texts:?}");
+
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    let mute = log.iter().find(|e| e.target_id == Some(flooder.id)).unwrap();
+    assert_eq!((mute.action, mute.actor_name.as_str(), mute.reason.as_str()), (Action::Mute, "MockBot", "Flooding"));
+}
+
+#[tokio::test]
+async fn admins_and_commands_count_but_admins_are_spared() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setflood 2 1m").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setfloodmode tban 1h").into_update()).await;
+    for _ in 0..3 {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, "hello").into_update()).await;
+    }
+    for _ in 0..3 {
+        send(&bot, &state, FakeMessage::new(GROUP, &member, "/roll").into_update()).await;
+    }
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/flood").into_update()).await;
+
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0]["user_id"], member.id);
+    let in_an_hour = chrono::Utc::now().timestamp() + 3600;
+    assert!((bans[0]["until_date"].as_i64().unwrap() - in_an_hour).abs() < 5);
+
+    let texts = api.sent_texts();
+    // Two rolls go through; the third message is the flood and isn't run
+    assert_eq!(texts.iter().filter(|t| t.contains("banned for 1h for flooding")).count(), 1, "{texts:?}");
+    assert!(texts.last().unwrap().contains("more than 2 messages in 1m will be banned for 1h"), "{texts:?}");
+}
+
+#[tokio::test]
+async fn bad_settings_are_refused() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    for cmd in ["/setflood", "/setflood 1", "/setflood 5 1h", "/setfloodmode tmute"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, cmd).into_update()).await;
+    }
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/flood").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[..4].iter().all(|t| t.starts_with("Usage")), "{texts:?}");
+    assert!(texts[4].contains("Antiflood is off"), "{texts:?}");
+    assert_eq!(state.lock().await.store.chat_settings(GROUP).flood_limit, 0);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use multipurpose_bot::state::Action;
+use serde_json::json;
+
+#[tokio::test]
+async fn approved_users_skip_automatic_enforcement_only() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let regular = FakeUser::new(2, "Regular");
+    let member  = FakeUser::new(3, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    let hello = FakeMessage::new(GROUP, &regular, "hello");
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approve").reply_to(&hello).into_update()).await;
+    for cmd in ["/setflood 3", "/lock forwards", "/addblacklist crypto"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, cmd).into_update()).await;
+    }
+    api.take_calls();
+
+    for i in 0..4 {
+        send(&bot, &state, FakeMessage::new(GROUP, &regular, &format!("crypto news {i}")).into_update()).await;
+    }
+    let forward = FakeMessage::new(GROUP, &regular, "fwd")
+        .with("forward_origin", json!({ "type": "hidden_user", "date": 0, "sender_user_name": "Someone" }));
+    let spam = FakeMessage::new(GROUP, &member, "crypto giveaway");
+    send(&bot, &state, forward.into_update()).await;
+    send(&bot, &state, spam.clone().into_update()).await;
+
+    let deleted: Vec<_> = api.calls_to("deleteMessage").iter().map(|c| c["message_id"].as_i64().unwrap()).collect();
+    assert_eq!(deleted, vec![spam.id()]);
+    assert!(api.calls_to("restrictChatMember").is_empty());
+
+    // Admin commands still work on them
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/ban").reply_to(&hello).into_update()).await;
+    assert_eq!(api.calls_to("banChatMember")[0]["user_id"], regular.id);
+}
+
+#[tokio::test]
+async fn approve_unapprove_and_list() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let other   = FakeUser::new(3, "Other");
+    let regular = FakeUser::new(2, "Regular");
+    api.set_admin(GROUP, admin.id);
+    api.set_admin(GROUP, other.id);
+    let from_other = FakeMessage::new(GROUP, &other, "hi");
+    let hello = FakeMessage::new(GROUP, &regular, "hello");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &regular, "/approve").reply_to(&hello).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approve").reply_to(&from_other).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approve").reply_to(&hello).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approve").reply_to(&hello).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approved").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/unapprove").reply_to(&hello).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/unapprove 2").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert_eq!(texts[0], "❌ You need to be an admin to use this command.");
+    assert!(texts[1].contains("is an admin"), "{texts:?}");
+    assert!(texts[2].starts_with("✅ <b>Regular</b> is approved"), "{texts:?}");
+    assert_eq!(texts[3], "✅ <b>Regular</b> is already approved.");
+    assert!(texts[4].starts_with("✅ <b>Approved users</b> (1)\n• <a href=\"tg://user?id=2\">Regular</a> — since "), "{texts:?}");
+    assert_eq!(texts[5], "✅ <b>Regular</b> is no longer approved.");
+    assert_eq!(texts[6], "❌ <b>User</b> isn't approved.");
+
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    let actions: Vec<Action> = log.iter().map(|e| e.action).collect();
+    assert_eq!(actions, vec![Action::Approve, Action::Unapprove]);
+}
+
+#[tokio::test]
+async fn only_the_owner_unapproves_everyone() {
+    let (api, bot, state) = setup().await;
+    let owner = FakeUser::new(1, "Owner");
+    let admin = FakeUser::new(5, "Admin");
+    api.set_owner(GROUP, owner.id);
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approve 2").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/approve 3").into_update()).await;
+    api.take_calls();
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/unapproveall").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/unapproveall").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/approved").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert_eq!(texts[0], "❌ Only the chat's owner can remove every approval.");
+    assert_eq!(texts[1], "✅ Removed 2 approval(s).");
+    assert!(texts[2].starts_with("Nobody is approved"), "{texts:?}");
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    assert_eq!((log[2].action, log[2].reason.as_str()), (Action::Unapprove, "All 2 approval(s) removed"));
+}
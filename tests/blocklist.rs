@@ -0,0 +1,102 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::cmd::blocklist::Blocklist;
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use multipurpose_bot::state::{BlockedTrigger, BlocklistAction};
+
+#[tokio::test]
+async fn words_phrases_and_patterns_are_deleted() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    for cmd in ["/addblacklist Crypto", "/addblacklist free  money", r"/addblacklist /t\.me\/\w+/"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, cmd).into_update()).await;
+    }
+    api.take_calls();
+
+    let clean   = FakeMessage::new(GROUP, &member, "cryptography is fun");
+    let word    = FakeMessage::new(GROUP, &member, "Buy CRYPTO!");
+    let phrase  = FakeMessage::new(GROUP, &member, "get Free Money here");
+    let pattern = FakeMessage::new(GROUP, &member, "").with("text", serde_json::Value::Null).with("caption", "join T.me/scam".into());
+    let from_admin = FakeMessage::new(GROUP, &admin, "crypto is banned here");
+    for msg in [clean, word.clone(), phrase.clone(), pattern.clone(), from_admin] {
+        send(&bot, &state, msg.into_update()).await;
+    }
+
+    let deleted: Vec<_> = api.calls_to("deleteMessage").iter().map(|c| c["message_id"].as_i64().unwrap()).collect();
+    assert_eq!(deleted, vec![word.id(), phrase.id(), pattern.id()]);
+    assert!(api.sent_texts().is_empty());
+}
+
+#[tokio::test]
+async fn modes_warn_and_ban_and_per_trigger_overrides() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/blacklistmode warn").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/addblacklist spam").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/addblacklist scam ban").into_update()).await;
+    api.take_calls();
+
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "spam spam").into_update()).await;
+    assert!(api.sent_texts()[0].contains("warned! (1/3)"), "{:?}", api.sent_texts());
+    assert_eq!(state.lock().await.store.warnings(GROUP, member.id)[0].issued_by_name, "MockBot");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "a scam").into_update()).await;
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0]["user_id"], member.id);
+    assert!(api.sent_texts()[1].contains("has been banned for using a blocklisted word"));
+}
+
+#[tokio::test]
+async fn list_remove_and_bad_patterns() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    for cmd in ["/addblacklist /(unclosed/", "/addblacklist spam mute", "/addblacklist <b>", "/blacklist",
+                "/unblacklist SPAM", "/unblacklist spam"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, cmd).into_update()).await;
+    }
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "spam").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("doesn't work: unclosed group"), "{texts:?}");
+    assert!(texts[3].contains("(2)") && texts[3].contains("<code>spam</code> → mute"), "{texts:?}");
+    assert!(texts[3].contains("<code>&lt;b&gt;</code>"), "{texts:?}");
+    assert!(texts[4].starts_with("✅ Removed"), "{texts:?}");
+    assert!(texts[5].contains("isn't on the blocklist"), "{texts:?}");
+    assert!(api.calls_to("deleteMessage").is_empty());
+}
+
+#[test]
+fn patterns_match_as_a_set_and_map_back_to_their_trigger() {
+    let blocked = |trigger: &str, action| BlockedTrigger { trigger: trigger.into(), action };
+    let list = Blocklist::compile(vec![
+        blocked(r"/c[a@]sh\s*app/", None),
+        blocked("/(broken/", None),
+        blocked(r"/\bt\.me/\w+/", Some(BlocklistAction::Ban)),
+    ]);
+
+    assert_eq!(list.find("Send it to C@SH   APP"), Some(&blocked(r"/c[a@]sh\s*app/", None)));
+    assert_eq!(list.find("join T.ME/scam"), Some(&blocked(r"/\bt\.me/\w+/", Some(BlocklistAction::Ban))));
+    assert_eq!(list.find("nothing to see, (broken"), None);
+}
+
+#[tokio::test]
+async fn oversized_patterns_are_refused() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, r"/addblacklist /\w{500}{500}/").into_update()).await;
+    assert!(api.sent_texts()[0].contains("doesn't work: it's too big"), "{:?}", api.sent_texts());
+    assert!(state.lock().await.store.blocklist(GROUP).is_empty());
+}
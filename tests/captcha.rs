@@ -0,0 +1,100 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{send, setup, GROUP};
+use multipurpose_bot::cmd::captcha::{self, CaptchaMode};
+use multipurpose_bot::mock_api::{callback, FakeMessage, FakeUser};
+
+#[tokio::test]
+async fn button_captcha_only_the_newcomer_can_solve() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let newbie = FakeUser::new(5, "Newbie");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/captcha button").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/welcome on").into_update()).await;
+    api.take_calls();
+    send(&bot, &state, FakeMessage::join(GROUP, &newbie).into_update()).await;
+
+    let mute = &api.calls_to("restrictChatMember")[0];
+    assert_eq!(mute["user_id"], newbie.id);
+    assert_eq!(mute["permissions"]["can_send_messages"], false);
+    let challenge = api.take_calls().into_iter().find(|c| c.method == "sendMessage").unwrap();
+    assert_eq!(challenge.params["reply_markup"]["inline_keyboard"][0][0]["callback_data"], "cap:5:ok");
+    let challenge_id = challenge.result["message_id"].as_i64().unwrap();
+
+    send(&bot, &state, callback(GROUP, &admin, challenge_id, "cap:5:ok")).await;
+    assert!(api.calls_to("answerCallbackQuery")[0]["text"].as_str().unwrap().contains("someone else"));
+    assert!(api.calls_to("restrictChatMember").is_empty());
+
+    send(&bot, &state, callback(GROUP, &newbie, challenge_id, "cap:5:ok")).await;
+    assert_eq!(api.calls_to("restrictChatMember")[0]["permissions"]["can_send_messages"], true);
+    assert_eq!(api.calls_to("deleteMessage")[0]["message_id"], challenge_id);
+    assert!(api.sent_texts()[0].contains("welcome to"), "{:?}", api.sent_texts());
+    assert_eq!(state.lock().await.store.captcha(GROUP, newbie.id), None);
+}
+
+#[tokio::test]
+async fn wrong_math_answers_get_the_member_kicked() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let newbie = FakeUser::new(5, "Newbie");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/captcha math").into_update()).await;
+    send(&bot, &state, FakeMessage::join(GROUP, &newbie).into_update()).await;
+    let challenge_id = state.lock().await.store.captcha(GROUP, newbie.id).unwrap().message_id;
+    assert!(api.sent_texts().last().unwrap().contains("What is"));
+
+    for _ in 0..3 {
+        send(&bot, &state, callback(GROUP, &newbie, challenge_id, "cap:5:999")).await;
+    }
+    let answers: Vec<_> = api.calls_to("answerCallbackQuery").iter().map(|a| a["text"].as_str().unwrap().to_string()).collect();
+    assert!(answers[0].contains("2 tries left") && answers[1].contains("1 try left"), "{answers:?}");
+    assert_eq!(api.calls_to("banChatMember")[0]["user_id"], newbie.id);
+    assert_eq!(api.calls_to("unbanChatMember").len(), 1);
+}
+
+#[tokio::test]
+async fn unsolved_captcha_times_out() {
+    let (api, bot, state) = setup().await;
+    let newbie = FakeUser::new(5, "Newbie");
+    {
+        let mut st = state.lock().await;
+        let mut settings = st.store.chat_settings(GROUP);
+        settings.captcha_mode = CaptchaMode::Emoji;
+        st.store.save_chat_settings(GROUP, &settings);
+    }
+
+    send(&bot, &state, FakeMessage::join(GROUP, &newbie).into_update()).await;
+    let rows = api.calls_to("sendMessage")[0]["reply_markup"]["inline_keyboard"].clone();
+    assert_eq!(rows.as_array().unwrap().len(), 2);
+    let pending = state.lock().await.store.captcha(GROUP, newbie.id).unwrap();
+    assert!((pending.deadline - Utc::now().timestamp() - 300).abs() <= 1, "{pending:?}");
+
+    captcha::tick(&bot, &state, Utc::now()).await;
+    assert!(api.calls_to("banChatMember").is_empty());
+
+    captcha::tick(&bot, &state, Utc::now() + Duration::minutes(6)).await;
+    assert_eq!(api.calls_to("banChatMember")[0]["user_id"], newbie.id);
+    assert_eq!(api.calls_to("deleteMessage")[0]["message_id"], pending.message_id);
+    assert_eq!(state.lock().await.store.captcha(GROUP, newbie.id), None);
+}
+
+#[tokio::test]
+async fn restricted_members_keep_their_restrictions() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let muted  = FakeUser::new(5, "Muted");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/captcha button").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/mute 5").into_update()).await;
+    api.take_calls();
+    send(&bot, &state, FakeMessage::join(GROUP, &muted).into_update()).await;
+
+    assert!(api.calls_to("restrictChatMember").is_empty());
+    assert!(api.sent_texts().iter().all(|t| !t.contains("human")), "{:?}", api.sent_texts());
+    assert_eq!(state.lock().await.store.captcha(GROUP, muted.id), None);
+}
@@ -0,0 +1,23 @@
+// Each test binary uses a different subset of these helpers
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use multipurpose_bot::handler::handle_update;
+use multipurpose_bot::mock_api::MockApi;
+use multipurpose_bot::state::{new_state, SharedState};
+use multipurpose_bot::storage::MemoryStorage;
+use tgbotrs::{Bot, Update};
+
+pub const GROUP: i64 = -100;
+
+/// Fresh mock server, a bot pointed at it and empty in-memory state.
+pub async fn setup() -> (MockApi, Bot, SharedState) {
+    let api   = MockApi::start().await;
+    let bot   = api.bot().await;
+    let state = new_state(Box::new(MemoryStorage::default()));
+    (api, bot, state)
+}
+
+pub async fn send(bot: &Bot, state: &SharedState, update: Update) {
+    handle_update(bot.clone(), update, Arc::clone(state)).await;
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+use multipurpose_bot::duration::{format, parse};
+
+#[test]
+fn parses_units_and_combinations() {
+    assert_eq!(parse("30s"), Some(Duration::from_secs(30)));
+    assert_eq!(parse("10m"), Some(Duration::from_secs(600)));
+    assert_eq!(parse("2H"),  Some(Duration::from_secs(7200)));
+    assert_eq!(parse("1d12h"), Some(Duration::from_secs(129_600)));
+    assert_eq!(parse("1w"), Some(Duration::from_secs(604_800)));
+}
+
+#[test]
+fn rejects_bare_numbers_and_junk() {
+    for bad in ["", "10", "5x", "h", "1d2", "0m"] {
+        assert_eq!(parse(bad), None, "{bad:?}");
+    }
+}
+
+#[test]
+fn formats_largest_units_first() {
+    assert_eq!(format(Duration::from_secs(93_784)), "1d 2h 3m 4s");
+    assert_eq!(format(Duration::from_secs(3600)), "1h");
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use multipurpose_bot::state::Action;
+
+const OTHER_GROUP: i64 = -200;
+
+#[tokio::test]
+async fn fban_bans_in_every_chat_and_on_rejoin() {
+    let (api, bot, state) = setup().await;
+    let owner   = FakeUser::new(1, "Owner");
+    let helper  = FakeUser::new(2, "Helper");
+    let spammer = FakeUser::new(3, "Spammer");
+    api.set_owner(GROUP, owner.id);
+    api.set_owner(OTHER_GROUP, owner.id);
+
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/newfed Friendly Network").into_update()).await;
+    let fed = state.lock().await.store.owned_federation(owner.id).unwrap();
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, &format!("/joinfed {}", fed.id)).into_update()).await;
+    send(&bot, &state, FakeMessage::new(OTHER_GROUP, &owner, &format!("/joinfed {}", fed.id)).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/fpromote 2").into_update()).await;
+    api.take_calls();
+
+    // The helper isn't an admin of either chat, only of the federation
+    let spam = FakeMessage::new(GROUP, &spammer, "cheap followers");
+    send(&bot, &state, FakeMessage::new(GROUP, &helper, "/fban selling followers").reply_to(&spam).into_update()).await;
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.iter().map(|b| b["chat_id"].as_i64().unwrap()).collect::<Vec<_>>(), vec![OTHER_GROUP, GROUP]);
+    assert!(bans.iter().all(|b| b["user_id"] == spammer.id));
+    let texts = api.sent_texts();
+    assert_eq!(texts[0], "🔨 <b>Spammer</b> is now banned in <b>Friendly Network</b>: 2 of 2 chat(s).\nReason: selling followers");
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    assert_eq!((log[0].action, log[0].actor_id), (Action::Ban, helper.id));
+    assert_eq!(log[0].reason, "Federation ban: selling followers");
+
+    // Coming back is caught before the captcha and the welcome
+    api.take_calls();
+    send(&bot, &state, FakeMessage::join(OTHER_GROUP, &spammer).into_update()).await;
+    assert_eq!(api.calls_to("banChatMember").len(), 1);
+    let texts = api.sent_texts();
+    assert_eq!(texts.len(), 1, "{texts:?}");
+    assert!(texts[0].contains("banned in the federation <b>Friendly Network</b>"), "{texts:?}");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &helper, "/unfban 3").into_update()).await;
+    assert_eq!(api.calls_to("unbanChatMember").len(), 2);
+    api.take_calls();
+    send(&bot, &state, FakeMessage::join(OTHER_GROUP, &spammer).into_update()).await;
+    assert!(api.calls_to("banChatMember").is_empty());
+}
+
+#[tokio::test]
+async fn only_owners_and_fed_admins_manage_a_federation() {
+    let (api, bot, state) = setup().await;
+    let owner  = FakeUser::new(1, "Owner");
+    let admin  = FakeUser::new(2, "Admin");
+    let member = FakeUser::new(4, "Member");
+    api.set_owner(GROUP, owner.id);
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/newfed Net").into_update()).await;
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/newfed Another").into_update()).await;
+    let fed = state.lock().await.store.owned_federation(owner.id).unwrap();
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, &format!("/joinfed {}", fed.id)).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, &format!("/joinfed {}", fed.id)).into_update()).await;
+    // A chat admin isn't a federation admin, and federation admins can't be fbanned
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/fban 4").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/fban 1").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/fpromote 4").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/fedadmins").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains(&format!("ID: <code>{}</code>", fed.id)), "{texts:?}");
+    assert!(texts[1].starts_with("❌ You already own <b>Net</b>"), "{texts:?}");
+    assert_eq!(texts[2], "❌ Only the chat's owner can change its federation.");
+    assert!(texts[3].contains("joined <b>Net</b>"), "{texts:?}");
+    assert_eq!(texts[4], "❌ Only admins of <b>Net</b> can do that.");
+    assert!(texts[5].contains("federation admin"), "{texts:?}");
+    assert_eq!(texts[6], "❌ Only the federation's owner can change its admins.");
+    assert!(texts[7].contains("👑 <a href=\"tg://user?id=1\">User</a>"), "{texts:?}");
+    assert!(api.calls_to("banChatMember").is_empty());
+}
+
+#[tokio::test]
+async fn fedbanlist_exports_csv() {
+    let (api, bot, state) = setup().await;
+    let owner = FakeUser::new(1, "Owner");
+    api.set_owner(GROUP, owner.id);
+
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/fedbanlist").into_update()).await;
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/newfed Net").into_update()).await;
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/fedbanlist").into_update()).await;
+    let fed = state.lock().await.store.owned_federation(owner.id).unwrap();
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, &format!("/joinfed {}", fed.id)).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/fban 7 spam, lots of it").into_update()).await;
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/fedbanlist").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].starts_with("❌ You don't own a federation"), "{texts:?}");
+    assert_eq!(texts[2], "✅ Nobody is banned in <b>Net</b>.");
+    let docs = api.calls_to("sendDocument");
+    assert_eq!(docs.len(), 1);
+    let body = docs[0].as_str().unwrap();
+    assert!(body.contains("user_id,user,reason,banned_by,time\n7,User,\"spam, lots of it\",1,"), "{body}");
+}
+
+#[tokio::test]
+async fn fban_by_id_from_a_private_chat() {
+    let (api, bot, state) = setup().await;
+    let owner = FakeUser::new(1, "Owner");
+    api.set_owner(GROUP, owner.id);
+
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/newfed Friendly Network").into_update()).await;
+    let fed = state.lock().await.store.owned_federation(owner.id).unwrap();
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, &format!("/joinfed {}", fed.id)).into_update()).await;
+    // Someone the bot has never seen in any chat
+    api.fail("getChatMember", "Bad Request: user not found");
+    api.take_calls();
+
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/fban 777 raid bot").into_update()).await;
+    assert_eq!(api.calls_to("banChatMember")[0]["user_id"], 777);
+    assert_eq!(state.lock().await.store.fed_ban(&fed.id, 777).unwrap().reason, "raid bot");
+    send(&bot, &state, FakeMessage::new(owner.id, &owner, "/unfban 777").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert_eq!(texts[0], "🔨 <b>777</b> is now banned in <b>Friendly Network</b>: 1 of 1 chat(s).\nReason: raid bot");
+    assert!(texts[1].starts_with("✅ <b>777</b> is no longer banned"), "{texts:?}");
+    assert_eq!(api.calls_to("unbanChatMember")[0]["user_id"], 777);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+
+#[tokio::test]
+async fn guessing_the_secret_wins() {
+    let (api, bot, state) = setup().await;
+    let alice = FakeUser::new(1, "Alice");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &alice, "/guess").into_update()).await;
+    let secret = state.lock().await.store.game(GROUP).expect("game started").secret;
+
+    let guess = format!("/guess {secret}");
+    send(&bot, &state, FakeMessage::new(GROUP, &alice, &guess).into_update()).await;
+
+    assert!(api.sent_texts().last().unwrap().contains("Correct!"));
+    assert!(state.lock().await.store.game(GROUP).is_none());
+}
+
+#[tokio::test]
+async fn running_out_of_attempts_loses() {
+    let (api, bot, state) = setup().await;
+    let alice = FakeUser::new(1, "Alice");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &alice, "/guess").into_update()).await;
+    let game  = state.lock().await.store.game(GROUP).expect("game started");
+    let wrong = if game.secret == 1 { "/guess 2" } else { "/guess 1" };
+
+    for _ in 0..game.max_attempts {
+        send(&bot, &state, FakeMessage::new(GROUP, &alice, wrong).into_update()).await;
+    }
+
+    let texts = api.sent_texts();
+    assert!(texts[1].contains(&format!("Guess 1/{}", game.max_attempts)), "{texts:?}");
+    assert!(texts.last().unwrap().contains("Game over!"));
+    assert!(state.lock().await.store.game(GROUP).is_none());
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use serde_json::json;
+
+#[tokio::test]
+async fn locked_types_are_deleted_for_members_only() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/lock links stickers, forwards").into_update()).await;
+    api.take_calls();
+
+    let link = FakeMessage::new(GROUP, &member, "see example.com")
+        .with("entities", json!([{ "type": "url", "offset": 4, "length": 11 }]));
+    let hidden = FakeMessage::new(GROUP, &member, "click")
+        .with("entities", json!([{ "type": "text_link", "offset": 0, "length": 5, "url": "https://x.y" }]));
+    let sticker = FakeMessage::new(GROUP, &member, "").with("text", json!(null))
+        .with("sticker", json!({ "file_id": "s", "file_unique_id": "s", "type": "regular", "width": 512,
+                                 "height": 512, "is_animated": false, "is_video": false }));
+    let forward = FakeMessage::new(GROUP, &member, "fwd")
+        .with("forward_origin", json!({ "type": "hidden_user", "date": 0, "sender_user_name": "Someone" }));
+    let plain = FakeMessage::new(GROUP, &member, "just text");
+    let admin_link = FakeMessage::new(GROUP, &admin, "docs: example.com")
+        .with("entities", json!([{ "type": "url", "offset": 6, "length": 11 }]));
+    let expected = [link.id(), hidden.id(), sticker.id(), forward.id()];
+    for msg in [link, hidden, sticker, forward, plain, admin_link] {
+        send(&bot, &state, msg.into_update()).await;
+    }
+
+    let deleted: Vec<_> = api.calls_to("deleteMessage").iter().map(|c| c["message_id"].as_i64().unwrap()).collect();
+    assert_eq!(deleted, expected);
+}
+
+#[tokio::test]
+async fn channel_lock_spares_anonymous_admins_and_linked_posts() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    let channel_bot = FakeUser::new(136_817_688, "Channel");
+    api.set_admin(GROUP, admin.id);
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/lock channel").into_update()).await;
+    api.take_calls();
+
+    let as_channel = FakeMessage::new(GROUP, &channel_bot, "hello from my channel")
+        .with("sender_chat", json!({ "id": -100_555, "type": "channel", "title": "Spam Channel" }));
+    let linked = FakeMessage::new(GROUP, &channel_bot, "new post")
+        .with("sender_chat", json!({ "id": -100_777, "type": "channel", "title": "Our Channel" }))
+        .with("is_automatic_forward", json!(true));
+    let anonymous = FakeMessage::new(GROUP, &channel_bot, "from the admins")
+        .with("sender_chat", json!({ "id": GROUP, "type": "supergroup", "title": "Group" }));
+    let expected = as_channel.id();
+    for msg in [as_channel, linked, anonymous] {
+        send(&bot, &state, msg.into_update()).await;
+    }
+
+    let deleted = api.calls_to("deleteMessage");
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0]["message_id"], expected);
+}
+
+#[tokio::test]
+async fn unlock_and_list() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    for cmd in ["/lock all", "/unlock gifs url", "/lock nonsense", "/locks"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, cmd).into_update()).await;
+    }
+
+    let texts = api.sent_texts();
+    assert!(texts[1].contains("Unlocked: gif, url"), "{texts:?}");
+    assert!(texts[2].starts_with("Usage: /lock"), "{texts:?}");
+    let list = &texts[3];
+    assert!(list.contains("🔓 <code>url</code>") && list.contains("🔒 <code>sticker</code>"), "{list}");
+    assert_eq!(state.lock().await.store.chat_settings(GROUP).locks.len(), 8);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+use common::{send, setup, GROUP};
+use multipurpose_bot::middleware::{DisabledCommands, Maintenance, Pipeline, RateLimit};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+
+#[tokio::test]
+async fn disabled_commands_cover_aliases() {
+    let (api, bot, state) = setup().await;
+    state.lock().await.middleware = Arc::new(Pipeline::default().with(DisabledCommands::new(["flip"])));
+    let alice = FakeUser::new(1, "Alice");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &alice, "/coin").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &alice, "/shrug").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("/coin is disabled"));
+    assert!(texts[1].contains("ツ"));
+}
+
+#[tokio::test]
+async fn maintenance_lets_owners_through() {
+    let (api, bot, state) = setup().await;
+    state.lock().await.middleware = Arc::new(Pipeline::default().with(Maintenance::new([1])));
+    let (owner, bob) = (FakeUser::new(1, "Owner"), FakeUser::new(2, "Bob"));
+
+    send(&bot, &state, FakeMessage::new(GROUP, &bob, "/shrug").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/shrug").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("maintenance"));
+    assert!(texts[1].contains("ツ"));
+}
+
+#[tokio::test]
+async fn rate_limit_warns_once_per_burst() {
+    let (api, bot, state) = setup().await;
+    state.lock().await.middleware = Arc::new(
+        Pipeline::default().with(RateLimit::new(2, Duration::from_secs(60))),
+    );
+    let alice = FakeUser::new(1, "Alice");
+
+    for _ in 0..5 {
+        send(&bot, &state, FakeMessage::new(GROUP, &alice, "/shrug").into_update()).await;
+    }
+
+    let texts = api.sent_texts();
+    assert_eq!(texts.len(), 3);
+    assert!(texts[2].contains("Slow down"));
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use multipurpose_bot::state::Warning;
+
+#[tokio::test]
+async fn third_warning_bans_and_resets() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(GROUP, admin.id);
+
+    let offending = FakeMessage::new(GROUP, &spammer, "cheap followers!!");
+    for _ in 0..3 {
+        let warn = FakeMessage::new(GROUP, &admin, "/warn spam").reply_to(&offending);
+        send(&bot, &state, warn.into_update()).await;
+    }
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("(1/3)"), "{texts:?}");
+    assert!(texts[1].contains("(2/3)"), "{texts:?}");
+    assert!(texts[2].contains("banned"), "{texts:?}");
+
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0]["user_id"], spammer.id);
+    assert!(state.lock().await.store.warnings(GROUP, spammer.id).is_empty());
+}
+
+#[tokio::test]
+async fn members_cannot_warn() {
+    let (api, bot, state) = setup().await;
+    let member  = FakeUser::new(1, "Member");
+    let other   = FakeUser::new(2, "Other");
+
+    let target = FakeMessage::new(GROUP, &other, "hi");
+    let warn   = FakeMessage::new(GROUP, &member, "/warn").reply_to(&target);
+    send(&bot, &state, warn.into_update()).await;
+
+    assert!(api.sent_texts()[0].contains("need to be an admin"));
+    assert!(state.lock().await.store.warnings(GROUP, other.id).is_empty());
+}
+
+#[tokio::test]
+async fn warn_mode_tmute_mutes_until_a_date() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warnlimit 1").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warnmode tmute 1h").into_update()).await;
+    let offending = FakeMessage::new(GROUP, &spammer, "spam");
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warn").reply_to(&offending).into_update()).await;
+
+    assert!(api.sent_texts().last().unwrap().contains("muted for 1h"));
+    let restrict = &api.calls_to("restrictChatMember")[0];
+    let in_an_hour = chrono::Utc::now().timestamp() + 3600;
+    assert!((restrict["until_date"].as_i64().unwrap() - in_an_hour).abs() < 5);
+    assert!(api.calls_to("banChatMember").is_empty());
+}
+
+#[tokio::test]
+async fn warns_lists_issuer_and_drops_expired() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(GROUP, admin.id);
+
+    let old = Warning {
+        reason: "ancient".into(),
+        issued_by: admin.id,
+        issued_by_name: "Admin".into(),
+        issued_at: chrono::Utc::now().timestamp() - 30 * 86_400,
+    };
+    state.lock().await.store.add_warning(GROUP, spammer.id, &old);
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warnexpiry 7").into_update()).await;
+
+    let offending = FakeMessage::new(GROUP, &spammer, "spam");
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warn flooding").reply_to(&offending).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warns").reply_to(&offending).into_update()).await;
+
+    let texts = api.sent_texts();
+    let warns = texts.last().unwrap();
+    assert!(texts[1].contains("(1/3)"), "{texts:?}");
+    assert!(warns.contains("1. flooding — by Admin"), "{warns}");
+    assert!(!warns.contains("ancient"), "{warns}");
+    assert!(warns.contains("expire after 7 day(s)"), "{warns}");
+}
+
+#[tokio::test]
+async fn timed_ban_passes_until_date() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(GROUP, admin.id);
+    let offending = FakeMessage::new(GROUP, &spammer, "spam");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/tban 1d12h").reply_to(&offending).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/ban").reply_to(&offending).into_update()).await;
+
+    let bans = api.calls_to("banChatMember");
+    let expected = chrono::Utc::now().timestamp() + 36 * 3600;
+    assert!((bans[0]["until_date"].as_i64().unwrap() - expected).abs() < 5);
+    assert!(bans[1].get("until_date").is_none());
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("banned for 1d 12h") && texts[0].contains("Until"), "{texts:?}");
+}
+
+#[tokio::test]
+async fn bad_durations_are_refused() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(GROUP, admin.id);
+    let offending = FakeMessage::new(GROUP, &spammer, "spam");
+
+    for cmd in ["/tmute", "/tmute soon", "/mute 10s", "/tban 400d"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &admin, cmd).reply_to(&offending).into_update()).await;
+    }
+
+    assert!(api.calls_to("restrictChatMember").is_empty());
+    assert!(api.calls_to("banChatMember").is_empty());
+    assert_eq!(api.sent_texts().iter().filter(|t| t.starts_with("❌")).count(), 4);
+}
+
+#[tokio::test]
+async fn ban_by_username_takes_duration_and_reason() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer").username("Spammer");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/ban @nobody").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &spammer, "cheap followers!!").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/ban @spammer 1h selling followers").into_update()).await;
+
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0]["user_id"], spammer.id);
+    assert!(bans[0]["until_date"].is_i64());
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("haven't seen @nobody"), "{texts:?}");
+    assert!(texts[1].contains("banned for 1h") && texts[1].ends_with("Reason: selling followers"), "{texts:?}");
+}
+
+#[tokio::test]
+async fn targets_by_id_and_text_mention() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/warn 2 flooding").into_update()).await;
+    let mention = FakeMessage::new(GROUP, &admin, "/kick Zoë off-topic").with("entities", serde_json::json!([
+        { "type": "bot_command", "offset": 0, "length": 5 },
+        { "type": "text_mention", "offset": 6, "length": 3,
+          "user": { "id": 3, "is_bot": false, "first_name": "Zoë" } },
+    ]));
+    send(&bot, &state, mention.into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/kick someone").into_update()).await;
+
+    assert_eq!(state.lock().await.store.warnings(GROUP, 2)[0].reason, "flooding");
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0]["user_id"], 3);
+
+    let texts = api.sent_texts();
+    assert!(texts[1].contains("Zoë") && texts[1].ends_with("Reason: off-topic"), "{texts:?}");
+    assert!(texts[2].contains("@username or user ID"), "{texts:?}");
+}
+
+#[tokio::test]
+async fn purge_batches_from_the_reply_up_to_the_command() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    let first   = FakeMessage::new(GROUP, &member, "first");
+    let command = FakeMessage::new(GROUP, &admin, "/purge").reply_to(&first).with("message_id", (first.id() + 150).into());
+    send(&bot, &state, command.into_update()).await;
+
+    assert_eq!(api.calls_to("deleteMessage")[0]["message_id"], first.id() + 150);
+    let batches = api.calls_to("deleteMessages");
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0]["message_ids"].as_array().unwrap().len(), 100);
+    assert_eq!(batches[0]["message_ids"][0], first.id() + 149);
+    assert_eq!(batches[1]["message_ids"][49], first.id());
+    assert_eq!(api.sent_texts(), vec!["🧹 Purged up to 150 messages."]);
+}
+
+#[tokio::test]
+async fn purge_skips_what_it_cannot_delete() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+    api.fail("deleteMessages", "Bad Request: message can't be deleted");
+    api.fail("deleteMessage", "Bad Request: message can't be deleted");
+
+    let old = FakeMessage::new(GROUP, &admin, "ancient").with("date", (chrono::Utc::now().timestamp() - 3 * 86_400).into());
+    let command = FakeMessage::new(GROUP, &admin, "/purge").reply_to(&old).with("message_id", (old.id() + 250).into());
+    send(&bot, &state, command.into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/spurge 5").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/purge").into_update()).await;
+
+    // Every batch is tried, but only the first one by one: nothing in it could go, so the
+    // older ones are past the 48-hour limit too
+    assert_eq!(api.calls_to("deleteMessages").len(), 4);
+    assert_eq!(api.calls_to("deleteMessage").len(), 1 + 100 + 1 + 5);
+    let texts = api.sent_texts();
+    assert_eq!(texts.len(), 2, "{texts:?}");
+    assert!(texts[0].starts_with("🧹 Purged 0 messages.") && texts[0].contains("48 hours"), "{texts:?}");
+    assert!(texts[1].contains("Reply to the first message"), "{texts:?}");
+}
+
+#[tokio::test]
+async fn purge_within_48_hours_keeps_going_but_caps_single_deletes() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+    api.fail("deleteMessages", "Bad Request: message can't be deleted");
+    api.fail("deleteMessage", "Bad Request: message can't be deleted");
+
+    let recent  = FakeMessage::new(GROUP, &admin, "recent");
+    let command = FakeMessage::new(GROUP, &admin, "/purge").reply_to(&recent).with("message_id", (recent.id() + 250).into());
+    send(&bot, &state, command.into_update()).await;
+
+    assert_eq!(api.calls_to("deleteMessages").len(), 3);
+    assert_eq!(api.calls_to("deleteMessage").len(), 1 + 200);
+    assert_eq!(api.sent_texts(), vec!["🧹 Purged 0 messages.\n250 couldn't be deleted."]);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{send, setup};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+use multipurpose_bot::modlog;
+use multipurpose_bot::state::{Action, AuditEntry};
+
+/// A private supergroup, so log entries can link to its messages
+const CHAT: i64 = -1001234567;
+const CHANNEL: i64 = -1009999;
+
+fn to_channel(api: &multipurpose_bot::mock_api::MockApi) -> Vec<String> {
+    api.calls_to("sendMessage").iter()
+        .filter(|p| p["chat_id"] == CHANNEL)
+        .map(|p| p["text"].as_str().unwrap_or("").to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn actions_are_posted_to_the_log_channel() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(CHAT, admin.id);
+
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, &format!("/setlog {CHANNEL}")).into_update()).await;
+    let offending = FakeMessage::new(CHAT, &spammer, "cheap followers!!");
+    let ban = FakeMessage::new(CHAT, &admin, "/ban 1h selling <followers>").reply_to(&offending);
+    send(&bot, &state, ban.into_update()).await;
+
+    let log = to_channel(&api);
+    assert_eq!(log.len(), 2, "{log:?}");
+    assert!(log[0].contains("moderation log"), "{log:?}");
+    let entry = &log[1];
+    assert!(entry.starts_with("#BAN"), "{entry}");
+    assert!(entry.contains("<b>Admin:</b> <a href=\"tg://user?id=1\">Admin</a>"), "{entry}");
+    assert!(entry.contains("<b>User:</b> <a href=\"tg://user?id=2\">Spammer</a>"), "{entry}");
+    assert!(entry.contains("<b>Duration:</b> 1h"), "{entry}");
+    assert!(entry.contains("<b>Reason:</b> selling &lt;followers&gt;"), "{entry}");
+    assert!(entry.contains(&format!("https://t.me/c/1234567/{}", offending.id())), "{entry}");
+}
+
+#[tokio::test]
+async fn nothing_is_logged_without_a_channel() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer");
+    api.set_admin(CHAT, admin.id);
+    let offending = FakeMessage::new(CHAT, &spammer, "spam");
+
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, &format!("/setlog {CHANNEL}")).into_update()).await;
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/unsetlog").into_update()).await;
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/warn").reply_to(&offending).into_update()).await;
+
+    assert_eq!(to_channel(&api).len(), 1);
+    assert!(api.sent_texts().last().unwrap().contains("(1/3)"));
+}
+
+#[tokio::test]
+async fn modlog_filters_and_exports_csv() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(2, "Spammer").username("spammer");
+    let troll   = FakeUser::new(3, "Troll");
+    api.set_admin(CHAT, admin.id);
+
+    let spam  = FakeMessage::new(CHAT, &spammer, "spam");
+    let troll_msg = FakeMessage::new(CHAT, &troll, "flame");
+    send(&bot, &state, spam.clone().into_update()).await;
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/warn links, again").reply_to(&spam).into_update()).await;
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/kick rude").reply_to(&troll_msg).into_update()).await;
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/mute 3 1h").into_update()).await;
+
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/modlog @spammer").into_update()).await;
+    let log = api.sent_texts().last().unwrap().clone();
+    assert!(log.contains("1 action(s)") && log.contains("#warn <b>Spammer</b> by Admin"), "{log}");
+
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/modlog 3 1d").into_update()).await;
+    let log = api.sent_texts().last().unwrap().clone();
+    assert!(log.contains("2 action(s)"), "{log}");
+    assert!(log.find("#mute <b>User</b> by Admin for 1h").unwrap() < log.find("#kick").unwrap(), "{log}");
+
+    send(&bot, &state, FakeMessage::new(CHAT, &admin, "/modlog csv").into_update()).await;
+    let upload = api.calls_to("sendDocument")[0].as_str().unwrap().to_string();
+    assert!(upload.contains(&format!("filename=\"modlog{CHAT}.csv\"")), "{upload}");
+    assert!(upload.contains("time,action,admin_id,admin,user_id,user,duration_secs,reason,message_id"), "{upload}");
+    assert!(upload.contains(r#",warn,1,Admin,2,Spammer,,"links, again","#), "{upload}");
+    assert!(upload.contains(",mute,1,Admin,3,User,3600,,"), "{upload}");
+}
+
+#[test]
+fn csv_export_defuses_formulas() {
+    let entry = AuditEntry {
+        action: Action::Ban, actor_id: 1, actor_name: "@admin".into(), target_id: Some(-5),
+        target_name: "=HYPERLINK(\"http://x\")".into(), reason: "-1+2, really".into(),
+        duration: None, message_id: 9, at: 0,
+    };
+    let csv = modlog::to_csv(&[entry]);
+    assert_eq!(
+        csv.lines().nth(1).unwrap(),
+        r#"1970-01-01T00:00:00Z,ban,1,"'@admin",-5,"'=HYPERLINK(""http://x"")",,"'-1+2, really",9"#,
+    );
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{send, setup, GROUP};
+use multipurpose_bot::cmd::nightmode::{self, NightMode, NightScope};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+
+fn at(hour: u32, minute: u32) -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn hours_across_midnight_and_offsets() {
+    let night = NightMode::parse("23:00-07:00 UTC+2 media").unwrap();
+    assert_eq!((night.start, night.end, night.utc_offset, night.scope), (23 * 60, 7 * 60, 120, NightScope::Media));
+    assert!(night.is_night(at(21, 0)));
+    assert!(night.is_night(at(4, 59)));
+    assert!(!night.is_night(at(5, 0)));
+    assert!(!night.is_night(at(20, 59)));
+
+    let day = NightMode::parse("1:30-5:00 -05:30").unwrap();
+    assert_eq!(day.describe(), "01:30–05:00 UTC-05:30");
+    assert!(day.is_night(at(7, 0)));
+    assert!(!day.is_night(at(1, 30)));
+
+    assert!(NightMode::parse("23:00").is_err());
+    assert!(NightMode::parse("7:00-7:00").is_err());
+    assert!(NightMode::parse("23:00-07:00 Europe/Berlin").unwrap_err().contains("Unknown timezone"));
+    assert!(NightMode::parse("23:00-07:00 UTC+15").is_err());
+}
+
+#[tokio::test]
+async fn scheduler_locks_and_unlocks_once_each() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setperms text photos links").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/nightmode 22:00-06:00 UTC+1 media").into_update()).await;
+    nightmode::tick(&bot, &state, at(12, 0)).await;
+    nightmode::tick(&bot, &state, at(21, 0)).await;
+    nightmode::tick(&bot, &state, at(21, 1)).await;
+    nightmode::tick(&bot, &state, at(5, 0)).await;
+
+    let sets = api.calls_to("setChatPermissions");
+    assert_eq!(sets.len(), 3, "{sets:?}");
+    assert_eq!(sets[0]["use_independent_chat_permissions"], true);
+    assert_eq!((sets[0]["permissions"]["can_send_photos"].as_bool(), sets[0]["permissions"]["can_send_polls"].as_bool()), (Some(true), Some(false)));
+    // Media off on top of the defaults, then the defaults back
+    assert_eq!((sets[1]["permissions"]["can_send_photos"].as_bool(), sets[1]["permissions"]["can_send_messages"].as_bool()), (Some(false), Some(true)));
+    assert_eq!(sets[2]["permissions"], sets[0]["permissions"]);
+
+    let texts = api.sent_texts();
+    assert_eq!(texts[0], "✅ Members may send: text, photos, links.");
+    assert_eq!(texts[1], "✅ Night mode: media is locked 22:00–06:00 UTC+01:00 every night.");
+    assert_eq!(texts[2], "🌙 Night mode — media is off until 06:00 UTC+01:00.");
+    assert!(texts[3].starts_with("☀️"), "{texts:?}");
+    assert!(!state.lock().await.store.chat_settings(GROUP).night_active);
+}
+
+#[tokio::test]
+async fn turning_it_off_at_night_unlocks_right_away() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/nightmode 0:00-23:59").into_update()).await;
+    nightmode::tick(&bot, &state, at(12, 0)).await;
+    assert_eq!(api.calls_to("setChatPermissions")[0]["permissions"]["can_send_messages"], false);
+
+    // Defaults changed at night wait for the morning
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setperms all").into_update()).await;
+    assert_eq!(api.calls_to("setChatPermissions").len(), 1);
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/nightmode off").into_update()).await;
+
+    let sets = api.calls_to("setChatPermissions");
+    assert_eq!(sets.len(), 2);
+    assert_eq!(sets[1]["permissions"]["can_send_messages"], true);
+    let texts = api.sent_texts();
+    assert!(texts[2].contains("once night mode ends"), "{texts:?}");
+    assert_eq!(texts.last().unwrap(), "✅ Night mode is off.");
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{callback, FakeMessage, FakeUser};
+use multipurpose_bot::state::Action;
+
+#[tokio::test]
+async fn listed_rights_and_title_are_granted() {
+    let (api, bot, state) = setup().await;
+    let owner = FakeUser::new(1, "Owner");
+    api.set_admin(GROUP, owner.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &owner, "/promote 2 Night Shift del pin").into_update()).await;
+
+    let promotes = api.calls_to("promoteChatMember");
+    assert_eq!(promotes.len(), 1);
+    let p = &promotes[0];
+    assert_eq!((p["can_manage_chat"].as_bool(), p["can_delete_messages"].as_bool(), p["can_pin_messages"].as_bool()), (Some(true), Some(true), Some(true)));
+    assert_eq!((p["can_restrict_members"].as_bool(), p["can_promote_members"].as_bool()), (Some(false), Some(false)));
+    assert_eq!(api.calls_to("setChatAdministratorCustomTitle")[0]["custom_title"], "Night Shift");
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("Rights: delete, pin\nTitle: <i>Night Shift</i>"), "{texts:?}");
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    assert_eq!((log[0].action, log[0].target_id, log[0].reason.as_str()), (Action::Promote, Some(2), "Rights: delete, pin"));
+}
+
+#[tokio::test]
+async fn rights_the_caller_lacks_are_refused() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    let mod_  = FakeUser::new(3, "Mod");
+    api.set_admin(GROUP, admin.id);
+    api.set_rights(GROUP, admin.id, &["can_promote_members", "can_delete_messages"]);
+    api.set_admin(GROUP, mod_.id);
+    api.set_rights(GROUP, mod_.id, &["can_delete_messages"]);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/promote 2 delete pin").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &mod_, "/promote 2 delete").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/promote 2 Seventeen characters").into_update()).await;
+
+    assert!(api.calls_to("promoteChatMember").is_empty());
+    let texts = api.sent_texts();
+    assert_eq!(texts[0], "❌ You can't grant rights you don't have: pin.");
+    assert!(texts[1].contains("need the right to add new admins"), "{texts:?}");
+    assert!(texts[2].contains("at most 16 characters"), "{texts:?}");
+}
+
+#[tokio::test]
+async fn checklist_offers_only_held_rights_and_only_its_admin_may_press() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    let other = FakeUser::new(3, "Other");
+    api.set_admin(GROUP, admin.id);
+    api.set_admin(GROUP, other.id);
+    api.set_rights(GROUP, admin.id, &["can_promote_members", "can_delete_messages"]);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/promote 2").into_update()).await;
+    let calls = api.take_calls();
+    let sent = calls.iter().find(|c| c.method == "sendMessage").unwrap();
+    let keyboard = &sent.params["reply_markup"]["inline_keyboard"];
+    assert_eq!(keyboard[0][0]["text"], "✅ Delete messages");
+    assert_eq!(keyboard[0][1]["text"], "▫️ Add admins");
+    assert_eq!(keyboard.as_array().unwrap().len(), 2);
+    let checklist = sent.result["message_id"].as_i64().unwrap();
+
+    send(&bot, &state, callback(GROUP, &other, checklist, "pro:ok")).await;
+    assert_eq!(api.calls_to("answerCallbackQuery")[0]["text"], "Only the admin who started this can use it.");
+    send(&bot, &state, callback(GROUP, &admin, checklist, "pro:pin")).await;
+    send(&bot, &state, callback(GROUP, &admin, checklist, "pro:promote")).await;
+    send(&bot, &state, callback(GROUP, &admin, checklist, "pro:ok")).await;
+
+    let promotes = api.calls_to("promoteChatMember");
+    assert_eq!(promotes.len(), 1);
+    assert_eq!((promotes[0]["can_delete_messages"].as_bool(), promotes[0]["can_promote_members"].as_bool()), (Some(true), Some(true)));
+    assert_eq!(promotes[0]["can_pin_messages"], false);
+    let edits = api.calls_to("editMessageText");
+    assert!(edits.last().unwrap()["text"].as_str().unwrap().contains("Rights: delete, promote"), "{edits:?}");
+    assert!(state.lock().await.promotions.is_empty());
+}
+
+#[tokio::test]
+async fn demote_needs_the_right_to_add_admins() {
+    let (api, bot, state) = setup().await;
+    let owner     = FakeUser::new(1, "Owner");
+    let rightless = FakeUser::new(3, "Rightless");
+    let promoter  = FakeUser::new(4, "Promoter");
+    api.set_owner(GROUP, owner.id);
+    api.set_admin(GROUP, rightless.id);
+    api.set_rights(GROUP, rightless.id, &[]);
+    api.set_admin(GROUP, promoter.id);
+    api.set_rights(GROUP, promoter.id, &["can_promote_members"]);
+    api.set_admin(GROUP, 2);
+
+    for (sender, cmd) in [(&rightless, "/demote 2"), (&promoter, "/demote 4"), (&promoter, "/demote 1"), (&promoter, "/demote 2")] {
+        send(&bot, &state, FakeMessage::new(GROUP, sender, cmd).into_update()).await;
+    }
+
+    let demotes = api.calls_to("promoteChatMember");
+    assert_eq!(demotes.len(), 1, "{demotes:?}");
+    assert_eq!(demotes[0]["user_id"], 2);
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("need the right to add new admins to demote"), "{texts:?}");
+    assert_eq!(texts[1], "❌ You can't demote yourself.");
+    assert_eq!(texts[2], "❌ The chat's owner can't be demoted.");
+    assert!(texts[3].contains("demoted"), "{texts:?}");
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{callback, FakeMessage, FakeUser};
+use multipurpose_bot::state::Action;
+
+#[tokio::test]
+async fn report_pings_admins_and_ban_button_bans() {
+    let (api, bot, state) = setup().await;
+    let admin    = FakeUser::new(1, "Admin");
+    let dm_admin = FakeUser::new(2, "Night Owl");
+    let spammer  = FakeUser::new(3, "Spammer");
+    let member   = FakeUser::new(4, "Member");
+    api.set_admin(GROUP, admin.id);
+    api.set_admin(GROUP, dm_admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &dm_admin, "/reports dm on").into_update()).await;
+    api.take_calls();
+    let spam = FakeMessage::new(GROUP, &spammer, "buy followers");
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/report selling stuff").reply_to(&spam).into_update()).await;
+
+    let sent = api.calls_to("sendMessage");
+    assert_eq!(sent.len(), 2, "{sent:?}");
+    assert_eq!(sent[0]["chat_id"], dm_admin.id);
+    assert_eq!(sent[1]["chat_id"], GROUP);
+    let notice = sent[1]["text"].as_str().unwrap();
+    assert!(notice.contains("<b>Reason:</b> selling stuff"), "{notice}");
+    assert!(notice.contains("tg://user?id=1\">\u{200b}</a>"), "{notice}");
+    assert!(!notice.contains("tg://user?id=2\">\u{200b}"), "{notice}");
+    let buttons = &sent[1]["reply_markup"]["inline_keyboard"];
+    assert_eq!(buttons[0][0]["callback_data"], format!("rep:{GROUP}:{}:ban", spam.id()));
+
+    // Pressed from the DM, so the chat comes from the callback data
+    send(&bot, &state, callback(dm_admin.id, &dm_admin, 99, &format!("rep:{GROUP}:{}:ban", spam.id()))).await;
+    let bans = api.calls_to("banChatMember");
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0]["user_id"], spammer.id);
+    let edits = api.calls_to("editMessageText");
+    assert_eq!(edits.len(), 2);
+    assert!(edits.iter().all(|e| e["text"].as_str().unwrap().contains("Spammer banned by")), "{edits:?}");
+
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    assert_eq!((log[0].action, log[0].actor_id, log[0].message_id), (Action::Ban, dm_admin.id, spam.id()));
+    assert_eq!(log[0].reason, "Reported: selling stuff");
+    assert!(state.lock().await.reports.is_empty());
+}
+
+#[tokio::test]
+async fn only_admins_can_press_and_at_admin_reports_once() {
+    let (api, bot, state) = setup().await;
+    let admin   = FakeUser::new(1, "Admin");
+    let spammer = FakeUser::new(3, "Spammer");
+    let member  = FakeUser::new(4, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    let spam = FakeMessage::new(GROUP, &spammer, "rude words");
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "@Admin, look").reply_to(&spam).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/report").reply_to(&spam).into_update()).await;
+    let texts = api.sent_texts();
+    assert!(texts[0].starts_with("🚨"), "{texts:?}");
+    assert!(texts[1].contains("already been reported"), "{texts:?}");
+
+    let data = format!("rep:{GROUP}:{}:warn", spam.id());
+    send(&bot, &state, callback(GROUP, &member, 50, &data)).await;
+    let answers = api.calls_to("answerCallbackQuery");
+    assert_eq!(answers[0]["text"], "Only admins can act on reports.");
+    assert!(state.lock().await.store.warnings(GROUP, spammer.id).is_empty());
+
+    send(&bot, &state, callback(GROUP, &admin, 50, &data)).await;
+    assert_eq!(state.lock().await.store.warnings(GROUP, spammer.id)[0].issued_by, admin.id);
+    send(&bot, &state, callback(GROUP, &admin, 50, &data)).await;
+    assert_eq!(state.lock().await.store.warnings(GROUP, spammer.id).len(), 1);
+}
+
+#[tokio::test]
+async fn refused_reports() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(4, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    let from_admin = FakeMessage::new(GROUP, &admin, "read the rules");
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/report").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/report").reply_to(&from_admin).into_update()).await;
+    // A quiet @admin without a reply, and after reports are turned off
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "@admin help").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/reports off").into_update()).await;
+    let other = FakeMessage::new(GROUP, &FakeUser::new(5, "Other"), "hi");
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "@admin").reply_to(&other).into_update()).await;
+
+    let texts = api.sent_texts();
+    assert_eq!(texts.len(), 3, "{texts:?}");
+    assert!(texts[0].contains("Reply to the message"));
+    assert!(texts[1].contains("Admins can't be reported"));
+}
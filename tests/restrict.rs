@@ -0,0 +1,97 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{callback, FakeMessage, FakeUser};
+use multipurpose_bot::state::Action;
+
+#[tokio::test]
+async fn restrict_takes_away_only_the_listed_permissions() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+    let spam = FakeMessage::new(GROUP, &member, "look at this");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/restrict media 2h").reply_to(&spam).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/restrict stickers").reply_to(&spam).into_update()).await;
+    let restricts = api.calls_to("restrictChatMember");
+    let perms = &restricts[1]["permissions"];
+    assert_eq!(restricts[0]["use_independent_chat_permissions"], true);
+    assert!(restricts[0]["until_date"].is_i64());
+    assert_eq!((perms["can_send_messages"].as_bool(), perms["can_send_photos"].as_bool()), (Some(true), Some(false)));
+    assert_eq!(perms["can_send_other_messages"], false);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/perms").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/unrestrict photos stickers").reply_to(&spam).into_update()).await;
+    let perms = &api.calls_to("restrictChatMember")[2]["permissions"];
+    assert_eq!((perms["can_send_photos"].as_bool(), perms["can_send_videos"].as_bool()), (Some(true), Some(false)));
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/unrestrict").reply_to(&spam).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/perms").into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].contains("Can't send: photos, videos, audio, voice, videonotes, documents\n⏰ Until"), "{texts:?}");
+    assert!(texts[2].contains("🚫 Photos\n🚫 Videos") && texts[2].contains("✅ Text"), "{texts:?}");
+    assert!(texts[2].contains("🚫 Stickers & GIFs\n✅ Polls"), "{texts:?}");
+    assert!(texts[4].contains("can send everything again"), "{texts:?}");
+    assert!(texts[5].contains("no restrictions of their own"), "{texts:?}");
+
+    let log = state.lock().await.store.audit_log(GROUP, 0);
+    let actions: Vec<Action> = log.iter().map(|e| e.action).collect();
+    assert!(actions.contains(&Action::Restrict) && actions.contains(&Action::Unrestrict), "{actions:?}");
+}
+
+#[tokio::test]
+async fn toggles_keyboard_applies_what_was_ticked() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let other  = FakeUser::new(3, "Other");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+    api.set_admin(GROUP, other.id);
+    let said = FakeMessage::new(GROUP, &member, "hi");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/restrict").reply_to(&said).into_update()).await;
+    let sent = api.calls_to("sendMessage");
+    let keyboard = &sent[0]["reply_markup"]["inline_keyboard"];
+    assert_eq!(keyboard[0][0]["callback_data"], "rst:text");
+    let msg_id = api.calls().iter().rev().find(|c| c.method == "sendMessage").unwrap().result["message_id"].as_i64().unwrap();
+
+    send(&bot, &state, callback(GROUP, &other, msg_id, "rst:links")).await;
+    assert_eq!(api.calls_to("answerCallbackQuery")[0]["text"], "Only the admin who started this can use it.");
+    send(&bot, &state, callback(GROUP, &admin, msg_id, "rst:links")).await;
+    send(&bot, &state, callback(GROUP, &admin, msg_id, "rst:polls")).await;
+    send(&bot, &state, callback(GROUP, &admin, msg_id, "rst:polls")).await;
+    send(&bot, &state, callback(GROUP, &admin, msg_id, "rst:ok")).await;
+
+    let restricts = api.calls_to("restrictChatMember");
+    assert_eq!(restricts.len(), 1);
+    let perms = &restricts[0]["permissions"];
+    assert_eq!((perms["can_add_web_page_previews"].as_bool(), perms["can_send_polls"].as_bool()), (Some(false), Some(true)));
+    let edits = api.calls_to("editMessageText");
+    assert!(edits.last().unwrap()["text"].as_str().unwrap().contains("Can't send: links"), "{edits:?}");
+    assert!(state.lock().await.restrictions.is_empty());
+}
+
+#[tokio::test]
+async fn refused_restrictions() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(2, "Member");
+    api.set_admin(GROUP, admin.id);
+    let said = FakeMessage::new(GROUP, &member, "hi");
+    let from_admin = FakeMessage::new(GROUP, &admin, "rules");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/restrict photoz").reply_to(&said).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/restrict media 400d").reply_to(&said).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/restrict media").reply_to(&from_admin).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/unrestrict").reply_to(&said).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &member, "/perms").reply_to(&from_admin).into_update()).await;
+
+    let texts = api.sent_texts();
+    assert!(texts[0].starts_with("❌ Unknown permission: photoz."), "{texts:?}");
+    assert!(texts[1].contains("between 30 seconds and 366 days"), "{texts:?}");
+    assert!(texts[2].contains("another admin"), "{texts:?}");
+    assert_eq!(texts[3], "✅ Member isn't restricted.");
+    assert!(texts[4].contains("is an admin"), "{texts:?}");
+    assert!(api.calls_to("restrictChatMember").is_empty());
+}
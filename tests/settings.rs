@@ -0,0 +1,64 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::i18n::Language;
+use multipurpose_bot::mock_api::{callback, FakeMessage, FakeUser};
+
+#[tokio::test]
+async fn admins_adjust_settings_from_the_menu() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/settings").into_update()).await;
+    let menu = api.take_calls().iter().find_map(|c| c.result["message_id"].as_i64()).expect("menu sent");
+
+    send(&bot, &state, callback(GROUP, &admin, menu, "set:warn:+")).await;
+    send(&bot, &state, callback(GROUP, &admin, menu, "set:guess:-")).await;
+    send(&bot, &state, callback(GROUP, &admin, menu, "set:lang")).await;
+
+    let settings = state.lock().await.store.chat_settings(GROUP);
+    assert_eq!(settings.warn_limit, 4);
+    assert_eq!(settings.guess_attempts, 6);
+    assert_eq!(settings.language, Language::Es);
+    let menu_text = api.calls_to("editMessageText").last().unwrap()["text"].as_str().unwrap().to_string();
+    assert!(menu_text.contains("Warn limit: <b>4</b>") && menu_text.contains("Game language: <b>🇪🇸 Español</b>"), "{menu_text}");
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/guess").into_update()).await;
+    assert!(api.sent_texts().last().unwrap().contains("Tienes *6 intentos*"));
+}
+
+#[tokio::test]
+async fn members_cannot_press_settings_buttons() {
+    let (api, bot, state) = setup().await;
+    let (admin, bob) = (FakeUser::new(1, "Admin"), FakeUser::new(2, "Bob"));
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, callback(GROUP, &bob, 99, "set:warn:-")).await;
+
+    assert_eq!(state.lock().await.store.chat_settings(GROUP).warn_limit, 3);
+    assert_eq!(api.calls_to("answerCallbackQuery")[0]["show_alert"], true);
+    assert!(api.calls_to("editMessageText").is_empty());
+}
+
+#[tokio::test]
+async fn admin_only_notes_refuse_members_before_anything_else() {
+    let (api, bot, state) = setup().await;
+    let (admin, bob) = (FakeUser::new(1, "Admin"), FakeUser::new(2, "Bob"));
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/save rules No spam").into_update()).await;
+    api.take_calls();
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/settings").into_update()).await;
+    let menu = api.take_calls().iter().find_map(|c| c.result["message_id"].as_i64()).expect("menu sent");
+    send(&bot, &state, callback(GROUP, &admin, menu, "set:notes")).await;
+    api.take_calls();
+    for cmd in ["/save", "/delnote", "/delnote rules"] {
+        send(&bot, &state, FakeMessage::new(GROUP, &bob, cmd).into_update()).await;
+    }
+
+    let texts = api.sent_texts();
+    assert_eq!(texts.len(), 3, "{texts:?}");
+    assert!(texts.iter().all(|t| t == "❌ Only admins can change notes in this chat."), "{texts:?}");
+    assert!(state.lock().await.store.note(GROUP, "rules").is_some());
+}
@@ -0,0 +1,221 @@
+use multipurpose_bot::state::{Action, Approval, AuditEntry, BlockedTrigger, BlocklistAction, ChatSettings, FedBan, Federation, PendingCaptcha, Warning};
+use multipurpose_bot::storage::{JsonStorage, MemoryStorage, SqliteStorage, Storage};
+
+fn warning(reason: &str, issued_at: i64) -> Warning {
+    Warning { reason: reason.into(), issued_by: 1, issued_by_name: "Admin".into(), issued_at }
+}
+
+/// Same checks against every backend
+fn warnings_round_trip(store: &mut dyn Storage) {
+    assert_eq!(store.add_warning(-100, 2, &warning("old", 1_000)), 1);
+    assert_eq!(store.add_warning(-100, 2, &warning("new", 5_000)), 2);
+    assert_eq!(store.warnings(-100, 2)[1], warning("new", 5_000));
+
+    assert_eq!(store.expire_warnings(-100, 2_000), 1);
+    assert_eq!(store.warnings(-100, 2), vec![warning("new", 5_000)]);
+}
+
+fn usernames_round_trip(store: &mut dyn Storage) {
+    assert!(store.remember_username("Alice", 7));
+    assert!(!store.remember_username("alice", 7));
+    assert_eq!(store.user_id_by_username("ALICE"), Some(7));
+
+    assert!(store.remember_username("alice", 8));
+    assert_eq!(store.user_id_by_username("alice"), Some(8));
+    assert_eq!(store.user_id_by_username("bob"), None);
+}
+
+fn settings_round_trip(store: &mut dyn Storage) {
+    let settings = ChatSettings { warn_limit: 5, ..ChatSettings::default() };
+    store.save_chat_settings(-300, &settings);
+    store.save_chat_settings(-100, &ChatSettings::default());
+    store.save_chat_settings(-300, &settings);
+
+    assert_eq!(store.chat_settings(-300).warn_limit, 5);
+    assert_eq!(store.configured_chats(), vec![-300, -100]);
+}
+
+fn audit(action: Action, at: i64) -> AuditEntry {
+    AuditEntry {
+        action,
+        actor_id: 1,
+        actor_name: "Admin".into(),
+        target_id: (action != Action::Pin).then_some(2),
+        target_name: "Spammer".into(),
+        reason: "spam".into(),
+        duration: (action == Action::Mute).then_some(3600),
+        message_id: 10,
+        at,
+    }
+}
+
+fn audit_round_trip(store: &mut dyn Storage) {
+    store.append_audit(-100, &audit(Action::Warn, 1_000));
+    store.append_audit(-100, &audit(Action::Mute, 2_000));
+    store.append_audit(-100, &audit(Action::Pin, 3_000));
+    store.append_audit(-200, &audit(Action::Ban, 3_000));
+
+    assert_eq!(store.audit_log(-100, 0).len(), 3);
+    assert_eq!(store.audit_log(-100, 2_000), vec![audit(Action::Mute, 2_000), audit(Action::Pin, 3_000)]);
+}
+
+fn blocked(trigger: &str, action: Option<BlocklistAction>) -> BlockedTrigger {
+    BlockedTrigger { trigger: trigger.into(), action }
+}
+
+fn blocklist_round_trip(store: &mut dyn Storage) {
+    store.add_blocked(-100, &blocked("spam", None));
+    store.add_blocked(-100, &blocked("/t\\.me/", Some(BlocklistAction::Ban)));
+    store.add_blocked(-100, &blocked("spam", Some(BlocklistAction::Warn)));
+    store.add_blocked(-200, &blocked("other", None));
+
+    assert_eq!(store.blocklist(-100), vec![blocked("/t\\.me/", Some(BlocklistAction::Ban)), blocked("spam", Some(BlocklistAction::Warn))]);
+    assert!(store.remove_blocked(-100, "spam"));
+    assert!(!store.remove_blocked(-100, "spam"));
+    assert_eq!(store.blocklist(-100).len(), 1);
+}
+
+fn fed_ban(user_id: i64, at: i64) -> FedBan {
+    FedBan { user_id, user_name: "Spammer".into(), reason: "spam".into(), banned_by: 1, at }
+}
+
+fn federations_round_trip(store: &mut dyn Storage) {
+    let mut fed = Federation { id: "f1".into(), name: "Network".into(), owner_id: 1, admins: Default::default() };
+    store.save_federation(&fed);
+    fed.admins.insert(5);
+    store.save_federation(&fed);
+    store.set_chat_federation(-300, Some("f1"));
+    store.set_chat_federation(-100, Some("f1"));
+    store.set_chat_federation(-200, Some("f2"));
+    store.set_chat_federation(-200, None);
+
+    assert_eq!(store.federation("f1"), Some(fed.clone()));
+    assert_eq!(store.owned_federation(1), Some(fed));
+    assert_eq!(store.owned_federation(5), None);
+    assert_eq!(store.chat_federation(-100).as_deref(), Some("f1"));
+    assert_eq!(store.chat_federation(-200), None);
+    assert_eq!(store.federation_chats("f1"), vec![-300, -100]);
+
+    store.add_fed_ban("f1", &fed_ban(7, 1_000));
+    store.add_fed_ban("f1", &fed_ban(8, 2_000));
+    store.add_fed_ban("f1", &fed_ban(7, 3_000));
+    assert_eq!(store.fed_bans("f1"), vec![fed_ban(8, 2_000), fed_ban(7, 3_000)]);
+    assert_eq!(store.fed_ban("f1", 7), Some(fed_ban(7, 3_000)));
+    assert!(store.remove_fed_ban("f1", 7));
+    assert!(!store.remove_fed_ban("f1", 7));
+    assert_eq!(store.fed_ban("f2", 8), None);
+}
+
+fn approval(user_id: i64, at: i64) -> Approval {
+    Approval { user_id, user_name: "Regular".into(), approved_by: 1, at }
+}
+
+fn approvals_round_trip(store: &mut dyn Storage) {
+    store.approve(-100, &approval(7, 1_000));
+    store.approve(-100, &approval(8, 2_000));
+    store.approve(-100, &approval(7, 3_000));
+    store.approve(-200, &approval(9, 1_000));
+    assert_eq!(store.approvals(-100), vec![approval(8, 2_000), approval(7, 3_000)]);
+    assert!(store.is_approved(-100, 7));
+    assert!(!store.is_approved(-100, 9));
+
+    assert!(store.unapprove(-100, 7));
+    assert!(!store.unapprove(-100, 7));
+    assert_eq!(store.unapprove_all(-200), 1);
+    assert_eq!(store.unapprove_all(-200), 0);
+    assert!(store.approvals(-200).is_empty());
+}
+
+fn captcha(user_id: i64, deadline: i64) -> PendingCaptcha {
+    PendingCaptcha { user_id, answer: "ok".into(), message_id: 40 + user_id, tries_left: 3, deadline }
+}
+
+fn captchas_round_trip(store: &mut dyn Storage) {
+    store.save_captcha(-100, &captcha(7, 3_000));
+    store.save_captcha(-200, &captcha(7, 1_000));
+    store.save_captcha(-100, &captcha(8, 2_000));
+    store.save_captcha(-100, &PendingCaptcha { tries_left: 1, ..captcha(8, 2_000) });
+    assert_eq!(store.captcha(-100, 8).unwrap().tries_left, 1);
+    assert_eq!(store.captcha(-200, 8), None);
+
+    let expired = store.expired_captchas(2_000);
+    assert_eq!(expired.iter().map(|(c, p)| (*c, p.user_id)).collect::<Vec<_>>(), vec![(-200, 7), (-100, 8)]);
+
+    assert_eq!(store.delete_captcha(-200, 7), Some(captcha(7, 1_000)));
+    assert_eq!(store.delete_captcha(-200, 7), None);
+    assert_eq!(store.expired_captchas(1_500), vec![]);
+}
+
+#[test]
+fn memory_backend() {
+    warnings_round_trip(&mut MemoryStorage::default());
+    usernames_round_trip(&mut MemoryStorage::default());
+    audit_round_trip(&mut MemoryStorage::default());
+    blocklist_round_trip(&mut MemoryStorage::default());
+    settings_round_trip(&mut MemoryStorage::default());
+    federations_round_trip(&mut MemoryStorage::default());
+    approvals_round_trip(&mut MemoryStorage::default());
+    captchas_round_trip(&mut MemoryStorage::default());
+}
+
+#[test]
+fn sqlite_backend() {
+    warnings_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    usernames_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    audit_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    blocklist_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    settings_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    federations_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    approvals_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+    captchas_round_trip(&mut SqliteStorage::open(":memory:").unwrap());
+}
+
+#[test]
+fn json_backend_survives_reopen() {
+    let path = std::env::temp_dir().join(format!("bot-test-{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    warnings_round_trip(&mut JsonStorage::open(path).unwrap());
+    audit_round_trip(&mut JsonStorage::open(path).unwrap());
+    blocklist_round_trip(&mut JsonStorage::open(path).unwrap());
+    federations_round_trip(&mut JsonStorage::open(path).unwrap());
+    approvals_round_trip(&mut JsonStorage::open(path).unwrap());
+    captchas_round_trip(&mut JsonStorage::open(path).unwrap());
+
+    let reopened = JsonStorage::open(path).unwrap();
+    assert_eq!(reopened.warnings(-100, 2), vec![warning("new", 5_000)]);
+    assert_eq!(reopened.audit_log(-100, 0).len(), 3);
+    assert_eq!(reopened.blocklist(-100).len(), 1);
+    assert_eq!(reopened.fed_bans("f1"), vec![fed_ban(8, 2_000)]);
+    assert_eq!(reopened.approvals(-100), vec![approval(8, 2_000)]);
+    assert_eq!(reopened.captcha(-100, 7), Some(captcha(7, 3_000)));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn json_reads_old_reason_only_warnings() {
+    let path = std::env::temp_dir().join(format!("bot-test-legacy-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{ "warnings": { "-100": { "2": ["spam"] } } }"#).unwrap();
+
+    let store = JsonStorage::open(path.to_str().unwrap()).unwrap();
+    assert_eq!(store.warnings(-100, 2)[0].reason, "spam");
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn sqlite_keeps_unreadable_settings_instead_of_overwriting_them() {
+    let path = std::env::temp_dir().join(format!("bot-test-settings-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let path = path.to_str().unwrap();
+    let bad = r#"{ "warn_limit": "five" }"#;
+    SqliteStorage::open(path).unwrap().save_chat_settings(-100, &ChatSettings::default());
+    rusqlite::Connection::open(path).unwrap()
+        .execute("UPDATE chat_settings SET data = ?1 WHERE chat_id = -100", [bad]).unwrap();
+
+    let mut store = SqliteStorage::open(path).unwrap();
+    assert_eq!(store.chat_settings(-100).warn_limit, ChatSettings::default().warn_limit);
+    store.save_chat_settings(-100, &ChatSettings { warn_limit: 7, ..ChatSettings::default() });
+    let data: String = rusqlite::Connection::open(path).unwrap()
+        .query_row("SELECT data FROM chat_settings WHERE chat_id = -100", [], |r| r.get(0)).unwrap();
+    assert_eq!(data, bad);
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::{send, setup, GROUP};
+use multipurpose_bot::mock_api::{FakeMessage, FakeUser};
+
+#[tokio::test]
+async fn default_welcome_once_switched_on() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let newbie = FakeUser::new(5, "Newbie");
+    api.set_admin(GROUP, admin.id);
+
+    // Off until an admin opts in
+    send(&bot, &state, FakeMessage::join(GROUP, &newbie).into_update()).await;
+    assert!(api.sent_texts().is_empty());
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/welcome on").into_update()).await;
+    api.take_calls();
+    send(&bot, &state, FakeMessage::join(GROUP, &newbie).into_update()).await;
+    send(&bot, &state, FakeMessage::leave(GROUP, &newbie).into_update()).await;
+
+    let texts = api.sent_texts();
+    assert_eq!(texts, vec![r#"Hey <a href="tg://user?id=5">Newbie</a>, welcome to <b>Mock Group</b>! 👋"#]);
+}
+
+#[tokio::test]
+async fn custom_welcome_with_buttons_replaces_the_last_one() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    let template = "/setwelcome Hi {first}, you're member #{count}!\n\
+                    [Rules](buttonurl://t.me/rules)[Site](buttonurl://https://example.com:same)\n\
+                    [Help](buttonurl://t.me/help)";
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, template).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/cleanwelcome on").into_update()).await;
+    api.take_calls();
+
+    send(&bot, &state, FakeMessage::join(GROUP, &FakeUser::new(5, "A & B")).into_update()).await;
+    let first = api.take_calls().into_iter().find(|c| c.method == "sendMessage").unwrap();
+    assert_eq!(first.params["text"], "Hi A &amp; B, you're member #42!");
+    let rows = &first.params["reply_markup"]["inline_keyboard"];
+    assert_eq!(rows[0][0]["url"], "https://t.me/rules");
+    assert_eq!(rows[0][1]["url"], "https://example.com");
+    assert_eq!(rows[1][0]["text"], "Help");
+
+    send(&bot, &state, FakeMessage::join(GROUP, &FakeUser::new(6, "C")).into_update()).await;
+    let deleted = api.calls_to("deleteMessage");
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0]["message_id"], first.result["message_id"]);
+}
+
+#[tokio::test]
+async fn placeholders_in_names_are_not_expanded() {
+    let (api, bot, state) = setup().await;
+    let admin = FakeUser::new(1, "Admin");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setwelcome {first} joined {chatname} ({id}) {unknown}").into_update()).await;
+    api.take_calls();
+    send(&bot, &state, FakeMessage::join(GROUP, &FakeUser::new(5, "{chatname}{id}")).into_update()).await;
+
+    assert_eq!(api.sent_texts(), vec!["{chatname}{id} joined Mock Group (5) {unknown}"]);
+}
+
+#[tokio::test]
+async fn goodbye_and_switching_off() {
+    let (api, bot, state) = setup().await;
+    let admin  = FakeUser::new(1, "Admin");
+    let member = FakeUser::new(5, "Member");
+    api.set_admin(GROUP, admin.id);
+
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/setgoodbye Bye {first}, {chatname} will miss you").into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/welcome on").into_update()).await;
+    send(&bot, &state, FakeMessage::leave(GROUP, &member).into_update()).await;
+    send(&bot, &state, FakeMessage::new(GROUP, &admin, "/welcome off").into_update()).await;
+    send(&bot, &state, FakeMessage::join(GROUP, &member).into_update()).await;
+    send(&bot, &state, FakeMessage::leave(GROUP, &member).into_update()).await;
+
+    let texts = api.sent_texts();
+    assert_eq!(texts.len(), 4, "{texts:?}");
+    assert!(texts[0].ends_with("Turn greetings on with /welcome on."), "{texts:?}");
+    assert_eq!(texts[2], "Bye Member, Mock Group will miss you");
+}